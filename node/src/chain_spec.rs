@@ -1,6 +1,6 @@
 use node_template_runtime::{
 	AccountId, AuraConfig, BalancesConfig, GenesisConfig, GrandpaConfig, Signature, SudoConfig,
-	SystemConfig, WASM_BINARY, SubstrateKittiesConfig
+	SystemConfig, WASM_BINARY, SubstrateKittiesConfig, OcwDemoConfig
 };
 use sc_service::ChainType;
 use sp_consensus_aura::sr25519::AuthorityId as AuraId;
@@ -157,5 +157,15 @@ fn testnet_genesis(
 		substrate_kitties: SubstrateKittiesConfig {
 			kitties: vec![],
 		},
+		ocw_demo: OcwDemoConfig {
+			// DOT/USD price endpoints with the JSON path of their price field
+			price_sources: vec![
+				(b"https://api.coincap.io/v2/assets/polkadot".to_vec(), b"data.priceUsd".to_vec()),
+				(b"https://min-api.cryptocompare.com/data/price?fsym=DOT&tsyms=USD".to_vec(), b"USD".to_vec()),
+				(b"https://api.binance.com/api/v3/ticker/price?symbol=DOTUSDT".to_vec(), b"price".to_vec()),
+			],
+			// The `demo` key inserted into the keystore of the dev node
+			authorities: vec![get_account_id_from_seed::<sr25519::Public>("Alice")],
+		},
 	}
 }
@@ -0,0 +1,29 @@
+//! Runtime API definition for the kitties pallet.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+// The `too_many_arguments` warning originates from `decl_runtime_apis` macro.
+#![allow(clippy::too_many_arguments)]
+// The `unnecessary_mut_passed` warning originates from `decl_runtime_apis` macro.
+#![allow(clippy::unnecessary_mut_passed)]
+
+use codec::Codec;
+use sp_std::vec::Vec;
+
+pub use pallet_kitties::{genetics::KittyTraits, Gender, KittyInfo};
+
+sp_api::decl_runtime_apis! {
+	pub trait KittiesApi<KittyIndex, AccountId, Balance> where
+		KittyIndex: Codec,
+		AccountId: Codec,
+		Balance: Codec,
+	{
+		/// Get a kitty by its id.
+		fn kitty(kitty_id: KittyIndex) -> Option<KittyInfo<KittyIndex, AccountId, Balance>>;
+		/// Get all kitties owned by `owner`.
+		fn kitties_owned(owner: AccountId) -> Vec<KittyInfo<KittyIndex, AccountId, Balance>>;
+		/// Get a page of kitties that currently have an ask price.
+		fn kitties_for_sale(offset: u32, limit: u32) -> Vec<KittyInfo<KittyIndex, AccountId, Balance>>;
+		/// Get the known ancestors of a kitty up to `depth` levels, with their level.
+		fn ancestry(kitty_id: KittyIndex, depth: u32) -> Vec<(u32, KittyIndex)>;
+	}
+}
@@ -0,0 +1,145 @@
+//! RPC interface for the kitties pallet.
+//!
+//! Exposes the `kitties_*` namespace so that clients can read decoded kitties instead of
+//! decoding the raw `Kitties` / `KittiesOwned` storage keys by hand.
+
+use std::sync::Arc;
+
+use codec::Codec;
+use jsonrpc_core::{Error as RpcError, ErrorCode, Result};
+use jsonrpc_derive::rpc;
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_runtime::{generic::BlockId, traits::Block as BlockT};
+
+pub use pallet_kitties_rpc_runtime_api::{KittiesApi as KittiesRuntimeApi, KittyInfo};
+
+#[rpc]
+pub trait KittiesApi<BlockHash, KittyIndex, AccountId, ResponseType> {
+	/// Get a kitty by its id, or `null` if it does not exist.
+	#[rpc(name = "kitties_getKitty")]
+	fn get_kitty(&self, kitty_id: KittyIndex, at: Option<BlockHash>) -> Result<Option<ResponseType>>;
+
+	/// Get all kitties owned by `owner`.
+	#[rpc(name = "kitties_getOwned")]
+	fn get_owned(&self, owner: AccountId, at: Option<BlockHash>) -> Result<Vec<ResponseType>>;
+
+	/// Get at most `limit` kitties that are for sale, skipping the first `offset` ones.
+	#[rpc(name = "kitties_listForSale")]
+	fn list_for_sale(
+		&self,
+		offset: u32,
+		limit: u32,
+		at: Option<BlockHash>,
+	) -> Result<Vec<ResponseType>>;
+
+	/// Get the known ancestors of a kitty up to `depth` levels, each with its level: 1 for the
+	/// parents, 2 for the grandparents and so on.
+	#[rpc(name = "kitties_getAncestry")]
+	fn get_ancestry(
+		&self,
+		kitty_id: KittyIndex,
+		depth: u32,
+		at: Option<BlockHash>,
+	) -> Result<Vec<(u32, KittyIndex)>>;
+}
+
+/// A struct that implements the [`KittiesApi`].
+pub struct Kitties<C, P> {
+	client: Arc<C>,
+	_marker: std::marker::PhantomData<P>,
+}
+
+impl<C, P> Kitties<C, P> {
+	/// Create new `Kitties` with the given reference to the client.
+	pub fn new(client: Arc<C>) -> Self {
+		Self { client, _marker: Default::default() }
+	}
+}
+
+/// Error type of this RPC api.
+pub enum Error {
+	/// The call to runtime failed.
+	RuntimeError,
+}
+
+impl From<Error> for i64 {
+	fn from(e: Error) -> i64 {
+		match e {
+			Error::RuntimeError => 1,
+		}
+	}
+}
+
+fn runtime_error(message: &str, e: impl std::fmt::Debug) -> RpcError {
+	RpcError {
+		code: ErrorCode::ServerError(Error::RuntimeError.into()),
+		message: message.into(),
+		data: Some(format!("{:?}", e).into()),
+	}
+}
+
+impl<C, Block, KittyIndex, AccountId, Balance>
+	KittiesApi<
+		<Block as BlockT>::Hash,
+		KittyIndex,
+		AccountId,
+		KittyInfo<KittyIndex, AccountId, Balance>,
+	> for Kitties<C, Block>
+where
+	Block: BlockT,
+	C: 'static + ProvideRuntimeApi<Block> + HeaderBackend<Block>,
+	C::Api: KittiesRuntimeApi<Block, KittyIndex, AccountId, Balance>,
+	KittyIndex: Codec,
+	AccountId: Codec,
+	Balance: Codec,
+{
+	fn get_kitty(
+		&self,
+		kitty_id: KittyIndex,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<Option<KittyInfo<KittyIndex, AccountId, Balance>>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+		api.kitty(&at, kitty_id).map_err(|e| runtime_error("Unable to query kitty.", e))
+	}
+
+	fn get_owned(
+		&self,
+		owner: AccountId,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<Vec<KittyInfo<KittyIndex, AccountId, Balance>>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+		api.kitties_owned(&at, owner)
+			.map_err(|e| runtime_error("Unable to query owned kitties.", e))
+	}
+
+	fn list_for_sale(
+		&self,
+		offset: u32,
+		limit: u32,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<Vec<KittyInfo<KittyIndex, AccountId, Balance>>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+		api.kitties_for_sale(&at, offset, limit)
+			.map_err(|e| runtime_error("Unable to query kitties for sale.", e))
+	}
+
+	fn get_ancestry(
+		&self,
+		kitty_id: KittyIndex,
+		depth: u32,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<Vec<(u32, KittyIndex)>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+		api.ancestry(&at, kitty_id, depth)
+			.map_err(|e| runtime_error("Unable to query kitty ancestry.", e))
+	}
+}
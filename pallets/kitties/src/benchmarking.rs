@@ -5,54 +5,406 @@
 use super::*;
 use crate::Pallet as SubstrateKitties;
 
-use frame_benchmarking::{benchmarks, account};
+use frame_benchmarking::{benchmarks, account, whitelisted_caller};
+use frame_support::{sp_runtime::traits::{One, Saturating}, traits::{Currency, OnInitialize, ReservableCurrency}, BoundedVec};
 use frame_system::RawOrigin;
 use sp_std::prelude::*;
 
+// Give `owner` `n` kitties of distinct dna, so that they get distinct ids.
+fn mint_kitties<T: Config>(owner: &T::AccountId, n: u32) -> Result<Vec<T::KittyIndex>, &'static str> {
+	(0..n)
+		.map(|i| mint_kitty::<T>(owner, i, Gender::Male))
+		.collect()
+}
+
+// Give `owner` a generation 0 kitty whose dna is derived from `seed`.
+fn mint_kitty<T: Config>(owner: &T::AccountId, seed: u32, gender: Gender) -> Result<T::KittyIndex, &'static str> {
+	let mut dna = [0u8; 16];
+	dna[..4].copy_from_slice(&seed.to_le_bytes());
+	SubstrateKitties::<T>::mint(owner, Some(dna), Some(gender), None).map_err(Into::into)
+}
+
+// The block at which a kitty created now is revealed.
+fn next_reveal_block<T: Config>() -> T::BlockNumber {
+	<frame_system::Pallet<T>>::block_number() + T::MintRevealDelay::get().max(One::one())
+}
+
+// Have `owner` create a kitty with the highest royalty, and reveal it right away.
+fn create_revealed_kitty<T: Config>(owner: &T::AccountId) -> Result<T::KittyIndex, &'static str> {
+	SubstrateKitties::<T>::create_kitty(RawOrigin::Signed(owner.clone()).into(), T::MaxRoyalty::get())?;
+	SubstrateKitties::<T>::on_initialize(next_reveal_block::<T>());
+	SubstrateKitties::<T>::kitties_owned(owner)
+		.into_iter()
+		.find(|kitty_id| SubstrateKitties::<T>::mint_deposits(kitty_id).is_some())
+		.ok_or("the kitty is revealed")
+}
+
+// Fund `who` with `multiple` times the existential deposit.
+fn fund<T: Config>(who: &T::AccountId, multiple: u32) {
+	T::Currency::make_free_balance_be(who, existential_deposits::<T>(multiple));
+}
+
+// `multiple` times the existential deposit.
+fn existential_deposits<T: Config>(multiple: u32) -> <T::Currency as Currency<T::AccountId>>::Balance {
+	T::Currency::minimum_balance().saturating_mul(multiple.into())
+}
+
+// Put a kitty of `seller` under the hammer for a block, with a bid of `bidder` on it.
+fn auction_with_bid<T: Config>(
+	seller: &T::AccountId,
+	seed: u32,
+	bidder: T::AccountId,
+) -> Result<T::KittyIndex, &'static str> {
+	let kitty_id = mint_kitty::<T>(seller, seed, Gender::Male)?;
+	SubstrateKitties::<T>::create_auction(
+		RawOrigin::Signed(seller.clone()).into(),
+		kitty_id,
+		existential_deposits::<T>(10),
+		One::one(),
+	)?;
+	fund::<T>(&bidder, 1_000);
+	SubstrateKitties::<T>::bid(RawOrigin::Signed(bidder).into(), kitty_id, existential_deposits::<T>(10))?;
+	Ok(kitty_id)
+}
+
+// Have `n` distinct buyers make an offer on `kitty_id`, expiring at the next block.
+fn make_offers<T: Config>(kitty_id: T::KittyIndex, n: u32) -> Result<(), &'static str> {
+	let expiry = <frame_system::Pallet<T>>::block_number() + One::one();
+	for i in 0..n {
+		let buyer: T::AccountId = account("buyer", i, 0);
+		fund::<T>(&buyer, 1_000);
+		SubstrateKitties::<T>::make_offer(
+			RawOrigin::Signed(buyer).into(),
+			kitty_id,
+			existential_deposits::<T>(10),
+			expiry,
+		)?;
+	}
+	Ok(())
+}
+
 benchmarks!{
+	// The ownership bookkeeping is O(1), the number of kitties the accounts already own does not
+	// show in the weights.
 	create_kitty {
-		let caller: T::AccountId = account("caller", 0, 0);
-		let kitties_owned = SubstrateKitties::<T>::kitties_owned(&caller);
-	}: _(RawOrigin::Signed(caller))
+		let caller: T::AccountId = whitelisted_caller();
+		fund::<T>(&caller, 1_000);
+		let reveal_block = next_reveal_block::<T>();
+	}: _(RawOrigin::Signed(caller.clone()), T::MaxRoyalty::get())
 	verify {
-		let caller: T::AccountId = account("caller", 0, 0);
-		let kitties_owned_now = SubstrateKitties::<T>::kitties_owned(caller);
-		assert_eq!(kitties_owned.len() + 1, kitties_owned_now.len());
+		assert_eq!(SubstrateKitties::<T>::pending_mints(reveal_block).len(), 1);
 	}
 
-	/*
 	set_price {
-		let caller = account("caller", 0, 0);
-		// todo: why Error when use bid_price instead of variable s?
-		let s in 0 .. 100;
+		let caller: T::AccountId = whitelisted_caller();
+		let kitty_id = mint_kitties::<T>(&caller, 1)?[0];
+		let price = existential_deposits::<T>(100);
+	}: _(RawOrigin::Signed(caller), kitty_id, Some(price))
+	verify {
+		assert_eq!(SubstrateKitties::<T>::kitties(&kitty_id).and_then(|kitty| kitty.price), Some(price));
+	}
 
-		Pallet::<T>::create_kitty(RawOrigin::Signed(caller));
-		let kitties_owned = Pallet::<T>::kitties_owned(caller);
-	}: _ (RawOrigin::Signed(caller), kitties_owned[0], Some(s.into()))
+	transfer {
+		let caller: T::AccountId = whitelisted_caller();
+		let receiver: T::AccountId = account("receiver", 0, 0);
+		let kitty_id = mint_kitties::<T>(&caller, 1)?[0];
+	}: _(RawOrigin::Signed(caller), kitty_id, receiver.clone())
 	verify {
-		// query the metadata of the kitty
-		let kitty = Pallet::<T>::kitties(kitties_owned[0])
-			.expect("Could have this kitty ID owned by caller");
-		assert_eq!(kitty.price, Some(s.into()));
-	}*/
+		assert_eq!(SubstrateKitties::<T>::owned_count(&receiver), 1);
+	}
 
-	impl_benchmark_test_suite!(SubstrateKitties, crate::mock::new_test_ext(), crate::mock::Test);
-}
+	// The sale pays a protocol fee and a royalty to the creator of the kitty
+	buy_kitty {
+		let seller: T::AccountId = account("seller", 0, 0);
+		let buyer: T::AccountId = whitelisted_caller();
+		let creator: T::AccountId = account("creator", 0, 0);
+		fund::<T>(&creator, 1_000);
+		let kitty_id = create_revealed_kitty::<T>(&creator)?;
+		SubstrateKitties::<T>::transfer(RawOrigin::Signed(creator).into(), kitty_id, seller.clone())?;
+
+		fund::<T>(&T::Treasury::get(), 1);
+		fund::<T>(&seller, 1);
+		fund::<T>(&buyer, 1_000);
+		let price = existential_deposits::<T>(100);
+		SubstrateKitties::<T>::set_price(RawOrigin::Signed(seller).into(), kitty_id, Some(price))?;
+	}: _(RawOrigin::Signed(buyer.clone()), kitty_id, Some(price))
+	verify {
+		assert_eq!(SubstrateKitties::<T>::owned_count(&buyer), 1);
+		assert_eq!(SubstrateKitties::<T>::kitties(&kitty_id).map(|kitty| kitty.owner), Some(buyer));
+	}
+
+	breed_kitty {
+		let caller: T::AccountId = whitelisted_caller();
+		let father = mint_kitty::<T>(&caller, 0, Gender::Male)?;
+		let mother = mint_kitty::<T>(&caller, 1, Gender::Female)?;
+		let reveal_block = next_reveal_block::<T>();
+	}: _(RawOrigin::Signed(caller.clone()), father, mother, T::MaxRoyalty::get())
+	verify {
+		assert_eq!(SubstrateKitties::<T>::pending_mints(reveal_block).len(), 1);
+	}
+
+	offer_sire {
+		let caller: T::AccountId = whitelisted_caller();
+		let kitty_id = mint_kitties::<T>(&caller, 1)?[0];
+		let fee = existential_deposits::<T>(10);
+	}: _(RawOrigin::Signed(caller), kitty_id, Some(fee))
+	verify {
+		assert_eq!(SubstrateKitties::<T>::sire_offers(&kitty_id), Some(fee));
+	}
+
+	breed_with_sire {
+		let sire_owner: T::AccountId = account("sire_owner", 0, 0);
+		let sire_id = mint_kitty::<T>(&sire_owner, 0, Gender::Male)?;
+		fund::<T>(&sire_owner, 1);
+		SubstrateKitties::<T>::offer_sire(
+			RawOrigin::Signed(sire_owner).into(),
+			sire_id,
+			Some(existential_deposits::<T>(10)),
+		)?;
+
+		let caller: T::AccountId = whitelisted_caller();
+		fund::<T>(&caller, 1_000);
+		let dam_id = mint_kitty::<T>(&caller, 1, Gender::Female)?;
+		let reveal_block = next_reveal_block::<T>();
+	}: _(RawOrigin::Signed(caller.clone()), dam_id, sire_id, T::MaxRoyalty::get())
+	verify {
+		assert_eq!(SubstrateKitties::<T>::pending_mints(reveal_block).len(), 1);
+		assert_eq!(T::Currency::reserved_balance(&caller), existential_deposits::<T>(10));
+	}
+
+	// `a` auctions already end at the same block as the new one.
+	create_auction {
+		let a in 0 .. T::MaxAuctionsPerBlock::get() - 1;
+
+		let other_seller: T::AccountId = account("other_seller", 0, 0);
+		for kitty_id in mint_kitties::<T>(&other_seller, a)? {
+			SubstrateKitties::<T>::create_auction(
+				RawOrigin::Signed(other_seller.clone()).into(),
+				kitty_id,
+				existential_deposits::<T>(10),
+				T::MaxAuctionDuration::get(),
+			)?;
+		}
+
+		let caller: T::AccountId = whitelisted_caller();
+		let kitty_id = mint_kitties::<T>(&caller, 1)?[0];
+		let reserve = existential_deposits::<T>(10);
+	}: _(RawOrigin::Signed(caller), kitty_id, reserve, T::MaxAuctionDuration::get())
+	verify {
+		assert!(SubstrateKitties::<T>::auctions(&kitty_id).is_some());
+	}
+
+	// The worst case outbids a previous bidder, whose bid is released.
+	bid {
+		let seller: T::AccountId = account("seller", 0, 0);
+		let kitty_id = auction_with_bid::<T>(&seller, 0, account("bidder", 0, 0))?;
+
+		let caller: T::AccountId = whitelisted_caller();
+		fund::<T>(&caller, 1_000);
+		let amount = existential_deposits::<T>(20);
+	}: _(RawOrigin::Signed(caller.clone()), kitty_id, amount)
+	verify {
+		assert_eq!(
+			SubstrateKitties::<T>::auctions(&kitty_id).and_then(|auction| auction.best_bid),
+			Some((caller, amount))
+		);
+	}
+
+	// Settle `a` auctions ending at the same block, each with a winning bid.
+	on_initialize {
+		let a in 0 .. T::MaxAuctionsPerBlock::get();
+
+		let seller: T::AccountId = account("seller", 0, 0);
+		fund::<T>(&seller, 1);
+		fund::<T>(&T::Treasury::get(), 1);
+		let kitty_ids = (0..a)
+			.map(|i| auction_with_bid::<T>(&seller, i, account("bidder", i, 0)))
+			.collect::<Result<Vec<_>, _>>()?;
+		let end = <frame_system::Pallet<T>>::block_number() + One::one();
+	}: {
+		SubstrateKitties::<T>::on_initialize(end);
+	}
+	verify {
+		assert!(kitty_ids.iter().all(|kitty_id| SubstrateKitties::<T>::auctions(kitty_id).is_none()));
+		assert_eq!(SubstrateKitties::<T>::owned_count(&seller), 0);
+	}
+
+	// An operator of the owner approves, which is the most expensive check
+	approve {
+		let owner: T::AccountId = account("owner", 0, 0);
+		let caller: T::AccountId = whitelisted_caller();
+		let delegate: T::AccountId = account("delegate", 0, 0);
+		let kitty_id = mint_kitties::<T>(&owner, 1)?[0];
+		SubstrateKitties::<T>::set_approval_for_all(RawOrigin::Signed(owner).into(), caller.clone(), true)?;
+	}: _(RawOrigin::Signed(caller), kitty_id, Some(delegate.clone()))
+	verify {
+		assert_eq!(SubstrateKitties::<T>::approvals(&kitty_id), Some(delegate));
+	}
+
+	set_approval_for_all {
+		let caller: T::AccountId = whitelisted_caller();
+		let operator: T::AccountId = account("operator", 0, 0);
+	}: _(RawOrigin::Signed(caller.clone()), operator.clone(), true)
+	verify {
+		assert!(SubstrateKitties::<T>::is_approved_for_all(&caller, &operator));
+	}
+
+	// An operator of the owner transfers, which is the most expensive check
+	transfer_from {
+		let owner: T::AccountId = account("owner", 0, 0);
+		let caller: T::AccountId = whitelisted_caller();
+		let receiver: T::AccountId = account("receiver", 0, 0);
+		let kitty_id = mint_kitties::<T>(&owner, 1)?[0];
+		SubstrateKitties::<T>::set_approval_for_all(RawOrigin::Signed(owner.clone()).into(), caller.clone(), true)?;
+	}: _(RawOrigin::Signed(caller), owner, receiver.clone(), kitty_id)
+	verify {
+		assert_eq!(SubstrateKitties::<T>::kitties(&kitty_id).map(|kitty| kitty.owner), Some(receiver));
+	}
+
+	// Each kitty goes to a distinct receiver, so that the capacity of every one is checked
+	transfer_batch {
+		let n in 1 .. T::MaxBatchSize::get();
+
+		let caller: T::AccountId = whitelisted_caller();
+		let transfers: BoundedVec<_, T::MaxBatchSize> = mint_kitties::<T>(&caller, n)?
+			.into_iter()
+			.enumerate()
+			.map(|(i, kitty_id)| (kitty_id, account("receiver", i as u32, 0)))
+			.collect::<Vec<_>>()
+			.try_into()
+			.map_err(|_| "too many transfers")?;
+	}: _(RawOrigin::Signed(caller.clone()), transfers)
+	verify {
+		assert_eq!(SubstrateKitties::<T>::owned_count(&caller), 0);
+	}
+
+	set_price_batch {
+		let n in 1 .. T::MaxBatchSize::get();
+
+		let caller: T::AccountId = whitelisted_caller();
+		let price = existential_deposits::<T>(100);
+		let kitty_ids = mint_kitties::<T>(&caller, n)?;
+		let prices: BoundedVec<_, T::MaxBatchSize> = kitty_ids.iter()
+			.map(|kitty_id| (*kitty_id, Some(price)))
+			.collect::<Vec<_>>()
+			.try_into()
+			.map_err(|_| "too many prices")?;
+	}: _(RawOrigin::Signed(caller), prices)
+	verify {
+		assert!(kitty_ids.iter().all(|kitty_id| {
+			SubstrateKitties::<T>::kitties(kitty_id).and_then(|kitty| kitty.price) == Some(price)
+		}));
+	}
+
+	// The `o` offers already on the kitty have expired, and are released
+	make_offer {
+		let o in 0 .. T::MaxOffersPerKitty::get();
+
+		let owner: T::AccountId = account("owner", 0, 0);
+		let kitty_id = mint_kitties::<T>(&owner, 1)?[0];
+		make_offers::<T>(kitty_id, o)?;
+		let now = <frame_system::Pallet<T>>::block_number() + One::one();
+		<frame_system::Pallet<T>>::set_block_number(now);
+
+		let caller: T::AccountId = whitelisted_caller();
+		fund::<T>(&caller, 1_000);
+		let amount = existential_deposits::<T>(20);
+	}: _(RawOrigin::Signed(caller.clone()), kitty_id, amount, now + One::one())
+	verify {
+		let offers = SubstrateKitties::<T>::offers(&kitty_id);
+		assert_eq!(offers.len(), 1);
+		assert_eq!(offers[0].buyer, caller);
+	}
+
+	// The `o` other offers on the kitty are released when it changes hands
+	accept_offer {
+		let o in 0 .. T::MaxOffersPerKitty::get() - 1;
+
+		let caller: T::AccountId = whitelisted_caller();
+		let kitty_id = mint_kitties::<T>(&caller, 1)?[0];
+		make_offers::<T>(kitty_id, o + 1)?;
+		fund::<T>(&T::Treasury::get(), 1);
+		let buyer: T::AccountId = account("buyer", o, 0);
+	}: _(RawOrigin::Signed(caller), kitty_id, buyer.clone())
+	verify {
+		assert_eq!(SubstrateKitties::<T>::kitties(&kitty_id).map(|kitty| kitty.owner), Some(buyer));
+		assert!(SubstrateKitties::<T>::offers(&kitty_id).is_empty());
+	}
 
-// todo: Error cannot find function 'test_benchmark_create_kitty' in this scope
-/*
-#[cfg(test)]
-mod tests {
-	use super::*;
-	use crate::mock::{new_test_ext, Test};
-	use frame_support::assert_ok;
+	// The offer of the caller is the last of `o` on the kitty
+	withdraw_offer {
+		let o in 1 .. T::MaxOffersPerKitty::get();
 
-	#[test]
-	fn test_benchmarks() {
-		new_test_ext().execute_with(|| {
-			assert_ok!(test_benchmark_create_kitty::<Test>());
-			assert_ok!(test_benchmark_set_price::<Test>());
-		});
+		let owner: T::AccountId = account("owner", 0, 0);
+		let kitty_id = mint_kitties::<T>(&owner, 1)?[0];
+		make_offers::<T>(kitty_id, o)?;
+		let buyer: T::AccountId = account("buyer", o - 1, 0);
+	}: _(RawOrigin::Signed(buyer), kitty_id)
+	verify {
+		assert_eq!(SubstrateKitties::<T>::offers(&kitty_id).len() as u32, o - 1);
+	}
+
+	// Release all `o` offers on a kitty, as when it changes hands or they all expired
+	release_offers {
+		let o in 0 .. T::MaxOffersPerKitty::get();
+
+		let owner: T::AccountId = account("owner", 0, 0);
+		let kitty_id = mint_kitties::<T>(&owner, 1)?[0];
+		make_offers::<T>(kitty_id, o)?;
+	}: {
+		let mut offers = SubstrateKitties::<T>::offers(&kitty_id);
+		SubstrateKitties::<T>::release_offers(&kitty_id, &mut offers, |_| true);
+		SubstrateKitties::<T>::put_offers(&kitty_id, offers);
+	}
+	verify {
+		assert!(SubstrateKitties::<T>::offers(&kitty_id).is_empty());
 	}
+
+	// The kitty was created by the caller, whose minting deposit is released
+	burn {
+		let caller: T::AccountId = whitelisted_caller();
+		fund::<T>(&caller, 1_000);
+		let kitty_id = create_revealed_kitty::<T>(&caller)?;
+	}: _(RawOrigin::Signed(caller.clone()), kitty_id)
+	verify {
+		assert!(SubstrateKitties::<T>::kitties(&kitty_id).is_none());
+		assert!(SubstrateKitties::<T>::mint_deposits(&kitty_id).is_none());
+		assert_eq!(SubstrateKitties::<T>::owned_count(&caller), 0);
+	}
+
+	// Each kitty revealed is bred by a different account with an offered sire, breeding reads the
+	// parents and paying the sire fee moves balance on top
+	reveal_mints {
+		let m in 0 .. T::MaxMintsPerBlock::get();
+
+		let reveal_block = next_reveal_block::<T>();
+		for i in 0 .. m {
+			let sire_owner: T::AccountId = account("sire_owner", i, 0);
+			let sire_id = mint_kitty::<T>(&sire_owner, 2 * i, Gender::Male)?;
+			SubstrateKitties::<T>::offer_sire(
+				RawOrigin::Signed(sire_owner).into(),
+				sire_id,
+				Some(existential_deposits::<T>(10)),
+			)?;
+
+			let owner: T::AccountId = account("owner", i, 0);
+			fund::<T>(&owner, 1_000);
+			let dam_id = mint_kitty::<T>(&owner, 2 * i + 1, Gender::Female)?;
+			SubstrateKitties::<T>::breed_with_sire(
+				RawOrigin::Signed(owner).into(),
+				dam_id,
+				sire_id,
+				T::MaxRoyalty::get(),
+			)?;
+		}
+		let kitty_cnt = SubstrateKitties::<T>::kitty_cnt();
+	}: {
+		SubstrateKitties::<T>::on_initialize(reveal_block);
+	}
+	verify {
+		assert!(SubstrateKitties::<T>::pending_mints(reveal_block).is_empty());
+		assert_eq!(SubstrateKitties::<T>::kitty_cnt(), kitty_cnt + m as u64);
+	}
+
+	impl_benchmark_test_suite!(SubstrateKitties, crate::mock::new_test_ext(), crate::mock::Test);
 }
- */
@@ -0,0 +1,194 @@
+//! Kitty genetics: decoding a DNA into visible traits, and passing genes on to offspring.
+//!
+//! A DNA is laid out as 7 gene pairs followed by the generation:
+//!
+//! | bytes   | content                                     |
+//! |---------|---------------------------------------------|
+//! | 0, 1    | fur colour, dominant and recessive slot     |
+//! | 2, 3    | eye colour, dominant and recessive slot     |
+//! | 4, 5    | pattern, dominant and recessive slot        |
+//! | 6 - 13  | hidden gene pairs, inherited the same way   |
+//! | 14, 15  | generation, big-endian `u16`                |
+//!
+//! Of the two alleles of a pair, the lower one is dominant and decides the trait. The low 3
+//! bits of an allele pick the variant, and an allele of at least `RARE_ALLELE` is rare: being
+//! recessive, a rare allele only shows when both slots carry one.
+
+use codec::{Decode, Encode};
+use frame_support::RuntimeDebug;
+use scale_info::TypeInfo;
+
+#[cfg(feature = "std")]
+use frame_support::serde::{Deserialize, Serialize};
+
+/// The raw DNA of a kitty.
+pub type Dna = [u8; 16];
+
+/// The number of gene pairs in a DNA.
+pub const GENE_PAIRS: usize = 7;
+
+/// Alleles from this value on are rare.
+pub const RARE_ALLELE: u8 = 0xe0;
+
+// The genes of the visible traits, by their pair index.
+const FUR_COLOUR_GENE: usize = 0;
+const EYE_COLOUR_GENE: usize = 1;
+const PATTERN_GENE: usize = 2;
+
+// The position of the generation in a DNA.
+const GENERATION_OFFSET: usize = 2 * GENE_PAIRS;
+
+#[derive(Clone, Copy, Encode, Decode, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub enum FurColour {
+	Black,
+	White,
+	Ginger,
+	Grey,
+	Cream,
+	Chocolate,
+	Cinnamon,
+	Lilac,
+}
+
+#[derive(Clone, Copy, Encode, Decode, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub enum EyeColour {
+	Blue,
+	Green,
+	Amber,
+	Copper,
+	Hazel,
+	Gold,
+	Aqua,
+	/// One eye of a different colour than the other.
+	Odd,
+}
+
+#[derive(Clone, Copy, Encode, Decode, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub enum Pattern {
+	Solid,
+	Tabby,
+	Tortoiseshell,
+	Calico,
+	Bicolour,
+	Pointed,
+	Spotted,
+	Tuxedo,
+}
+
+/// How rare a kitty is, by the number of visible traits decided by a rare allele.
+#[derive(Clone, Copy, Encode, Decode, PartialEq, Eq, PartialOrd, Ord, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub enum RarityTier {
+	Common,
+	Uncommon,
+	Rare,
+	Legendary,
+}
+
+/// The traits a DNA decodes into.
+#[derive(Clone, Copy, Encode, Decode, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct KittyTraits {
+	pub fur_colour: FurColour,
+	pub eye_colour: EyeColour,
+	pub pattern: Pattern,
+	pub rarity: RarityTier,
+	/// 0 for a minted kitty, one more than the older parent for a bred one.
+	pub generation: u16,
+}
+
+// The allele deciding the trait of the gene pair `gene`.
+fn dominant(dna: &Dna, gene: usize) -> u8 {
+	dna[2 * gene].min(dna[2 * gene + 1])
+}
+
+/// The generation recorded in `dna`.
+pub fn generation(dna: &Dna) -> u16 {
+	u16::from_be_bytes([dna[GENERATION_OFFSET], dna[GENERATION_OFFSET + 1]])
+}
+
+/// Record `generation` in `dna`.
+pub fn set_generation(dna: &mut Dna, generation: u16) {
+	dna[GENERATION_OFFSET..].copy_from_slice(&generation.to_be_bytes());
+}
+
+/// Decode the traits of `dna`. Every DNA decodes.
+pub fn decode(dna: &Dna) -> KittyTraits {
+	let fur = dominant(dna, FUR_COLOUR_GENE);
+	let eyes = dominant(dna, EYE_COLOUR_GENE);
+	let pattern = dominant(dna, PATTERN_GENE);
+
+	let rarity = match [fur, eyes, pattern].iter().filter(|allele| **allele >= RARE_ALLELE).count() {
+		0 => RarityTier::Common,
+		1 => RarityTier::Uncommon,
+		2 => RarityTier::Rare,
+		_ => RarityTier::Legendary,
+	};
+
+	KittyTraits {
+		fur_colour: match fur & 0b111 {
+			0 => FurColour::Black,
+			1 => FurColour::White,
+			2 => FurColour::Ginger,
+			3 => FurColour::Grey,
+			4 => FurColour::Cream,
+			5 => FurColour::Chocolate,
+			6 => FurColour::Cinnamon,
+			_ => FurColour::Lilac,
+		},
+		eye_colour: match eyes & 0b111 {
+			0 => EyeColour::Blue,
+			1 => EyeColour::Green,
+			2 => EyeColour::Amber,
+			3 => EyeColour::Copper,
+			4 => EyeColour::Hazel,
+			5 => EyeColour::Gold,
+			6 => EyeColour::Aqua,
+			_ => EyeColour::Odd,
+		},
+		pattern: match pattern & 0b111 {
+			0 => Pattern::Solid,
+			1 => Pattern::Tabby,
+			2 => Pattern::Tortoiseshell,
+			3 => Pattern::Calico,
+			4 => Pattern::Bicolour,
+			5 => Pattern::Pointed,
+			6 => Pattern::Spotted,
+			_ => Pattern::Tuxedo,
+		},
+		rarity,
+		generation: generation(dna),
+	}
+}
+
+/// A generation 0 DNA from random bytes.
+pub fn from_random(random: [u8; 16]) -> Dna {
+	let mut dna = [0u8; 16];
+	for gene in 0..GENE_PAIRS {
+		let (a, b) = (random[2 * gene], random[2 * gene + 1]);
+		dna[2 * gene] = a.min(b);
+		dna[2 * gene + 1] = a.max(b);
+	}
+	dna
+}
+
+/// The DNA of the offspring of `dna1` and `dna2`.
+///
+/// For each gene pair, each parent passes on one of its two alleles, chosen by a bit of `random`.
+/// The generation is one more than the one of the older parent.
+pub fn breed(dna1: &Dna, dna2: &Dna, random: [u8; 16]) -> Dna {
+	let mut child = [0u8; 16];
+	for gene in 0..GENE_PAIRS {
+		let from1 = dna1[2 * gene + (random[gene] & 1) as usize];
+		let from2 = dna2[2 * gene + ((random[gene] >> 1) & 1) as usize];
+		child[2 * gene] = from1.min(from2);
+		child[2 * gene + 1] = from1.max(from2);
+	}
+
+	set_generation(&mut child, generation(dna1).max(generation(dna2)).saturating_add(1));
+	child
+}
@@ -8,26 +8,31 @@ mod mock;
 mod tests;
 #[cfg(feature = "runtime-benchmarks")]
 mod benchmarking;
+pub mod genetics;
+pub mod migrations;
+mod nonfungibles;
 pub mod weights;
 
 #[frame_support::pallet]
 pub mod pallet {
 	use frame_support::{
-		sp_runtime::traits::Hash,
+		sp_runtime::{traits::{Hash, One, Saturating, Zero}, PerThing, Permill},
 		dispatch::{DispatchResult, DispatchResultWithPostInfo},
-		traits::{Currency, ReservableCurrency, ExistenceRequirement, Randomness},
+		traits::{BalanceStatus, Currency, ReservableCurrency, ExistenceRequirement, Randomness, StorageVersion},
 		pallet_prelude::*,
 	};
 
 	// helper traits
 	use frame_system::pallet_prelude::*;
 	use scale_info::TypeInfo;
+	use sp_std::{collections::{btree_map::BTreeMap, btree_set::BTreeSet}, prelude::*};
 	use frame_support::{
 		sp_io::hashing::blake2_128,
 		transactional
 	};
 
 	use crate::weights::WeightInfo;
+	use crate::genetics::{self, KittyTraits};
 
 	#[cfg(feature = "std")]
 	use frame_support::serde::{Deserialize, Serialize};
@@ -44,6 +49,14 @@ pub mod pallet {
 		pub price: Option<BalanceOf<T>>,
 		pub gender: Gender,
 		pub owner: AccountOf<T>,
+		/// The kitties it was bred from, `None` for a minted kitty.
+		pub parents: Option<(T::KittyIndex, T::KittyIndex)>,
+		/// 0 for a minted kitty, one more than the older parent for a bred one.
+		pub generation: u16,
+		/// The block the kitty was minted or bred in.
+		pub birth_block: T::BlockNumber,
+		/// The block from which the kitty can breed again.
+		pub cooldown_end: T::BlockNumber,
 	}
 
 	// Enum declaration for Gender.
@@ -55,9 +68,90 @@ pub mod pallet {
 		Female,
 	}
 
+	// Struct for holding a running English auction of a kitty.
+	#[derive(Clone, Encode, Decode, PartialEq, RuntimeDebug, TypeInfo)]
+	#[scale_info(skip_type_params(T))]
+	pub struct Auction<T: Config> {
+		pub seller: AccountOf<T>,
+		/// The lowest bid the seller is willing to accept.
+		pub reserve: BalanceOf<T>,
+		/// The block at which the auction is settled.
+		pub end: T::BlockNumber,
+		/// The highest bidder so far and the amount reserved from them.
+		pub best_bid: Option<(AccountOf<T>, BalanceOf<T>)>,
+	}
+
+	// Struct for holding an escrowed offer to buy a kitty.
+	#[derive(Clone, Encode, Decode, PartialEq, RuntimeDebug, TypeInfo)]
+	#[scale_info(skip_type_params(T))]
+	pub struct Offer<T: Config> {
+		pub buyer: AccountOf<T>,
+		/// The amount reserved from the buyer.
+		pub amount: BalanceOf<T>,
+		/// The block from which the offer can no longer be accepted.
+		pub expiry: T::BlockNumber,
+	}
+
+	// Struct for holding a kitty whose dna is revealed at a later block.
+	#[derive(Clone, Encode, Decode, PartialEq, RuntimeDebug, TypeInfo)]
+	#[scale_info(skip_type_params(T))]
+	pub struct PendingMint<T: Config> {
+		pub owner: AccountOf<T>,
+		/// The minting deposit reserved from the owner, nothing for a bred kitty.
+		pub deposit: BalanceOf<T>,
+		/// The royalty the owner gets on every sale of the kitty.
+		pub royalty: Permill,
+		/// The kitties it is bred from, `None` for a created kitty.
+		pub parents: Option<(T::KittyIndex, T::KittyIndex)>,
+		/// The owner of the sire and the fee reserved from the owner to pay them at the reveal,
+		/// for a kitty bred with an offered sire.
+		pub sire_fee: Option<(AccountOf<T>, BalanceOf<T>)>,
+	}
+
+	/// How the price paid for a kitty was split between the treasury, the creator of the kitty
+	/// and the seller.
+	#[derive(Clone, Encode, Decode, PartialEq, RuntimeDebug, TypeInfo)]
+	pub struct Sale<AccountId, Balance> {
+		/// The whole amount paid by the buyer.
+		pub price: Balance,
+		/// The part paid to the treasury.
+		pub protocol_fee: Balance,
+		/// The creator of the kitty and the royalty paid to them, if any.
+		pub royalty: Option<(AccountId, Balance)>,
+		/// The part paid to the seller.
+		pub proceeds: Balance,
+	}
+
+	pub type SaleOf<T> = Sale<AccountOf<T>, BalanceOf<T>>;
+
+	/// A self-contained view of a kitty together with its id, as served by the `KittiesApi`
+	/// runtime API and the `kitties_*` RPC namespace.
+	#[derive(Clone, Encode, Decode, PartialEq, RuntimeDebug, TypeInfo)]
+	#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+	#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+	pub struct KittyInfo<KittyIndex, AccountId, Balance> {
+		pub id: KittyIndex,
+		pub dna: [u8; 16],
+		pub price: Option<Balance>,
+		pub gender: Gender,
+		pub owner: AccountId,
+		/// The kitties it was bred from, `None` for a minted kitty.
+		pub parents: Option<(KittyIndex, KittyIndex)>,
+		/// The traits decoded from the dna.
+		pub traits: KittyTraits,
+	}
+
+	pub type KittyInfoOf<T> = KittyInfo<<T as Config>::KittyIndex, AccountOf<T>, BalanceOf<T>>;
+
+	/// The current storage version.
+	const STORAGE_VERSION: StorageVersion = StorageVersion::new(4);
+
+	/// The deepest ancestry the `ancestry` query walks.
+	pub const MAX_ANCESTRY_DEPTH: u32 = 8;
 
 	#[pallet::pallet]
 	#[pallet::generate_store(pub(super) trait Store)]
+	#[pallet::storage_version(STORAGE_VERSION)]
 	pub struct Pallet<T>(_);
 
 	/// Configure the pallet by specifying the parameters and types it depends on.
@@ -69,13 +163,15 @@ pub mod pallet {
 		/// The Currency handler for the Kitties pallet.
 		type Currency: Currency<Self::AccountId> + ReservableCurrency<Self::AccountId>;
 
-		// Specify the type for Randomness we want to specify for runtime.
-		// type KittyRandomness: Randomness<Self::KittyIndex, Self::BlockNumber>;
+		/// The source of randomness for the dna and gender of new kitties. It should not be
+		/// biasable by block authors, like the VRF output of BABE. The pallet does not provide
+		/// one: with a biasable source, the reveal delay only keeps the senders from picking the
+		/// block their kitty is drawn from, not its author from steering the draw.
 		type KittyRandomness: Randomness<Self::Hash, Self::BlockNumber>;
 
 		// type KittyIndex = Self::Hash;
 		// type KittyHashing = Self::Hashing;
-		type KittyIndex: Parameter + Member + MaybeSerializeDeserialize + Copy;
+		type KittyIndex: Parameter + Member + MaybeSerializeDeserialize + Copy + Ord;
 		type KittyHashing: Hash<Output = Self::KittyIndex> + TypeInfo;
 
 		/// Information on runtime weights
@@ -87,6 +183,49 @@ pub mod pallet {
 
 		#[pallet::constant]
 		type MinKittyMintingPrice: Get<u32>;
+
+		/// The maximum number of blocks an auction can run for.
+		#[pallet::constant]
+		type MaxAuctionDuration: Get<Self::BlockNumber>;
+
+		/// The maximum number of auctions that can be settled in a single block.
+		#[pallet::constant]
+		type MaxAuctionsPerBlock: Get<u32>;
+
+		/// The number of blocks a generation 0 kitty has to wait between two breedings. Each
+		/// generation waits this long once more.
+		#[pallet::constant]
+		type BreedingCooldown: Get<Self::BlockNumber>;
+
+		/// The maximum number of items in a batch call.
+		#[pallet::constant]
+		type MaxBatchSize: Get<u32>;
+
+		/// The maximum number of open offers on a single kitty.
+		#[pallet::constant]
+		type MaxOffersPerKitty: Get<u32>;
+
+		/// The account receiving the protocol fee of every sale.
+		#[pallet::constant]
+		type Treasury: Get<Self::AccountId>;
+
+		/// The part of the price of every sale paid to the treasury.
+		#[pallet::constant]
+		type ProtocolFee: Get<Permill>;
+
+		/// The highest royalty the creator of a kitty can ask on its sales.
+		#[pallet::constant]
+		type MaxRoyalty: Get<Permill>;
+
+		/// The number of blocks, at least one, between the creation of a kitty and the reveal of
+		/// its dna. It should be enough for the randomness of the reveal block to be unknown at
+		/// creation.
+		#[pallet::constant]
+		type MintRevealDelay: Get<Self::BlockNumber>;
+
+		/// The maximum number of kitties revealed in a single block.
+		#[pallet::constant]
+		type MaxMintsPerBlock: Get<u32>;
 	}
 
 	// Storage items.
@@ -95,6 +234,12 @@ pub mod pallet {
 	/// Keeps track of the number of Kitties in existence.
 	pub(super) type KittyCnt<T: Config> = StorageValue<_, u64, ValueQuery>;
 
+	#[pallet::storage]
+	#[pallet::getter(fn kitty_nonce)]
+	/// The number of kitties ever minted or bred, hashed into the id of each new kitty so that
+	/// identical kitties get distinct ids.
+	pub(super) type KittyNonce<T: Config> = StorageValue<_, u64, ValueQuery>;
+
 	#[pallet::storage]
 	#[pallet::getter(fn kitties)]
 	/// Unique assets （Hash Map）
@@ -106,16 +251,137 @@ pub mod pallet {
 	>;
 
 	#[pallet::storage]
-	#[pallet::getter(fn kitties_owned)]
 	/// Keeps track of what accounts own what Kitty.
-	pub(super) type KittiesOwned<T: Config> = StorageMap<
+	pub(super) type KittiesOwned<T: Config> = StorageDoubleMap<
+		_,
+		Twox64Concat,
+		T::AccountId,
+		Twox64Concat,
+		T::KittyIndex,
+		(),
+	>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn owned_count)]
+	/// The number of kitties each account owns, at most `MaxKittyOwned`.
+	pub(super) type OwnedCount<T: Config> = StorageMap<
+		_,
+		Twox64Concat,
+		T::AccountId,
+		u32,
+		ValueQuery,
+	>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn auctions)]
+	/// The running auctions, keyed by the kitty under the hammer.
+	pub(super) type Auctions<T: Config> = StorageMap<
+		_,
+		Twox64Concat,
+		T::KittyIndex,
+		Auction<T>,
+	>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn auctions_ending_at)]
+	/// The kitties whose auction is settled at a given block.
+	pub(super) type AuctionsEndingAt<T: Config> = StorageMap<
+		_,
+		Twox64Concat,
+		T::BlockNumber,
+		BoundedVec<T::KittyIndex, T::MaxAuctionsPerBlock>,
+		ValueQuery,
+	>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn pending_mints)]
+	/// The kitties created but not yet revealed, by the block at which they are revealed.
+	pub(super) type PendingMints<T: Config> = StorageMap<
+		_,
+		Twox64Concat,
+		T::BlockNumber,
+		BoundedVec<PendingMint<T>, T::MaxMintsPerBlock>,
+		ValueQuery,
+	>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn sire_offers)]
+	/// The kitties offered as a sire, with the fee the owner asks for each breeding.
+	pub(super) type SireOffers<T: Config> = StorageMap<
+		_,
+		Twox64Concat,
+		T::KittyIndex,
+		BalanceOf<T>,
+	>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn approvals)]
+	/// The account approved to transfer a kitty on behalf of its owner, cleared on every change
+	/// of hands.
+	pub(super) type Approvals<T: Config> = StorageMap<
+		_,
+		Twox64Concat,
+		T::KittyIndex,
+		T::AccountId,
+	>;
+
+	#[pallet::storage]
+	/// The operators each account approved to transfer all of its kitties.
+	pub(super) type OperatorApprovals<T: Config> = StorageDoubleMap<
 		_,
 		Twox64Concat,
 		T::AccountId,
-		BoundedVec<T::KittyIndex, T::MaxKittyOwned>,
+		Twox64Concat,
+		T::AccountId,
+		(),
+	>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn offers)]
+	/// The open offers to buy a kitty, at most one per buyer. They are all released when the
+	/// kitty changes hands.
+	pub(super) type Offers<T: Config> = StorageMap<
+		_,
+		Twox64Concat,
+		T::KittyIndex,
+		BoundedVec<Offer<T>, T::MaxOffersPerKitty>,
 		ValueQuery,
 	>;
 
+	#[pallet::storage]
+	#[pallet::getter(fn mint_deposits)]
+	/// The account that reserved the minting deposit of a kitty and the amount, released to that
+	/// account when the kitty is burned.
+	pub(super) type MintDeposits<T: Config> = StorageMap<
+		_,
+		Twox64Concat,
+		T::KittyIndex,
+		(T::AccountId, BalanceOf<T>),
+	>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn royalties)]
+	/// The account that minted or bred a kitty and the part of the price of every sale of the
+	/// kitty paid to it.
+	pub(super) type Royalties<T: Config> = StorageMap<
+		_,
+		Twox64Concat,
+		T::KittyIndex,
+		(T::AccountId, Permill),
+	>;
+
+	#[pallet::storage]
+	/// The ids of the burned kitties, which are never reissued.
+	pub(super) type BurnedKitties<T: Config> = StorageMap<
+		_,
+		Twox64Concat,
+		T::KittyIndex,
+		(),
+	>;
+
+	#[pallet::storage]
+	/// The last kitty whose offers were swept for expired ones, `None` to start over.
+	pub(super) type OfferSweepCursor<T: Config> = StorageValue<_, T::KittyIndex>;
 
 	// Our pallet's genesis configuration.
 	#[pallet::genesis_config]
@@ -136,7 +402,7 @@ pub mod pallet {
 		fn build(&self) {
 			// When building a kitty from genesis config, we require the dna and gender to be supplied.
 			for (acct, dna, gender) in &self.kitties {
-				let _ = <Pallet<T>>::mint(acct, Some(dna.clone()), Some(gender.clone()));
+				let _ = <Pallet<T>>::mint(acct, Some(dna.clone()), Some(gender.clone()), None);
 			}
 		}
 	}
@@ -158,7 +424,7 @@ pub mod pallet {
 		NotKittyOwner,
 		/// Ensures the Kitty is for sale.
 		KittyNotForSale,
-		/// Ensures that the buying price is greater than the asking price.
+		/// Ensures that the asking price is not above the highest price the buyer will pay.
 		KittyBidPriceTooLow,
 		/// Ensures that an account has enough funds to purchase a Kitty.
 		NotEnoughBalance,
@@ -166,6 +432,40 @@ pub mod pallet {
 		SameParentGender,
 		/// Ensure the parent kitties are not the same
 		SameParentKittyId,
+		/// The kitty is locked in a running auction.
+		KittyInAuction,
+		/// There is no running auction for the kitty.
+		AuctionNotExist,
+		/// The auction has already reached its end block.
+		AuctionEnded,
+		/// The auction duration must be non-zero and at most `MaxAuctionDuration`.
+		InvalidAuctionDuration,
+		/// Too many auctions are already settled at the requested end block.
+		TooManyAuctionsEnding,
+		/// The bid is lower than the reserve price of the auction.
+		BidBelowReserve,
+		/// The bid does not exceed the current highest bid.
+		BidNotHighEnough,
+		/// The kitty has bred or was born too recently to breed.
+		KittyOnCooldown,
+		/// A kitty cannot breed with its parent, child or sibling.
+		KittiesRelated,
+		/// The kitty is not offered as a sire.
+		SireNotOffered,
+		/// A kitty with the same id already exists, or was burned.
+		DuplicateKittyId,
+		/// The account is neither the owner of the kitty nor approved to transfer it.
+		NotApproved,
+		/// The account has no offer on the kitty.
+		OfferNotExist,
+		/// The offer has expired, or its expiry block is not in the future.
+		OfferExpired,
+		/// The kitty already has `MaxOffersPerKitty` offers, none lower than this one.
+		OfferTooLow,
+		/// The royalty is higher than `MaxRoyalty`.
+		RoyaltyTooHigh,
+		/// Too many kitties are already revealed at the reveal block.
+		TooManyPendingMints,
 	}
 
 	#[pallet::event]
@@ -179,38 +479,146 @@ pub mod pallet {
 		PriceSet(T::AccountId, T::KittyIndex, Option<BalanceOf<T>>),
 		/// A Kitty was sucessfully transferred. \[from, to, kitty_id\]
 		Transferred(T::AccountId, T::AccountId, T::KittyIndex),
-		/// A Kitty was sucessfully bought. \[buyer, seller, kitty_id, bid_price\]
-		Bought(T::AccountId, T::AccountId, T::KittyIndex, BalanceOf<T>),
+		/// A Kitty was sucessfully bought at its asking price, the price of the sale being the
+		/// amount paid. \[seller, buyer, kitty_id, ask_price, sale\]
+		Bought(T::AccountId, T::AccountId, T::KittyIndex, BalanceOf<T>, SaleOf<T>),
+		/// An auction was created. \[seller, kitty_id, reserve, end\]
+		AuctionCreated(T::AccountId, T::KittyIndex, BalanceOf<T>, T::BlockNumber),
+		/// A bid was placed in an auction. \[bidder, kitty_id, amount\]
+		BidPlaced(T::AccountId, T::KittyIndex, BalanceOf<T>),
+		/// An auction was settled, with the winner and the sale if there was any bid.
+		/// \[seller, kitty_id, winning_bid\]
+		AuctionSettled(T::AccountId, T::KittyIndex, Option<(T::AccountId, SaleOf<T>)>),
+		/// A kitty was offered as a sire, or the offer was withdrawn.
+		/// \[sire_owner, kitty_id, fee\]
+		SireOffered(T::AccountId, T::KittyIndex, Option<BalanceOf<T>>),
+		/// A kitty was bred with a sire of another account, which is paid the fee reserved from
+		/// the breeder once the kitty is revealed.
+		/// \[breeder, sire_owner, sire_id, fee\]
+		BredWithSire(T::AccountId, T::AccountId, T::KittyIndex, BalanceOf<T>),
+		/// An account was approved to transfer a kitty, or the approval was cleared.
+		/// \[owner, delegate, kitty_id\]
+		Approval(T::AccountId, Option<T::AccountId>, T::KittyIndex),
+		/// An operator was approved to transfer all kitties of an owner, or no longer is.
+		/// \[owner, operator, approved\]
+		ApprovalForAll(T::AccountId, T::AccountId, bool),
+		/// An offer to buy a kitty was made, the amount is reserved until it is accepted,
+		/// withdrawn or released. \[buyer, kitty_id, amount, expiry\]
+		OfferMade(T::AccountId, T::KittyIndex, BalanceOf<T>, T::BlockNumber),
+		/// An offer was withdrawn by the buyer. \[buyer, kitty_id, amount\]
+		OfferWithdrawn(T::AccountId, T::KittyIndex, BalanceOf<T>),
+		/// An offer was accepted and the kitty handed over to the buyer.
+		/// \[seller, buyer, kitty_id, sale\]
+		OfferAccepted(T::AccountId, T::AccountId, T::KittyIndex, SaleOf<T>),
+		/// An offer was released because it expired, was outbid or the kitty changed hands.
+		/// \[buyer, kitty_id, amount\]
+		OfferReleased(T::AccountId, T::KittyIndex, BalanceOf<T>),
+		/// A kitty was burned by its owner. \[owner, kitty_id\]
+		Burned(T::AccountId, T::KittyIndex),
+		/// A kitty was created or bred, and is revealed at the given block. \[owner, reveal_block\]
+		MintRequested(T::AccountId, T::BlockNumber),
+		/// A created or bred kitty could not be revealed, and any deposit or sire fee was
+		/// released. \[owner\]
+		MintCancelled(T::AccountId),
+	}
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		fn on_runtime_upgrade() -> Weight {
+			crate::migrations::v1::migrate::<T>()
+				.saturating_add(crate::migrations::v2::migrate::<T>())
+				.saturating_add(crate::migrations::v3::migrate::<T>())
+				.saturating_add(crate::migrations::v4::migrate::<T>())
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn pre_upgrade() -> Result<(), &'static str> {
+			crate::migrations::v1::pre_upgrade::<T>()?;
+			crate::migrations::v2::pre_upgrade::<T>()?;
+			crate::migrations::v3::pre_upgrade::<T>()?;
+			crate::migrations::v4::pre_upgrade::<T>()
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade() -> Result<(), &'static str> {
+			crate::migrations::v1::post_upgrade::<T>()?;
+			crate::migrations::v2::post_upgrade::<T>()?;
+			crate::migrations::v3::post_upgrade::<T>()?;
+			crate::migrations::v4::post_upgrade::<T>()
+		}
+
+		// Reveal every kitty due at this block, then settle every auction that ends at it.
+		fn on_initialize(now: T::BlockNumber) -> Weight {
+			let minting = <PendingMints<T>>::take(now);
+			let mints = minting.len() as u32;
+
+			for pending in minting {
+				Self::reveal_mint(pending);
+			}
+
+			let ending = <AuctionsEndingAt<T>>::take(now);
+			let count = ending.len() as u32;
+
+			for kitty_id in ending {
+				Self::settle_auction(&kitty_id);
+			}
+
+			// Each kitty changing hands releases its offers
+			T::WeightInfo::reveal_mints(mints)
+				.saturating_add(T::WeightInfo::on_initialize(count))
+				.saturating_add(
+					T::WeightInfo::release_offers(T::MaxOffersPerKitty::get()).saturating_mul(count as Weight)
+				)
+		}
+
+		// Release the expired offers with the weight left in the block.
+		fn on_idle(now: T::BlockNumber, remaining_weight: Weight) -> Weight {
+			Self::sweep_expired_offers(now, remaining_weight)
+		}
 	}
 
 	#[pallet::call]
 	impl<T: Config> Pallet<T> {
 
-		// create_kitty: create a kitty and update the Storage
-		// #[pallet::weight(1_000)]
+		// create_kitty: create a kitty, with the royalty the sender gets on every sale of the
+		// kitty. Its dna is only revealed `MintRevealDelay` blocks later, from the randomness of
+		// that block, so the sender cannot pick a favourable one.
 		#[pallet::weight(T::WeightInfo::create_kitty())]
+		#[transactional]
 		pub fn create_kitty(
-			origin: OriginFor<T>
+			origin: OriginFor<T>,
+			royalty: Permill,
 		) -> DispatchResultWithPostInfo {
 			// checks the origin is signed
 			let sender = ensure_signed(origin)?;
 
-			// Check the buyer has enough minting balance
+			ensure!(royalty <= T::MaxRoyalty::get(), <Error<T>>::RoyaltyTooHigh);
+
+			// Verify the sender has the capacity to receive one more kitty
+			ensure!(Self::owned_count(&sender) < T::MaxKittyOwned::get(), <Error<T>>::ExceedMaxKittyOwned);
+
+			// schedule the reveal, this may fail so do it first
 			let min_balance = <BalanceOf<T>>::from(T::MinKittyMintingPrice::get());
-			T::Currency::reserve(&sender, min_balance)?;
+			let reveal_block = Self::schedule_mint(PendingMint {
+				owner: sender.clone(),
+				deposit: min_balance,
+				royalty,
+				parents: None,
+				sire_fee: None,
+			})?;
 
-			// calls a private mint() function
-			let kitty_id = Self::mint(&sender, None, None)?;
+			// Check the buyer has enough minting balance
+			T::Currency::reserve(&sender, min_balance)?;
 
-			// emits log and event that create a kitty successfully
-			log::info!("A kitty is born with ID: {:?}.", kitty_id);
-			Self::deposit_event(Event::Created(sender, kitty_id));
+			// emits log and event that a kitty is on its way
+			log::info!("A kitty is revealed at block {:?}.", reveal_block);
+			Self::deposit_event(Event::MintRequested(sender, reveal_block));
 
 			Ok(().into())
 		}
 
 		// set_price: set the price of the kitty by its owner
-		#[pallet::weight(1_000)]
+		#[pallet::weight(T::WeightInfo::set_price())]
 		pub fn set_price(
 			origin: OriginFor<T>,
 			kitty_id: T::KittyIndex,
@@ -219,22 +627,12 @@ pub mod pallet {
 			// checks the origin is signed
 			let sender = ensure_signed(origin)?;
 
-			// checks the ownership of the specific kitty
-			ensure!(Self::is_kitty_owner(&kitty_id, &sender)?, <Error<T>>::NotKittyOwner);
-
-			// Get the kitty object and modify the price
-			let mut kitty = Self::kitties(&kitty_id).ok_or(<Error<T>>::KittyNotExist)?;
-			kitty.price = new_price.clone();
-			<Kitties<T>>::insert(&kitty_id, kitty);
-
-			// Deposit a "PriceSet" event.
-			Self::deposit_event(Event::PriceSet(sender, kitty_id, new_price));
-
-			Ok(())
+			Self::do_set_price(&sender, kitty_id, new_price)
 		}
 
 		// transfer: transfer the ownership of a kitty
-		#[pallet::weight(1_000)]
+		#[pallet::weight(T::WeightInfo::transfer()
+			.saturating_add(T::WeightInfo::release_offers(T::MaxOffersPerKitty::get())))]
 		pub fn transfer(
 			origin: OriginFor<T>,
 			kitty_id: T::KittyIndex,
@@ -249,9 +647,11 @@ pub mod pallet {
 			// Verify the kitty is not transferring back to its owner.
 			ensure!(sender != receiver, <Error<T>>::TransferToSelf);
 
+			// Verify the kitty is not locked in an auction
+			ensure!(!<Auctions<T>>::contains_key(&kitty_id), <Error<T>>::KittyInAuction);
+
 			// Verify the recipient has the capacity to receive one more kitty
-			let to_owned = <KittiesOwned<T>>::get(&receiver);
-			ensure!((to_owned.len() as u32) < T::MaxKittyOwned::get(), <Error<T>>::ExceedMaxKittyOwned);
+			ensure!(Self::owned_count(&receiver) < T::MaxKittyOwned::get(), <Error<T>>::ExceedMaxKittyOwned);
 
 			// calls a private transfer_kitty_to() function
 			Self::transfer_kitty_to(&kitty_id, &receiver)?;
@@ -262,12 +662,15 @@ pub mod pallet {
 			Ok(())
 		}
 
-		// buy_kitty: buy a on-selling kitty
-		#[pallet::weight(1_000)]
+		// buy_kitty: buy a on-selling kitty at its asking price, which must not be above
+		// `max_price` if given, so the seller cannot raise it before the purchase goes through
+		#[pallet::weight(T::WeightInfo::buy_kitty()
+			.saturating_add(T::WeightInfo::release_offers(T::MaxOffersPerKitty::get())))]
+		#[transactional]
 		pub fn buy_kitty(
 			origin: OriginFor<T>,
 			kitty_id: T::KittyIndex,
-			bid_price: BalanceOf<T>
+			max_price: Option<BalanceOf<T>>
 		) -> DispatchResult {
 			// checks the origin is signed
 			let buyer = ensure_signed(origin)?;
@@ -275,11 +678,10 @@ pub mod pallet {
 			// Get the kitty object
 			let kitty = Self::kitties(&kitty_id).ok_or(<Error<T>>::KittyNotExist)?;
 
-			// Check the kitty is for sale and the kitty ask price <= bid_price
-			if let Some(ask_price) = kitty.price {
-				ensure!(ask_price <= bid_price, <Error<T>>::KittyBidPriceTooLow);
-			} else {
-				Err(<Error<T>>::KittyNotForSale)?;
+			// Check the kitty is for sale and the kitty ask price <= max_price
+			let ask_price = kitty.price.ok_or(<Error<T>>::KittyNotForSale)?;
+			if let Some(max_price) = max_price {
+				ensure!(ask_price <= max_price, <Error<T>>::KittyBidPriceTooLow);
 			}
 
 			// checks the ownership of the kitty is not from buyer himself
@@ -287,34 +689,37 @@ pub mod pallet {
 			ensure!(buyer != seller, <Error<T>>::BuyerIsKittyOwner);
 
 			// Check the buyer has enough free balance
-			ensure!(T::Currency::free_balance(&buyer) >= bid_price, <Error<T>>::NotEnoughBalance);
+			ensure!(T::Currency::free_balance(&buyer) >= ask_price, <Error<T>>::NotEnoughBalance);
 
 			// Verify the buyer has the capacity to receive one more kitty
-			let to_owned = <KittiesOwned<T>>::get(&buyer);
-			ensure!((to_owned.len() as u32) < T::MaxKittyOwned::get(), <Error<T>>::ExceedMaxKittyOwned);
+			ensure!(Self::owned_count(&buyer) < T::MaxKittyOwned::get(), <Error<T>>::ExceedMaxKittyOwned);
 
-			// Transfer the amount from buyer to seller
-			T::Currency::transfer(&buyer, &seller, bid_price, ExistenceRequirement::KeepAlive)?;
+			// Pay the amount from buyer to the treasury, the creator and the seller
+			let sale = Self::pay_sale(&kitty_id, &seller, &buyer, ask_price, ExistenceRequirement::KeepAlive)?;
 
 			// calls a private transfer_kitty_to() function
 			Self::transfer_kitty_to(&kitty_id, &buyer)?;
 
 			// Deposit a "Bought" event.
-			Self::deposit_event(Event::Bought(seller, buyer, kitty_id, bid_price));
+			Self::deposit_event(Event::Bought(seller, buyer, kitty_id, ask_price, sale));
 
 			Ok(())
 		}
 
-		// breed_kitty: breed a baby kitty by two kitties owned with different gender
-		#[pallet::weight(1_000)]
+		// breed_kitty: breed a baby kitty by two kitties owned with different gender. Like a
+		// created kitty, its dna is only revealed `MintRevealDelay` blocks later.
+		#[pallet::weight(T::WeightInfo::breed_kitty())]
 		pub fn breed_kitty(
 			origin: OriginFor<T>,
 			kitty_id1: T::KittyIndex,
 			kitty_id2: T::KittyIndex,
+			royalty: Permill,
 		) -> DispatchResult {
 			// checks the origin is signed
 			let owner = ensure_signed(origin)?;
 
+			ensure!(royalty <= T::MaxRoyalty::get(), <Error<T>>::RoyaltyTooHigh);
+
 			// The kitty id cannot be the same
 			ensure!(kitty_id1 != kitty_id2, <Error<T>>::SameParentKittyId);
 
@@ -323,22 +728,462 @@ pub mod pallet {
 			let parent2 = Self::kitties(&kitty_id2).ok_or(<Error<T>>::KittyNotExist)?;
 
 			// checks the ownership of the kitty
-			let owner_kitty1 = parent1.owner;
-			ensure!(owner == owner_kitty1, <Error<T>>::NotKittyOwner);
+			ensure!(owner == parent1.owner, <Error<T>>::NotKittyOwner);
+			ensure!(owner == parent2.owner, <Error<T>>::NotKittyOwner);
 
-			let owner_kitty2 = parent2.owner;
-			ensure!(owner == owner_kitty2, <Error<T>>::NotKittyOwner);
+			let reveal_block = Self::do_breed(&owner, royalty, (kitty_id1, parent1), (kitty_id2, parent2), None)?;
 
-			// check the genders of the parents
-			let gender_kitty1 = parent1.gender;
-			let gender_kitty2 = parent2.gender;
-			ensure!(gender_kitty1 != gender_kitty2, <Error<T>>::SameParentGender);
+			// Deposit a "MintRequested" event.
+			Self::deposit_event(Event::MintRequested(owner, reveal_block));
+
+			Ok(())
+		}
+
+		// offer_sire: offer a kitty as a sire to other accounts for a fee, or withdraw the offer
+		#[pallet::weight(T::WeightInfo::offer_sire())]
+		pub fn offer_sire(
+			origin: OriginFor<T>,
+			kitty_id: T::KittyIndex,
+			fee: Option<BalanceOf<T>>,
+		) -> DispatchResult {
+			// checks the origin is signed
+			let sender = ensure_signed(origin)?;
+
+			// checks the ownership of the specific kitty
+			ensure!(Self::is_kitty_owner(&kitty_id, &sender)?, <Error<T>>::NotKittyOwner);
+
+			// A kitty under the hammer cannot be rented out
+			ensure!(!<Auctions<T>>::contains_key(&kitty_id), <Error<T>>::KittyInAuction);
+
+			match fee {
+				Some(fee) => <SireOffers<T>>::insert(&kitty_id, fee),
+				None => <SireOffers<T>>::remove(&kitty_id),
+			}
+
+			// Deposit a "SireOffered" event.
+			Self::deposit_event(Event::SireOffered(sender, kitty_id, fee));
+
+			Ok(())
+		}
+
+		// breed_with_sire: breed an owned kitty with a sire offered by another account, reserving
+		// its fee. The baby kitty goes to the owner of `my_kitty`, who gets `royalty` on its sales,
+		// once revealed `MintRevealDelay` blocks later. The fee is paid to the sire owner at the
+		// reveal, or released if the kitty cannot be revealed.
+		#[pallet::weight(T::WeightInfo::breed_with_sire())]
+		#[transactional]
+		pub fn breed_with_sire(
+			origin: OriginFor<T>,
+			my_kitty: T::KittyIndex,
+			sire_id: T::KittyIndex,
+			royalty: Permill,
+		) -> DispatchResult {
+			// checks the origin is signed
+			let breeder = ensure_signed(origin)?;
+
+			ensure!(royalty <= T::MaxRoyalty::get(), <Error<T>>::RoyaltyTooHigh);
+
+			// The kitty id cannot be the same
+			ensure!(my_kitty != sire_id, <Error<T>>::SameParentKittyId);
+
+			let dam = Self::kitties(&my_kitty).ok_or(<Error<T>>::KittyNotExist)?;
+			let sire = Self::kitties(&sire_id).ok_or(<Error<T>>::KittyNotExist)?;
+			let fee = Self::sire_offers(&sire_id).ok_or(<Error<T>>::SireNotOffered)?;
+
+			// checks the breeder owns the dam but not the sire
+			ensure!(breeder == dam.owner, <Error<T>>::NotKittyOwner);
+			let sire_owner = sire.owner.clone();
+			ensure!(breeder != sire_owner, <Error<T>>::BuyerIsKittyOwner);
+
+			// Check the breeder has enough free balance
+			ensure!(T::Currency::free_balance(&breeder) >= fee, <Error<T>>::NotEnoughBalance);
+
+			let sire_fee = Some((sire_owner.clone(), fee));
+			let reveal_block = Self::do_breed(&breeder, royalty, (my_kitty, dam), (sire_id, sire), sire_fee)?;
+
+			// Reserve the fee until the reveal
+			T::Currency::reserve(&breeder, fee)?;
+
+			// Deposit a "MintRequested" and a "BredWithSire" event.
+			Self::deposit_event(Event::MintRequested(breeder.clone(), reveal_block));
+			Self::deposit_event(Event::BredWithSire(breeder, sire_owner, sire_id, fee));
+
+			Ok(())
+		}
+
+		// create_auction: put a kitty under the hammer until `duration` blocks from now
+		#[pallet::weight(T::WeightInfo::create_auction(T::MaxAuctionsPerBlock::get()))]
+		pub fn create_auction(
+			origin: OriginFor<T>,
+			kitty_id: T::KittyIndex,
+			reserve: BalanceOf<T>,
+			duration: T::BlockNumber,
+		) -> DispatchResult {
+			// checks the origin is signed
+			let seller = ensure_signed(origin)?;
+
+			// checks the ownership of the specific kitty
+			ensure!(Self::is_kitty_owner(&kitty_id, &seller)?, <Error<T>>::NotKittyOwner);
+			ensure!(!<Auctions<T>>::contains_key(&kitty_id), <Error<T>>::KittyInAuction);
+
+			// checks the auction duration
+			ensure!(
+				!duration.is_zero() && duration <= T::MaxAuctionDuration::get(),
+				<Error<T>>::InvalidAuctionDuration
+			);
+			let end = <frame_system::Pallet<T>>::block_number() + duration;
+
+			// schedule the settlement, this may fail so do it first
+			<AuctionsEndingAt<T>>::try_mutate(&end, |ending| {
+				ending.try_push(kitty_id)
+			}).map_err(|_| <Error<T>>::TooManyAuctionsEnding)?;
+
+			// A kitty under the hammer cannot be bought at a fixed price, nor rented out
+			let mut kitty = Self::kitties(&kitty_id).ok_or(<Error<T>>::KittyNotExist)?;
+			kitty.price = None;
+			<Kitties<T>>::insert(&kitty_id, kitty);
+			<SireOffers<T>>::remove(&kitty_id);
+
+			<Auctions<T>>::insert(&kitty_id, Auction {
+				seller: seller.clone(),
+				reserve,
+				end,
+				best_bid: None,
+			});
+
+			// Deposit a "AuctionCreated" event.
+			Self::deposit_event(Event::AuctionCreated(seller, kitty_id, reserve, end));
+
+			Ok(())
+		}
+
+		// bid: place a bid in a running auction, the amount is reserved until outbid or settled
+		#[pallet::weight(T::WeightInfo::bid())]
+		#[transactional]
+		pub fn bid(
+			origin: OriginFor<T>,
+			kitty_id: T::KittyIndex,
+			amount: BalanceOf<T>,
+		) -> DispatchResult {
+			// checks the origin is signed
+			let bidder = ensure_signed(origin)?;
+
+			let mut auction = Self::auctions(&kitty_id).ok_or(<Error<T>>::AuctionNotExist)?;
+
+			// checks the auction is still running
+			ensure!(
+				<frame_system::Pallet<T>>::block_number() < auction.end,
+				<Error<T>>::AuctionEnded
+			);
+
+			// checks the seller is not bidding on his own kitty
+			ensure!(bidder != auction.seller, <Error<T>>::BuyerIsKittyOwner);
+
+			// checks the bid beats both the reserve and the current highest bid
+			ensure!(amount >= auction.reserve, <Error<T>>::BidBelowReserve);
+			if let Some((_, best)) = &auction.best_bid {
+				ensure!(amount > *best, <Error<T>>::BidNotHighEnough);
+			}
+
+			// Verify the bidder has the capacity to receive one more kitty
+			ensure!(Self::owned_count(&bidder) < T::MaxKittyOwned::get(), <Error<T>>::ExceedMaxKittyOwned);
+
+			// Release the reserve of the outbid bidder
+			if let Some((prev_bidder, prev_amount)) = auction.best_bid.take() {
+				T::Currency::unreserve(&prev_bidder, prev_amount);
+			}
+
+			// Lock the bid of the new highest bidder
+			T::Currency::reserve(&bidder, amount)?;
+
+			auction.best_bid = Some((bidder.clone(), amount));
+			<Auctions<T>>::insert(&kitty_id, auction);
+
+			// Deposit a "BidPlaced" event.
+			Self::deposit_event(Event::BidPlaced(bidder, kitty_id, amount));
+
+			Ok(())
+		}
+
+		// approve: approve an account to transfer a kitty, or clear the approval
+		#[pallet::weight(T::WeightInfo::approve())]
+		pub fn approve(
+			origin: OriginFor<T>,
+			kitty_id: T::KittyIndex,
+			delegate: Option<T::AccountId>,
+		) -> DispatchResult {
+			// checks the origin is signed
+			let sender = ensure_signed(origin)?;
+
+			// The owner or one of its operators may approve
+			let owner = Self::kitties(&kitty_id).ok_or(<Error<T>>::KittyNotExist)?.owner;
+			ensure!(
+				sender == owner || Self::is_approved_for_all(&owner, &sender),
+				<Error<T>>::NotKittyOwner
+			);
+
+			match &delegate {
+				Some(delegate) => <Approvals<T>>::insert(&kitty_id, delegate),
+				None => <Approvals<T>>::remove(&kitty_id),
+			}
+
+			// Deposit an "Approval" event.
+			Self::deposit_event(Event::Approval(owner, delegate, kitty_id));
+
+			Ok(())
+		}
+
+		// set_approval_for_all: approve an operator to transfer all kitties of the sender, or
+		// revoke the approval
+		#[pallet::weight(T::WeightInfo::set_approval_for_all())]
+		pub fn set_approval_for_all(
+			origin: OriginFor<T>,
+			operator: T::AccountId,
+			approved: bool,
+		) -> DispatchResult {
+			// checks the origin is signed
+			let sender = ensure_signed(origin)?;
+
+			if approved {
+				<OperatorApprovals<T>>::insert(&sender, &operator, ());
+			} else {
+				<OperatorApprovals<T>>::remove(&sender, &operator);
+			}
+
+			// Deposit an "ApprovalForAll" event.
+			Self::deposit_event(Event::ApprovalForAll(sender, operator, approved));
+
+			Ok(())
+		}
+
+		// transfer_from: transfer a kitty of `from` as its owner, its approved account or one of
+		// its operators
+		#[pallet::weight(T::WeightInfo::transfer_from()
+			.saturating_add(T::WeightInfo::release_offers(T::MaxOffersPerKitty::get())))]
+		pub fn transfer_from(
+			origin: OriginFor<T>,
+			from: T::AccountId,
+			to: T::AccountId,
+			kitty_id: T::KittyIndex,
+		) -> DispatchResult {
+			// checks the origin is signed
+			let sender = ensure_signed(origin)?;
+
+			// checks `from` owns the kitty and the sender may move it
+			ensure!(Self::is_kitty_owner(&kitty_id, &from)?, <Error<T>>::NotKittyOwner);
+			ensure!(
+				sender == from ||
+					Self::approvals(&kitty_id).as_ref() == Some(&sender) ||
+					Self::is_approved_for_all(&from, &sender),
+				<Error<T>>::NotApproved
+			);
+
+			// Verify the kitty is not transferring back to its owner.
+			ensure!(from != to, <Error<T>>::TransferToSelf);
+
+			// Verify the kitty is not locked in an auction
+			ensure!(!<Auctions<T>>::contains_key(&kitty_id), <Error<T>>::KittyInAuction);
+
+			// Verify the recipient has the capacity to receive one more kitty
+			ensure!(Self::owned_count(&to) < T::MaxKittyOwned::get(), <Error<T>>::ExceedMaxKittyOwned);
+
+			Self::transfer_kitty_to(&kitty_id, &to)?;
+
+			// Deposit a "Transferred" event.
+			Self::deposit_event(Event::Transferred(from, to, kitty_id));
+
+			Ok(())
+		}
+
+		// transfer_batch: transfer several kitties, each to its receiver, all or none of them
+		#[pallet::weight(T::WeightInfo::transfer_batch(transfers.len() as u32).saturating_add(
+			T::WeightInfo::release_offers(T::MaxOffersPerKitty::get()).saturating_mul(transfers.len() as Weight)
+		))]
+		#[transactional]
+		pub fn transfer_batch(
+			origin: OriginFor<T>,
+			transfers: BoundedVec<(T::KittyIndex, T::AccountId), T::MaxBatchSize>,
+		) -> DispatchResult {
+			// checks the origin is signed
+			let sender = ensure_signed(origin)?;
+
+			// Verify every recipient has the capacity to receive all of its kitties at once
+			let mut received = BTreeMap::<T::AccountId, u32>::new();
+			for (_, receiver) in transfers.iter() {
+				*received.entry(receiver.clone()).or_default() += 1;
+			}
+			for (receiver, count) in received.iter() {
+				ensure!(
+					Self::owned_count(receiver).saturating_add(*count) <= T::MaxKittyOwned::get(),
+					<Error<T>>::ExceedMaxKittyOwned
+				);
+			}
+
+			for (kitty_id, receiver) in transfers {
+				// Same checks as `transfer`, the capacity aside
+				ensure!(Self::is_kitty_owner(&kitty_id, &sender)?, <Error<T>>::NotKittyOwner);
+				ensure!(sender != receiver, <Error<T>>::TransferToSelf);
+				ensure!(!<Auctions<T>>::contains_key(&kitty_id), <Error<T>>::KittyInAuction);
+
+				Self::transfer_kitty_to(&kitty_id, &receiver)?;
+
+				// Deposit a "Transferred" event.
+				Self::deposit_event(Event::Transferred(sender.clone(), receiver, kitty_id));
+			}
+
+			Ok(())
+		}
+
+		// set_price_batch: set the price of several kitties, all or none of them
+		#[pallet::weight(T::WeightInfo::set_price_batch(prices.len() as u32))]
+		#[transactional]
+		pub fn set_price_batch(
+			origin: OriginFor<T>,
+			prices: BoundedVec<(T::KittyIndex, Option<BalanceOf<T>>), T::MaxBatchSize>,
+		) -> DispatchResult {
+			// checks the origin is signed
+			let sender = ensure_signed(origin)?;
+
+			for (kitty_id, new_price) in prices {
+				Self::do_set_price(&sender, kitty_id, new_price)?;
+			}
+
+			Ok(())
+		}
+
+		// make_offer: offer to buy a kitty until `expiry`, reserving the amount. A new offer of
+		// the same buyer replaces the previous one.
+		#[pallet::weight(T::WeightInfo::make_offer(T::MaxOffersPerKitty::get()))]
+		#[transactional]
+		pub fn make_offer(
+			origin: OriginFor<T>,
+			kitty_id: T::KittyIndex,
+			amount: BalanceOf<T>,
+			expiry: T::BlockNumber,
+		) -> DispatchResult {
+			// checks the origin is signed
+			let buyer = ensure_signed(origin)?;
+
+			// checks the kitty is not the buyer's own
+			let kitty = Self::kitties(&kitty_id).ok_or(<Error<T>>::KittyNotExist)?;
+			ensure!(buyer != kitty.owner, <Error<T>>::BuyerIsKittyOwner);
+
+			// A kitty under the hammer is bought by bidding
+			ensure!(!<Auctions<T>>::contains_key(&kitty_id), <Error<T>>::KittyInAuction);
+
+			let now = <frame_system::Pallet<T>>::block_number();
+			ensure!(expiry > now, <Error<T>>::OfferExpired);
+
+			// Release the expired offers, and the previous offer of the buyer
+			let mut offers = Self::offers(&kitty_id);
+			Self::release_offers(&kitty_id, &mut offers, |offer| offer.expiry <= now || offer.buyer == buyer);
+
+			// A full book only takes an offer above the lowest one, which is released
+			if offers.len() as u32 >= T::MaxOffersPerKitty::get() {
+				let lowest = offers.iter()
+					.enumerate()
+					.min_by_key(|(_, offer)| offer.amount)
+					.map(|(index, _)| index)
+					.ok_or(<Error<T>>::OfferTooLow)?;
+				ensure!(amount > offers[lowest].amount, <Error<T>>::OfferTooLow);
+				let outbid = offers.remove(lowest);
+				T::Currency::unreserve(&outbid.buyer, outbid.amount);
+				Self::deposit_event(Event::OfferReleased(outbid.buyer, kitty_id, outbid.amount));
+			}
+
+			// Lock the amount of the offer
+			T::Currency::reserve(&buyer, amount)?;
+
+			offers.try_push(Offer { buyer: buyer.clone(), amount, expiry })
+				.map_err(|_| <Error<T>>::OfferTooLow)?;
+			Self::put_offers(&kitty_id, offers);
+
+			// Deposit an "OfferMade" event.
+			Self::deposit_event(Event::OfferMade(buyer, kitty_id, amount, expiry));
+
+			Ok(())
+		}
+
+		// accept_offer: sell a kitty to the buyer of an open offer, for the reserved amount
+		#[pallet::weight(T::WeightInfo::accept_offer(T::MaxOffersPerKitty::get()))]
+		#[transactional]
+		pub fn accept_offer(
+			origin: OriginFor<T>,
+			kitty_id: T::KittyIndex,
+			buyer: T::AccountId,
+		) -> DispatchResult {
+			// checks the origin is signed
+			let seller = ensure_signed(origin)?;
+
+			// checks the ownership of the specific kitty
+			ensure!(Self::is_kitty_owner(&kitty_id, &seller)?, <Error<T>>::NotKittyOwner);
+
+			// Verify the kitty is not locked in an auction
+			ensure!(!<Auctions<T>>::contains_key(&kitty_id), <Error<T>>::KittyInAuction);
+
+			let mut offers = Self::offers(&kitty_id);
+			let index = offers.iter()
+				.position(|offer| offer.buyer == buyer)
+				.ok_or(<Error<T>>::OfferNotExist)?;
+			let offer = offers.remove(index);
+			ensure!(<frame_system::Pallet<T>>::block_number() < offer.expiry, <Error<T>>::OfferExpired);
+
+			// Verify the buyer has the capacity to receive one more kitty
+			ensure!(Self::owned_count(&buyer) < T::MaxKittyOwned::get(), <Error<T>>::ExceedMaxKittyOwned);
+
+			Self::put_offers(&kitty_id, offers);
+
+			// Pay with the reserved amount and hand the kitty over, which releases the other offers
+			let sale = Self::sell_from_reserve(&kitty_id, &seller, &buyer, offer.amount)?;
+
+			// Deposit an "OfferAccepted" event.
+			Self::deposit_event(Event::OfferAccepted(seller, buyer, kitty_id, sale));
+
+			Ok(())
+		}
+
+		// withdraw_offer: withdraw the offer of the sender on a kitty, releasing the amount
+		#[pallet::weight(T::WeightInfo::withdraw_offer(T::MaxOffersPerKitty::get()))]
+		pub fn withdraw_offer(
+			origin: OriginFor<T>,
+			kitty_id: T::KittyIndex,
+		) -> DispatchResult {
+			// checks the origin is signed
+			let buyer = ensure_signed(origin)?;
+
+			let mut offers = Self::offers(&kitty_id);
+			let index = offers.iter()
+				.position(|offer| offer.buyer == buyer)
+				.ok_or(<Error<T>>::OfferNotExist)?;
+			let offer = offers.remove(index);
+			Self::put_offers(&kitty_id, offers);
 
-			let new_dna = Self::breed_dna(parent1.dna, parent2.dna)?;
-			let new_kitty_id = Self::mint(&owner, Some(new_dna), None)?;
+			T::Currency::unreserve(&buyer, offer.amount);
 
-			// Deposit a "Created" event.
-			Self::deposit_event(Event::Created(owner, new_kitty_id));
+			// Deposit an "OfferWithdrawn" event.
+			Self::deposit_event(Event::OfferWithdrawn(buyer, kitty_id, offer.amount));
+
+			Ok(())
+		}
+
+		// burn: destroy a kitty, releasing its minting deposit to whoever paid it. The kitties
+		// created before storage version 3 have no deposit recorded, which stays reserved.
+		#[pallet::weight(T::WeightInfo::burn()
+			.saturating_add(T::WeightInfo::release_offers(T::MaxOffersPerKitty::get())))]
+		pub fn burn(
+			origin: OriginFor<T>,
+			kitty_id: T::KittyIndex,
+		) -> DispatchResult {
+			// checks the origin is signed
+			let sender = ensure_signed(origin)?;
+
+			// checks the ownership of the specific kitty
+			ensure!(Self::is_kitty_owner(&kitty_id, &sender)?, <Error<T>>::NotKittyOwner);
+
+			Self::do_burn(&kitty_id)?;
+
+			// Deposit a "Burned" event.
+			Self::deposit_event(Event::Burned(sender, kitty_id));
 
 			Ok(())
 		}
@@ -348,50 +1193,296 @@ pub mod pallet {
 	impl<T: Config> Pallet<T> {
 		// Function to randomly generate gender for Kitty struct
 		fn gen_gender() -> Gender {
-			let random = T::KittyRandomness::random(&b"gender"[..]).0;
-			match random.as_ref()[0] % 2 {
+			match Self::gen_random(&b"gender"[..])[0] % 2 {
 				0 => Gender::Male,
 				_ => Gender::Female,
 			}
 		}
 
-		// Funtion to randomly generate DNA for kitty struct
-		fn gen_dna() -> [u8; 16] {
+		// Function to generate random bytes for `subject`, distinct for every kitty even within
+		// an extrinsic: the kitty nonce changes with every mint.
+		fn gen_random(subject: &[u8]) -> [u8; 16] {
 			let payload = (
-				T::KittyRandomness::random(&b"dna"[..]).0,
+				T::KittyRandomness::random(subject).0,
 				<frame_system::Pallet<T>>::block_number(),
+				<frame_system::Pallet<T>>::extrinsic_index(),
+				Self::kitty_nonce(),
 			);
 			payload.using_encoded(blake2_128)
 		}
 
-		// Helper to mint a Kitty.
+		// Funtion to randomly generate a generation 0 DNA for kitty struct
+		fn gen_dna() -> [u8; 16] {
+			genetics::from_random(Self::gen_random(&b"dna"[..]))
+		}
+
+		// Helper to mint a Kitty, a bred kitty starts with a cooldown.
 		pub fn mint(
 			owner: &T::AccountId,
 			dna: Option<[u8; 16]>,
 			gender: Option<Gender>,
+			parents: Option<(T::KittyIndex, T::KittyIndex)>,
 		) -> Result<T::KittyIndex, Error<T>> {
-			let kitty = Kitty::<T> {
-				dna: dna.unwrap_or_else(Self::gen_dna),
+			let kitty = Self::new_kitty(owner, dna, gender, parents);
+
+			// Gen an id of the kitty, unique even among identical kitties
+			let nonce = Self::kitty_nonce();
+			let kitty_id = T::KittyHashing::hash_of(&(&kitty, nonce));
+
+			Self::insert_kitty(kitty_id, kitty)?;
+			<KittyNonce<T>>::put(nonce.wrapping_add(1));
+			Ok(kitty_id)
+		}
+
+		// Build a kitty born in the current block, with random dna and gender unless given.
+		pub(crate) fn new_kitty(
+			owner: &T::AccountId,
+			dna: Option<[u8; 16]>,
+			gender: Option<Gender>,
+			parents: Option<(T::KittyIndex, T::KittyIndex)>,
+		) -> Kitty<T> {
+			// A kitty without parents is generation 0, a bred one is one generation younger than
+			// its older parent. The dna records the same generation, whatever was given.
+			let generation = parents.map_or(0, |(parent1, parent2)| {
+				let generation = |kitty_id: T::KittyIndex| Self::kitties(kitty_id).map_or(0, |kitty| kitty.generation);
+				generation(parent1).max(generation(parent2)).saturating_add(1)
+			});
+			let mut dna = dna.unwrap_or_else(Self::gen_dna);
+			genetics::set_generation(&mut dna, generation);
+			let birth_block = <frame_system::Pallet<T>>::block_number();
+			let cooldown_end = match parents {
+				Some(_) => birth_block.saturating_add(Self::breeding_cooldown(generation)),
+				None => birth_block,
+			};
+
+			Kitty::<T> {
+				dna,
 				price: None,
 				gender: gender.unwrap_or_else(Self::gen_gender),
 				owner: owner.clone(),
-			};
+				parents,
+				generation,
+				birth_block,
+				cooldown_end,
+			}
+		}
 
-			// Gen an id of the kitty
-			let kitty_id = T::KittyHashing::hash_of(&kitty);
+		// Store a new kitty under `kitty_id` and add it to the kitties of its owner.
+		pub(crate) fn insert_kitty(kitty_id: T::KittyIndex, kitty: Kitty<T>) -> Result<(), Error<T>> {
+			ensure!(
+				!<Kitties<T>>::contains_key(&kitty_id) && !<BurnedKitties<T>>::contains_key(&kitty_id),
+				<Error<T>>::DuplicateKittyId
+			);
 
 			// Performs this operation first as it may fail
 			let new_cnt = Self::kitty_cnt().checked_add(1)
 				.ok_or(<Error<T>>::KittyCntOverflow)?;
 
 			// Performs this operation first because as it may fail
-			<KittiesOwned<T>>::try_mutate(&owner, |kitty_vec| {
-				kitty_vec.try_push(kitty_id)
-			}).map_err(|_| <Error<T>>::ExceedMaxKittyOwned)?;
+			let owner = &kitty.owner;
+			let owned = Self::owned_count(owner);
+			ensure!(owned < T::MaxKittyOwned::get(), <Error<T>>::ExceedMaxKittyOwned);
 
+			<KittiesOwned<T>>::insert(owner, kitty_id, ());
+			<OwnedCount<T>>::insert(owner, owned + 1);
 			<Kitties<T>>::insert(kitty_id, kitty);
 			<KittyCnt<T>>::put(new_cnt);
-			Ok(kitty_id)
+			Ok(())
+		}
+
+		// Destroy a kitty with everything attached to it, and release its minting deposit. The id
+		// is retired for good. Returns the last owner.
+		pub(crate) fn do_burn(kitty_id: &T::KittyIndex) -> Result<T::AccountId, Error<T>> {
+			// A kitty under the hammer belongs to the auction until it is settled
+			ensure!(!<Auctions<T>>::contains_key(kitty_id), <Error<T>>::KittyInAuction);
+
+			let kitty = <Kitties<T>>::take(kitty_id).ok_or(<Error<T>>::KittyNotExist)?;
+			<KittiesOwned<T>>::remove(&kitty.owner, kitty_id);
+			<OwnedCount<T>>::mutate(&kitty.owner, |owned| *owned = owned.saturating_sub(1));
+			<KittyCnt<T>>::mutate(|cnt| *cnt = cnt.saturating_sub(1));
+			<BurnedKitties<T>>::insert(kitty_id, ());
+
+			<SireOffers<T>>::remove(kitty_id);
+			<Approvals<T>>::remove(kitty_id);
+			let mut offers = <Offers<T>>::take(kitty_id);
+			Self::release_offers(kitty_id, &mut offers, |_| true);
+
+			if let Some((depositor, deposit)) = <MintDeposits<T>>::take(kitty_id) {
+				T::Currency::unreserve(&depositor, deposit);
+			}
+			<Royalties<T>>::remove(kitty_id);
+
+			Ok(kitty.owner)
+		}
+
+		// Build the RPC view of a kitty
+		fn to_kitty_info(kitty_id: T::KittyIndex, kitty: Kitty<T>) -> KittyInfoOf<T> {
+			KittyInfo {
+				id: kitty_id,
+				dna: kitty.dna,
+				price: kitty.price,
+				gender: kitty.gender,
+				owner: kitty.owner,
+				parents: kitty.parents,
+				traits: genetics::decode(&kitty.dna),
+			}
+		}
+
+		/// Walk the ancestry of a kitty up to `depth` levels, at most `MAX_ANCESTRY_DEPTH`, used by
+		/// the `KittiesApi` runtime API.
+		///
+		/// Returns the known ancestors with their level, parents being at level 1, grandparents at
+		/// level 2 and so on. An ancestor shared by several lines is only listed once, at its
+		/// nearest level.
+		pub fn ancestry(kitty_id: T::KittyIndex, depth: u32) -> Vec<(u32, T::KittyIndex)> {
+			let mut ancestors = Vec::new();
+			let mut seen = BTreeSet::new();
+			let mut current = vec![kitty_id];
+
+			for level in 1..=depth.min(MAX_ANCESTRY_DEPTH) {
+				let parents = current.iter()
+					.filter_map(|kitty_id| Self::kitties(kitty_id).and_then(|kitty| kitty.parents))
+					.flat_map(|(parent1, parent2)| [parent1, parent2])
+					.filter(|parent| <Kitties<T>>::contains_key(parent))
+					.filter(|parent| seen.insert(*parent))
+					.collect::<Vec<_>>();
+				if parents.is_empty() {
+					break;
+				}
+
+				ancestors.extend(parents.iter().map(|parent| (level, *parent)));
+				current = parents;
+			}
+
+			ancestors
+		}
+
+		// Schedule a baby kitty for `owner` from two kitties, once they are both free to breed
+		// together, and start the cooldown of both parents. The sire fee, if any, must be reserved
+		// from `owner` by the caller. Returns the block it is revealed at.
+		fn do_breed(
+			owner: &T::AccountId,
+			royalty: Permill,
+			(kitty_id1, parent1): (T::KittyIndex, Kitty<T>),
+			(kitty_id2, parent2): (T::KittyIndex, Kitty<T>),
+			sire_fee: Option<(T::AccountId, BalanceOf<T>)>,
+		) -> Result<T::BlockNumber, Error<T>> {
+			// Neither parent can be locked in an auction
+			ensure!(!<Auctions<T>>::contains_key(&kitty_id1), <Error<T>>::KittyInAuction);
+			ensure!(!<Auctions<T>>::contains_key(&kitty_id2), <Error<T>>::KittyInAuction);
+
+			// Both parents must have recovered from their last breeding
+			let now = <frame_system::Pallet<T>>::block_number();
+			ensure!(
+				now >= parent1.cooldown_end && now >= parent2.cooldown_end,
+				<Error<T>>::KittyOnCooldown
+			);
+
+			// No inbreeding between parents and children, or between siblings
+			ensure!(!Self::are_related(&kitty_id1, &parent1, &kitty_id2, &parent2), <Error<T>>::KittiesRelated);
+
+			// check the genders of the parents
+			ensure!(parent1.gender != parent2.gender, <Error<T>>::SameParentGender);
+
+			// Verify the owner has the capacity to receive one more kitty
+			ensure!(Self::owned_count(owner) < T::MaxKittyOwned::get(), <Error<T>>::ExceedMaxKittyOwned);
+
+			// The dna is bred at the reveal, from the randomness of that block
+			let reveal_block = Self::schedule_mint(PendingMint {
+				owner: owner.clone(),
+				deposit: Zero::zero(),
+				royalty,
+				parents: Some((kitty_id1, kitty_id2)),
+				sire_fee,
+			})?;
+
+			// Both parents rest before breeding again
+			for (kitty_id, mut parent) in [(kitty_id1, parent1), (kitty_id2, parent2)] {
+				parent.cooldown_end = now.saturating_add(Self::breeding_cooldown(parent.generation));
+				<Kitties<T>>::insert(&kitty_id, parent);
+			}
+
+			Ok(reveal_block)
+		}
+
+		// The number of blocks a kitty of `generation` waits between two breedings
+		fn breeding_cooldown(generation: u16) -> T::BlockNumber {
+			T::BreedingCooldown::get().saturating_mul((generation as u32 + 1).into())
+		}
+
+		// Whether one kitty is a parent of the other, or both share a parent
+		fn are_related(
+			kitty_id1: &T::KittyIndex,
+			kitty1: &Kitty<T>,
+			kitty_id2: &T::KittyIndex,
+			kitty2: &Kitty<T>,
+		) -> bool {
+			let is_parent_of = |parent_id: &T::KittyIndex, child: &Kitty<T>| {
+				matches!(&child.parents, Some((p1, p2)) if p1 == parent_id || p2 == parent_id)
+			};
+
+			let siblings = match (&kitty1.parents, &kitty2.parents) {
+				(Some((a1, a2)), Some((b1, b2))) => a1 == b1 || a1 == b2 || a2 == b1 || a2 == b2,
+				_ => false,
+			};
+
+			is_parent_of(kitty_id1, kitty2) || is_parent_of(kitty_id2, kitty1) || siblings
+		}
+
+		/// Query a single kitty by its id, used by the `KittiesApi` runtime API.
+		pub fn kitty_info(kitty_id: T::KittyIndex) -> Option<KittyInfoOf<T>> {
+			Self::kitties(&kitty_id).map(|kitty| Self::to_kitty_info(kitty_id, kitty))
+		}
+
+		/// The ids of all kitties owned by `owner`, in no particular order.
+		pub fn kitties_owned(owner: &T::AccountId) -> Vec<T::KittyIndex> {
+			<KittiesOwned<T>>::iter_key_prefix(owner).collect()
+		}
+
+		/// Query all kitties owned by `owner`, used by the `KittiesApi` runtime API.
+		pub fn owned_kitty_infos(owner: &T::AccountId) -> Vec<KittyInfoOf<T>> {
+			Self::kitties_owned(owner)
+				.into_iter()
+				.filter_map(Self::kitty_info)
+				.collect()
+		}
+
+		/// Query a page of kitties that currently have an ask price, used by the `KittiesApi`
+		/// runtime API. The order follows the storage iteration order of `Kitties`.
+		pub fn kitties_for_sale(offset: u32, limit: u32) -> Vec<KittyInfoOf<T>> {
+			<Kitties<T>>::iter()
+				.filter(|(_, kitty)| kitty.price.is_some())
+				.skip(offset as usize)
+				.take(limit as usize)
+				.map(|(kitty_id, kitty)| Self::to_kitty_info(kitty_id, kitty))
+				.collect()
+		}
+
+		// Set the price of a kitty of `owner`, `None` taking it off the market.
+		fn do_set_price(
+			owner: &T::AccountId,
+			kitty_id: T::KittyIndex,
+			new_price: Option<BalanceOf<T>>,
+		) -> DispatchResult {
+			// Get the kitty object and check its ownership
+			let mut kitty = Self::kitties(&kitty_id).ok_or(<Error<T>>::KittyNotExist)?;
+			ensure!(kitty.owner == *owner, <Error<T>>::NotKittyOwner);
+
+			// The price of a kitty under the hammer is decided by the auction
+			ensure!(!<Auctions<T>>::contains_key(&kitty_id), <Error<T>>::KittyInAuction);
+
+			kitty.price = new_price.clone();
+			<Kitties<T>>::insert(&kitty_id, kitty);
+
+			// Deposit a "PriceSet" event.
+			Self::deposit_event(Event::PriceSet(owner.clone(), kitty_id, new_price));
+
+			Ok(())
+		}
+
+		/// Whether `operator` is approved to transfer all kitties of `owner`.
+		pub fn is_approved_for_all(owner: &T::AccountId, operator: &T::AccountId) -> bool {
+			<OperatorApprovals<T>>::contains_key(owner, operator)
 		}
 
 		// check if the sender is the owner of the kitty
@@ -405,7 +1496,8 @@ pub mod pallet {
 			}
 		}
 
-		// Helper to generate a Extrinsics to transfer the ownership of a kitty
+		// Helper to generate a Extrinsics to transfer the ownership of a kitty. The caller checks
+		// that `recv` can own one more kitty.
 		#[transactional]
 		pub fn transfer_kitty_to(
 			kitty_id: &T::KittyIndex,
@@ -414,15 +1506,16 @@ pub mod pallet {
 			// get the kitty object by kitty_id
 			let mut kitty = Self::kitties(&kitty_id).ok_or(<Error<T>>::KittyNotExist)?;
 			let prev_owner = kitty.owner;
+			// The ownership bookkeeping below assumes the kitty changes hands
+			ensure!(prev_owner != *recv, <Error<T>>::TransferToSelf);
 
-			// Remove `kitty_id` from the KittyOwned vector of `prev_kitty_owner`
-			<KittiesOwned<T>>::try_mutate(&prev_owner, |owned| {
-				if let Some(ind) = owned.iter().position(|&id| id == *kitty_id) {
-					owned.swap_remove(ind);
-					return Ok(());
-				}
-				Err(())
-			}).map_err(|_| <Error<T>>::KittyNotExist)?;
+			// Performs this operation first as it may fail
+			ensure!(<KittiesOwned<T>>::contains_key(&prev_owner, kitty_id), <Error<T>>::KittyNotExist);
+			let recv_owned = Self::owned_count(recv);
+
+			// Remove `kitty_id` from the kitties of `prev_owner`
+			<KittiesOwned<T>>::remove(&prev_owner, kitty_id);
+			<OwnedCount<T>>::mutate(&prev_owner, |owned| *owned = owned.saturating_sub(1));
 
 			// Transfer the ownership of the kitty
 			kitty.owner = recv.clone();
@@ -432,27 +1525,239 @@ pub mod pallet {
 
 			<Kitties<T>>::insert(&kitty_id, kitty);
 
+			// The sire offer and the approval were made by the previous owner, and the offers to
+			// buy the kitty were made to them
+			<SireOffers<T>>::remove(kitty_id);
+			<Approvals<T>>::remove(kitty_id);
+			let mut offers = <Offers<T>>::take(kitty_id);
+			Self::release_offers(kitty_id, &mut offers, |_| true);
+
 			// update the ownership storage
-			<KittiesOwned<T>>::try_mutate(&recv, |kitty_vec| {
-				kitty_vec.try_push(*kitty_id)
-			}).map_err(|_| <Error<T>>::ExceedMaxKittyOwned)?;
+			<KittiesOwned<T>>::insert(recv, kitty_id, ());
+			<OwnedCount<T>>::insert(recv, recv_owned + 1);
 
 			Ok(())
 		}
 
-		// generate a breed dna by mixing with dna1 and dna2
-		pub fn breed_dna(
-			dna1: [u8; 16],
-			dna2: [u8; 16]
-		) -> Result<[u8; 16], Error<T>> {
+		// Settle an ended auction: the kitty goes to the highest bidder and the reserved bid
+		// pays for it. If the sale cannot go through, the bid is released instead.
+		fn settle_auction(kitty_id: &T::KittyIndex) {
+			let auction = match <Auctions<T>>::take(kitty_id) {
+				Some(auction) => auction,
+				None => return,
+			};
+
+			let winning_bid = match auction.best_bid {
+				Some((winner, amount)) => match Self::sell_from_reserve(kitty_id, &auction.seller, &winner, amount) {
+					Ok(sale) => Some((winner, sale)),
+					Err(e) => {
+						log::warn!("Failed to settle auction of kitty {:?}: {:?}", kitty_id, e);
+						T::Currency::unreserve(&winner, amount);
+						None
+					},
+				},
+				None => None,
+			};
+
+			// Deposit a "AuctionSettled" event.
+			Self::deposit_event(Event::AuctionSettled(auction.seller, *kitty_id, winning_bid));
+		}
+
+		// Queue a kitty to reveal `MintRevealDelay` blocks from now, returning the reveal block.
+		fn schedule_mint(pending: PendingMint<T>) -> Result<T::BlockNumber, Error<T>> {
+			let reveal_block = <frame_system::Pallet<T>>::block_number()
+				.saturating_add(T::MintRevealDelay::get().max(One::one()));
+			<PendingMints<T>>::try_mutate(&reveal_block, |minting| minting.try_push(pending))
+				.map_err(|_| <Error<T>>::TooManyPendingMints)?;
+			Ok(reveal_block)
+		}
+
+		// Mint a kitty created or bred `MintRevealDelay` blocks ago, paying the sire fee if any.
+		// If it cannot be minted any more, the deposit and the sire fee are released instead.
+		fn reveal_mint(pending: PendingMint<T>) {
+			match Self::mint_pending(&pending) {
+				Ok(kitty_id) => {
+					// Record the deposit of a created kitty, released when the kitty is burned
+					if pending.parents.is_none() {
+						<MintDeposits<T>>::insert(&kitty_id, (pending.owner.clone(), pending.deposit));
+					}
+					Self::set_royalty(&kitty_id, &pending.owner, pending.royalty);
+
+					// The sire owner is paid now that the kitty is born, the owner keeps a fee that
+					// cannot be paid
+					if let Some((sire_owner, fee)) = &pending.sire_fee {
+						if let Err(e) = T::Currency::repatriate_reserved(&pending.owner, sire_owner, *fee, BalanceStatus::Free) {
+							log::warn!("Failed to pay the sire fee of {:?}: {:?}", pending.owner, e);
+							T::Currency::unreserve(&pending.owner, *fee);
+						}
+					}
+
+					// emits log and event that create a kitty successfully
+					log::info!("A kitty is born with ID: {:?}.", kitty_id);
+					Self::deposit_event(Event::Created(pending.owner, kitty_id));
+				},
+				Err(e) => {
+					log::warn!("Failed to reveal a kitty of {:?}: {:?}", pending.owner, e);
+					T::Currency::unreserve(&pending.owner, pending.deposit);
+					if let Some((_, fee)) = pending.sire_fee {
+						T::Currency::unreserve(&pending.owner, fee);
+					}
+					Self::deposit_event(Event::MintCancelled(pending.owner));
+				},
+			}
+		}
+
+		// Mint a pending kitty, a bred one from the dna of its parents if they still exist.
+		fn mint_pending(pending: &PendingMint<T>) -> Result<T::KittyIndex, Error<T>> {
+			let dna = match pending.parents {
+				Some((kitty_id1, kitty_id2)) => {
+					let parent1 = Self::kitties(&kitty_id1).ok_or(<Error<T>>::KittyNotExist)?;
+					let parent2 = Self::kitties(&kitty_id2).ok_or(<Error<T>>::KittyNotExist)?;
+					Some(Self::breed_dna(parent1.dna, parent2.dna)?)
+				},
+				None => None,
+			};
+			Self::mint(&pending.owner, dna, None, pending.parents)
+		}
+
+		// Record the royalty of the creator of a new kitty, if any.
+		fn set_royalty(kitty_id: &T::KittyIndex, creator: &T::AccountId, royalty: Permill) {
+			if !royalty.is_zero() {
+				<Royalties<T>>::insert(kitty_id, (creator.clone(), royalty));
+			}
+		}
+
+		// Pay `price` for a kitty from the free balance of `buyer`: the protocol fee goes to the
+		// treasury, the royalty to the creator of the kitty and the rest to the seller. Each part
+		// is rounded down, and a part too small to open the account it is due to stays with the
+		// seller.
+		fn pay_sale(
+			kitty_id: &T::KittyIndex,
+			seller: &T::AccountId,
+			buyer: &T::AccountId,
+			price: BalanceOf<T>,
+			existence: ExistenceRequirement,
+		) -> Result<SaleOf<T>, DispatchError> {
+			let treasury = T::Treasury::get();
+			let protocol_fee = Some(T::ProtocolFee::get().mul_floor(price))
+				.filter(|fee| Self::can_receive(&treasury, *fee))
+				.unwrap_or_else(Zero::zero);
+
+			// The royalty is capped by the current `MaxRoyalty`, and the seller keeps it when
+			// selling a kitty of its own making
+			let royalty = Self::royalties(kitty_id)
+				.filter(|(creator, _)| creator != seller)
+				.map(|(creator, royalty)| {
+					let amount = royalty.min(T::MaxRoyalty::get()).mul_floor(price);
+					(creator, amount.min(price.saturating_sub(protocol_fee)))
+				})
+				.filter(|(creator, amount)| !amount.is_zero() && Self::can_receive(creator, *amount));
+			let royalty_amount = royalty.as_ref().map(|(_, amount)| *amount).unwrap_or_else(Zero::zero);
+			let proceeds = price.saturating_sub(protocol_fee).saturating_sub(royalty_amount);
+
+			if !protocol_fee.is_zero() {
+				T::Currency::transfer(buyer, &treasury, protocol_fee, existence)?;
+			}
+			if let Some((creator, amount)) = &royalty {
+				T::Currency::transfer(buyer, creator, *amount, existence)?;
+			}
+			T::Currency::transfer(buyer, seller, proceeds, existence)?;
+
+			Ok(Sale { price, protocol_fee, royalty, proceeds })
+		}
+
+		// Whether `amount` can be paid to `who`, the account existing or the amount opening it.
+		fn can_receive(who: &T::AccountId, amount: BalanceOf<T>) -> bool {
+			T::Currency::total_balance(who).saturating_add(amount) >= T::Currency::minimum_balance()
+		}
+
+		// Sell a kitty to `buyer` for `price` out of its reserved balance, all or nothing. The
+		// buyer committed the amount, so it may be left below the existential deposit.
+		#[transactional]
+		fn sell_from_reserve(
+			kitty_id: &T::KittyIndex,
+			seller: &T::AccountId,
+			buyer: &T::AccountId,
+			price: BalanceOf<T>,
+		) -> Result<SaleOf<T>, DispatchError> {
+			// The buyer may have received kitties since committing the amount
+			ensure!(Self::owned_count(buyer) < T::MaxKittyOwned::get(), <Error<T>>::ExceedMaxKittyOwned);
+			// The whole amount must still be reserved
+			ensure!(T::Currency::unreserve(buyer, price).is_zero(), <Error<T>>::NotEnoughBalance);
+
+			let sale = Self::pay_sale(kitty_id, seller, buyer, price, ExistenceRequirement::AllowDeath)?;
+			Self::transfer_kitty_to(kitty_id, buyer)?;
+			Ok(sale)
+		}
 
-			let mut new_dna = Self::gen_dna();
+		// Release the offers on a kitty for which `release` holds, keeping the others in `offers`.
+		pub(crate) fn release_offers(
+			kitty_id: &T::KittyIndex,
+			offers: &mut BoundedVec<Offer<T>, T::MaxOffersPerKitty>,
+			release: impl Fn(&Offer<T>) -> bool,
+		) {
+			offers.retain(|offer| {
+				if !release(offer) {
+					return true;
+				}
+				T::Currency::unreserve(&offer.buyer, offer.amount);
+				Self::deposit_event(Event::OfferReleased(offer.buyer.clone(), *kitty_id, offer.amount));
+				false
+			});
+		}
 
-			for i in 0..new_dna.len() {
-				new_dna[i] = (new_dna[i] & dna1[i]) | (!new_dna[i] & dna2[i]);
+		// Store the offers left on a kitty, removing the entry once there is none.
+		pub(crate) fn put_offers(
+			kitty_id: &T::KittyIndex,
+			offers: BoundedVec<Offer<T>, T::MaxOffersPerKitty>,
+		) {
+			if offers.is_empty() {
+				<Offers<T>>::remove(kitty_id);
+			} else {
+				<Offers<T>>::insert(kitty_id, offers);
 			}
+		}
+
+		// Sweep the kitties with offers for expired ones, as far as `remaining_weight` allows,
+		// resuming from the last swept kitty in the next block.
+		fn sweep_expired_offers(now: T::BlockNumber, remaining_weight: Weight) -> Weight {
+			let step = T::WeightInfo::release_offers(T::MaxOffersPerKitty::get());
+			// Reading and writing the cursor
+			let mut used = T::DbWeight::get().reads_writes(1, 1);
+			if used.saturating_add(step) > remaining_weight {
+				return 0;
+			}
+
+			// Collect the kitties to sweep first, `Offers` is not changed while iterating over it
+			let budget = (remaining_weight.saturating_sub(used) / step.max(1)) as usize;
+			let swept = match <OfferSweepCursor<T>>::get() {
+				Some(last) => <Offers<T>>::iter_from(<Offers<T>>::hashed_key_for(last)),
+				None => <Offers<T>>::iter(),
+			}.take(budget).collect::<Vec<_>>();
 
-			Ok(new_dna)
+			// Every kitty was swept once the map runs out, start over in the next block
+			let cursor = if swept.len() < budget {
+				None
+			} else {
+				swept.last().map(|(kitty_id, _)| *kitty_id)
+			};
+
+			for (kitty_id, mut offers) in swept {
+				Self::release_offers(&kitty_id, &mut offers, |offer| offer.expiry <= now);
+				Self::put_offers(&kitty_id, offers);
+				used = used.saturating_add(step);
+			}
+
+			<OfferSweepCursor<T>>::set(cursor);
+			used
+		}
+
+		// generate a breed dna by passing on one allele of each gene pair of dna1 and dna2
+		pub fn breed_dna(
+			dna1: [u8; 16],
+			dna2: [u8; 16]
+		) -> Result<[u8; 16], Error<T>> {
+			Ok(genetics::breed(&dna1, &dna2, Self::gen_random(&b"breed"[..])))
 		}
 	}
 }
@@ -0,0 +1,341 @@
+//! Storage migrations of the kitties pallet.
+
+/// Migration to storage version 1: kitties record their lineage.
+pub mod v1 {
+	use crate::{
+		genetics,
+		pallet::{Config, Kitties, Kitty, Pallet},
+	};
+	use codec::{Decode, Encode};
+	use frame_support::{
+		sp_runtime::traits::Zero,
+		traits::{Currency, GetStorageVersion, StorageVersion},
+		weights::Weight,
+		RuntimeDebug,
+	};
+	use sp_std::prelude::*;
+
+	#[cfg(feature = "try-runtime")]
+	use frame_support::traits::OnRuntimeUpgradeHelpersExt;
+
+	type BalanceOf<T> = <<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+
+	/// A kitty as stored before version 1.
+	#[derive(Encode, Decode, Clone, PartialEq, RuntimeDebug)]
+	pub struct OldKitty<AccountId, Balance> {
+		pub dna: [u8; 16],
+		pub price: Option<Balance>,
+		pub gender: crate::Gender,
+		pub owner: AccountId,
+	}
+
+	/// Translate every kitty to the version 1 layout.
+	///
+	/// The lineage of the existing kitties is unknown: they get no parents, generation 0 and the
+	/// genesis block as birth block. The generation recorded in their dna is reset accordingly, the
+	/// genes are left untouched.
+	pub fn migrate<T: Config>() -> Weight {
+		if Pallet::<T>::on_chain_storage_version() >= 1 {
+			log::info!("kitties: storage already at version 1 or later, skipping the v1 migration");
+			return T::DbWeight::get().reads(1);
+		}
+
+		let mut translated: Weight = 0;
+		Kitties::<T>::translate::<OldKitty<T::AccountId, BalanceOf<T>>, _>(|_, old| {
+			translated += 1;
+			let mut dna = old.dna;
+			genetics::set_generation(&mut dna, 0);
+			Some(Kitty {
+				dna,
+				price: old.price,
+				gender: old.gender,
+				owner: old.owner,
+				parents: None,
+				generation: 0,
+				birth_block: Zero::zero(),
+				cooldown_end: Zero::zero(),
+			})
+		});
+
+		StorageVersion::new(1).put::<Pallet<T>>();
+		log::info!("kitties: migrated {} kitties to storage version 1", translated);
+
+		T::DbWeight::get().reads_writes(translated + 1, translated + 1)
+	}
+
+	/// Record the number of kitties to translate.
+	#[cfg(feature = "try-runtime")]
+	pub fn pre_upgrade<T: Config>() -> Result<(), &'static str> {
+		if Pallet::<T>::on_chain_storage_version() < 1 {
+			let count = Kitties::<T>::iter_keys().count() as u32;
+			Pallet::<T>::set_temp_storage(count, "v1::kitties");
+		}
+		Ok(())
+	}
+
+	/// Check every kitty decodes in the version 1 layout.
+	#[cfg(feature = "try-runtime")]
+	pub fn post_upgrade<T: Config>() -> Result<(), &'static str> {
+		frame_support::ensure!(
+			Pallet::<T>::on_chain_storage_version() >= 1,
+			"kitties: storage version not upgraded to 1"
+		);
+		if let Some(count) = Pallet::<T>::get_temp_storage::<u32>("v1::kitties") {
+			frame_support::ensure!(
+				Kitties::<T>::iter_values().count() as u32 == count,
+				"kitties: some kitties do not decode after the v1 migration"
+			);
+		}
+		Ok(())
+	}
+}
+
+/// Migration to storage version 2: `KittiesOwned` moves from a `BoundedVec` per owner to a double
+/// map, with the number of kitties of each owner kept in `OwnedCount`.
+pub mod v2 {
+	use crate::pallet::{Config, KittiesOwned, OwnedCount, Pallet};
+	use frame_support::{
+		storage::migration,
+		traits::{GetStorageVersion, PalletInfoAccess, StorageVersion},
+		weights::Weight,
+		Twox64Concat,
+	};
+	use sp_std::prelude::*;
+
+	#[cfg(feature = "try-runtime")]
+	use crate::pallet::Kitties;
+	#[cfg(feature = "try-runtime")]
+	use frame_support::traits::OnRuntimeUpgradeHelpersExt;
+
+	/// The name of the `KittiesOwned` storage, in both layouts.
+	const KITTIES_OWNED: &[u8] = b"KittiesOwned";
+
+	// Take the kitties of every owner out of the version 1 `KittiesOwned`. A `BoundedVec` encodes as
+	// a `Vec`, which also decodes the lists longer than the current `MaxKittyOwned`.
+	fn drain_old_owned<T: Config>() -> Vec<(T::AccountId, Vec<T::KittyIndex>)> {
+		migration::storage_key_iter::<T::AccountId, Vec<T::KittyIndex>, Twox64Concat>(
+			Pallet::<T>::name().as_bytes(),
+			KITTIES_OWNED,
+		)
+		.drain()
+		.collect()
+	}
+
+	/// Move the ownership index to the double map.
+	pub fn migrate<T: Config>() -> Weight {
+		if Pallet::<T>::on_chain_storage_version() != 1 {
+			log::info!("kitties: storage not at version 1, skipping the v2 migration");
+			return T::DbWeight::get().reads(1);
+		}
+
+		// Drain everything before writing, as both layouts live under the same prefix
+		let old_owned = drain_old_owned::<T>();
+
+		let owners = old_owned.len() as Weight;
+		let mut kitties: Weight = 0;
+		for (owner, owned) in old_owned {
+			kitties += owned.len() as Weight;
+			for kitty_id in owned.iter() {
+				KittiesOwned::<T>::insert(&owner, kitty_id, ());
+			}
+			OwnedCount::<T>::insert(&owner, owned.len() as u32);
+		}
+
+		StorageVersion::new(2).put::<Pallet<T>>();
+		log::info!("kitties: migrated the kitties of {} owners to storage version 2", owners);
+
+		T::DbWeight::get().reads_writes(owners + 1, 2 * owners + kitties + 1)
+	}
+
+	/// Record the number of owned kitties to move.
+	#[cfg(feature = "try-runtime")]
+	pub fn pre_upgrade<T: Config>() -> Result<(), &'static str> {
+		if Pallet::<T>::on_chain_storage_version() < 2 {
+			let count = migration::storage_key_iter::<T::AccountId, Vec<T::KittyIndex>, Twox64Concat>(
+				Pallet::<T>::name().as_bytes(),
+				KITTIES_OWNED,
+			)
+			.map(|(_, owned)| owned.len() as u32)
+			.sum::<u32>();
+			Pallet::<T>::set_temp_storage(count, "v2::kitties_owned");
+		}
+		Ok(())
+	}
+
+	/// Check the double map holds every owned kitty, with the right owner and counters.
+	#[cfg(feature = "try-runtime")]
+	pub fn post_upgrade<T: Config>() -> Result<(), &'static str> {
+		frame_support::ensure!(
+			Pallet::<T>::on_chain_storage_version() >= 2,
+			"kitties: storage version not upgraded to 2"
+		);
+		if let Some(count) = Pallet::<T>::get_temp_storage::<u32>("v2::kitties_owned") {
+			frame_support::ensure!(
+				KittiesOwned::<T>::iter_keys().count() as u32 == count,
+				"kitties: owned kitties lost in the v2 migration"
+			);
+		}
+
+		for (owner, owned) in OwnedCount::<T>::iter() {
+			frame_support::ensure!(
+				KittiesOwned::<T>::iter_key_prefix(&owner).count() as u32 == owned,
+				"kitties: owned count does not match the owned kitties"
+			);
+		}
+		for (owner, kitty_id, ()) in KittiesOwned::<T>::iter() {
+			frame_support::ensure!(
+				Kitties::<T>::get(&kitty_id).map(|kitty| kitty.owner) == Some(owner),
+				"kitties: owned kitty with another owner"
+			);
+		}
+		Ok(())
+	}
+}
+
+/// Migration to storage version 3: `MintDeposits` records who paid the minting deposit of every
+/// kitty created from then on, so that burning the kitty releases it.
+///
+/// The kitties created before are left without a record. Their creator is not stored anywhere,
+/// and the reserved balance of an account also holds its bids, offers and other deposits, so it
+/// does not tell who paid. Their deposit stays reserved from their creator, and burning them
+/// releases nothing.
+pub mod v3 {
+	use crate::pallet::{Config, Pallet};
+	use frame_support::{
+		traits::{GetStorageVersion, StorageVersion},
+		weights::Weight,
+	};
+
+	#[cfg(feature = "try-runtime")]
+	use crate::pallet::MintDeposits;
+	#[cfg(feature = "try-runtime")]
+	use frame_support::traits::OnRuntimeUpgradeHelpersExt;
+
+	/// Bump the storage version, no deposit is recorded for the existing kitties.
+	pub fn migrate<T: Config>() -> Weight {
+		if Pallet::<T>::on_chain_storage_version() != 2 {
+			log::info!("kitties: storage not at version 2, skipping the v3 migration");
+			return T::DbWeight::get().reads(1);
+		}
+
+		StorageVersion::new(3).put::<Pallet<T>>();
+		log::info!("kitties: upgraded to storage version 3, the existing kitties keep no minting deposit");
+
+		T::DbWeight::get().reads_writes(1, 1)
+	}
+
+	/// Record the number of minting deposits.
+	#[cfg(feature = "try-runtime")]
+	pub fn pre_upgrade<T: Config>() -> Result<(), &'static str> {
+		let count = MintDeposits::<T>::iter_keys().count() as u32;
+		Pallet::<T>::set_temp_storage(count, "v3::mint_deposits");
+		Ok(())
+	}
+
+	/// Check no minting deposit was recorded or dropped.
+	#[cfg(feature = "try-runtime")]
+	pub fn post_upgrade<T: Config>() -> Result<(), &'static str> {
+		frame_support::ensure!(
+			Pallet::<T>::on_chain_storage_version() >= 3,
+			"kitties: storage version not upgraded to 3"
+		);
+		if let Some(count) = Pallet::<T>::get_temp_storage::<u32>("v3::mint_deposits") {
+			frame_support::ensure!(
+				MintDeposits::<T>::iter_keys().count() as u32 == count,
+				"kitties: minting deposits changed in the v3 migration"
+			);
+		}
+		Ok(())
+	}
+}
+
+/// Migration to storage version 4: the kitties bred with an offered sire keep its fee reserved
+/// until they are revealed, `PendingMints` records who it is paid to.
+///
+/// The fee of the kitties pending at the upgrade was already paid when they were bred, so they
+/// record none.
+pub mod v4 {
+	use crate::pallet::{Config, PendingMint, PendingMints, Pallet};
+	use codec::{Decode, Encode};
+	use frame_support::{
+		sp_runtime::Permill,
+		traits::{Currency, GetStorageVersion, StorageVersion},
+		weights::Weight,
+		RuntimeDebug,
+	};
+	use sp_std::prelude::*;
+
+	#[cfg(feature = "try-runtime")]
+	use frame_support::traits::OnRuntimeUpgradeHelpersExt;
+
+	type BalanceOf<T> = <<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+
+	/// A pending kitty as stored before version 4.
+	#[derive(Encode, Decode, Clone, PartialEq, RuntimeDebug)]
+	pub struct OldPendingMint<AccountId, Balance, KittyIndex> {
+		pub owner: AccountId,
+		pub deposit: Balance,
+		pub royalty: Permill,
+		pub parents: Option<(KittyIndex, KittyIndex)>,
+	}
+
+	type OldPendingMints<T> = Vec<
+		OldPendingMint<<T as frame_system::Config>::AccountId, BalanceOf<T>, <T as Config>::KittyIndex>,
+	>;
+
+	/// Translate the pending kitties to the version 4 layout.
+	pub fn migrate<T: Config>() -> Weight {
+		if Pallet::<T>::on_chain_storage_version() != 3 {
+			log::info!("kitties: storage not at version 3, skipping the v4 migration");
+			return T::DbWeight::get().reads(1);
+		}
+
+		let mut translated: Weight = 0;
+		PendingMints::<T>::translate::<OldPendingMints<T>, _>(|_, old| {
+			translated += 1;
+			let pending = old
+				.into_iter()
+				.map(|old| PendingMint {
+					owner: old.owner,
+					deposit: old.deposit,
+					royalty: old.royalty,
+					parents: old.parents,
+					sire_fee: None,
+				})
+				.collect::<Vec<_>>();
+			// The bound is unchanged, so the pending kitties of a block still fit
+			pending.try_into().ok()
+		});
+
+		StorageVersion::new(4).put::<Pallet<T>>();
+		log::info!("kitties: migrated the pending kitties of {} blocks to storage version 4", translated);
+
+		T::DbWeight::get().reads_writes(translated + 1, translated + 1)
+	}
+
+	/// Record the number of pending kitties to translate.
+	#[cfg(feature = "try-runtime")]
+	pub fn pre_upgrade<T: Config>() -> Result<(), &'static str> {
+		if Pallet::<T>::on_chain_storage_version() < 4 {
+			let count = PendingMints::<T>::iter_keys().count() as u32;
+			Pallet::<T>::set_temp_storage(count, "v4::pending_mints");
+		}
+		Ok(())
+	}
+
+	/// Check every pending kitty decodes in the version 4 layout.
+	#[cfg(feature = "try-runtime")]
+	pub fn post_upgrade<T: Config>() -> Result<(), &'static str> {
+		frame_support::ensure!(
+			Pallet::<T>::on_chain_storage_version() >= 4,
+			"kitties: storage version not upgraded to 4"
+		);
+		if let Some(count) = Pallet::<T>::get_temp_storage::<u32>("v4::pending_mints") {
+			frame_support::ensure!(
+				PendingMints::<T>::iter_values().count() as u32 == count,
+				"kitties: some pending kitties do not decode after the v4 migration"
+			);
+		}
+		Ok(())
+	}
+}
@@ -2,12 +2,14 @@ use crate as pallet_kitties;
 use pallet_kitties::Gender;
 use frame_support::{
 	parameter_types,
+	BoundedVec,
+	traits::{OnFinalize, OnInitialize, Randomness},
 };
 use sp_core::H256;
 use sp_runtime::{
 	testing::Header,
-	traits::{BlakeTwo256, IdentityLookup},
-	BuildStorage,
+	traits::{BlakeTwo256, Hash, IdentityLookup},
+	BuildStorage, Permill,
 };
 
 use frame_benchmarking::account;
@@ -24,7 +26,6 @@ frame_support::construct_runtime!(
 	{
 		System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
 		Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>},
-		RandomnessCollectiveFlip: pallet_randomness_collective_flip::{Pallet, Storage},
 		SubstrateKitties: pallet_kitties::{Pallet, Call, Storage, Config<T>, Event<T>},
 	}
 );
@@ -81,22 +82,71 @@ parameter_types! {
 	pub const MaxKittyOwned: u32 = 5;
 	// The reserve price of mining a kitty is 3
 	pub const MinKittyMintingPrice: u32 = 3;
+	// An auction runs for at most 10 blocks
+	pub const MaxAuctionDuration: u64 = 10;
+	pub const MaxAuctionsPerBlock: u32 = 2;
+	// A generation 0 kitty breeds at most every 2 blocks
+	pub const BreedingCooldown: u64 = 2;
+	pub const MaxBatchSize: u32 = 3;
+	pub const MaxOffersPerKitty: u32 = 2;
+	pub const KittyTreasury: u64 = 99;
+	// No fee unless a test sets one
+	pub storage ProtocolFee: Permill = Permill::zero();
+	pub const MaxRoyalty: Permill = Permill::from_percent(10);
+	pub const MintRevealDelay: u64 = 2;
+	pub const MaxMintsPerBlock: u32 = 2;
+}
+
+// A deterministic source of randomness, so that the dna of new kitties can be asserted exactly.
+pub struct TestRandomness;
+
+impl Randomness<H256, u64> for TestRandomness {
+	fn random(subject: &[u8]) -> (H256, u64) {
+		(BlakeTwo256::hash(subject), System::block_number())
+	}
 }
 
 // impl Config for Test (add use super::*)
 impl pallet_kitties::Config for Test {
 	type Event = Event;
 	type Currency = Balances;
-	type KittyRandomness = RandomnessCollectiveFlip;
+	type KittyRandomness = TestRandomness;
 	type MaxKittyOwned = MaxKittyOwned;
 	type MinKittyMintingPrice = MinKittyMintingPrice;
+	type MaxAuctionDuration = MaxAuctionDuration;
+	type MaxAuctionsPerBlock = MaxAuctionsPerBlock;
+	type BreedingCooldown = BreedingCooldown;
+	type MaxBatchSize = MaxBatchSize;
+	type MaxOffersPerKitty = MaxOffersPerKitty;
+	type Treasury = KittyTreasury;
+	type ProtocolFee = ProtocolFee;
+	type MaxRoyalty = MaxRoyalty;
+	type MintRevealDelay = MintRevealDelay;
+	type MaxMintsPerBlock = MaxMintsPerBlock;
 	type KittyIndex = sp_core::H256;
 	type KittyHashing = BlakeTwo256;
+	type WeightInfo = ();
 }
 
-impl pallet_randomness_collective_flip::Config for Test {}
+// A generation 0 dna with the given gene pairs.
+pub(crate) fn dna(genes: &[u8; 14]) -> [u8; 16] {
+	let mut dna = [0u8; 16];
+	dna[..14].copy_from_slice(genes);
+	dna
+}
 
 pub(crate) fn new_test_ext() -> sp_io::TestExternalities {
+	new_test_ext_with_kitties(vec![
+		(1, *b"1234567890123456", Gender::Female),
+		(2, *b"123456789012345a", Gender::Male),
+		(3, *b"123456789012345e", Gender::Male),
+		(3, *b"1234567890123462", Gender::Male),
+		(3, *b"1234567890123466", Gender::Female),
+	])
+}
+
+// The test externalities with the given genesis kitties.
+pub(crate) fn new_test_ext_with_kitties(kitties: Vec<(u64, [u8; 16], Gender)>) -> sp_io::TestExternalities {
 	let mut t = frame_system::GenesisConfig::default().build_storage::<Test>().unwrap();
 	GenesisConfig {
 		balances: BalancesConfig {
@@ -104,18 +154,11 @@ pub(crate) fn new_test_ext() -> sp_io::TestExternalities {
 				(1,  100),
 				(2,  10),
 				(3,  2),
+				(4,  50),
 				(account("caller", 0, 0), 100)
 			]
 		},
-		substrate_kitties: SubstrateKittiesConfig {
-			kitties: vec![
-				(1, *b"1234567890123456", Gender::Female),
-				(2, *b"123456789012345a", Gender::Male),
-				(3, *b"123456789012345e", Gender::Male),
-				(3, *b"1234567890123462", Gender::Male),
-				(3, *b"1234567890123466", Gender::Female),
-			]
-		},
+		substrate_kitties: SubstrateKittiesConfig { kitties },
 		..Default::default()
 	}
 		.assimilate_storage(&mut t)
@@ -126,6 +169,46 @@ pub(crate) fn new_test_ext() -> sp_io::TestExternalities {
 	ext
 }
 
+// A batch of items for the batch calls.
+pub(crate) fn batch<T>(items: Vec<T>) -> BoundedVec<T, MaxBatchSize> {
+	items.try_into().expect("the batch fits in `MaxBatchSize`")
+}
+
+// The id of the genesis kitty at `index` in the genesis config, which was minted with that
+// nonce. The genesis kitties only differ by their generation bytes, reset to 0 when minted.
+pub(crate) fn genesis_kitty(index: u64) -> H256 {
+	pallet_kitties::Kitties::<Test>::iter()
+		.find(|(kitty_id, kitty)| BlakeTwo256::hash_of(&(kitty, index)) == *kitty_id)
+		.map(|(kitty_id, _)| kitty_id)
+		.expect("a genesis kitty has this index")
+}
+
+// The id of the last kitty created or bred, from the events.
+pub(crate) fn last_created() -> H256 {
+	System::events()
+		.into_iter()
+		.rev()
+		.find_map(|record| match record.event {
+			Event::SubstrateKitties(pallet_kitties::Event::Created(_, kitty_id)) => Some(kitty_id),
+			_ => None,
+		})
+		.expect("a kitty was created")
+}
+
+// Advance the chain to block `n`, running the kitties hooks on the way.
+pub(crate) fn run_to_block(n: u64) {
+	while System::block_number() < n {
+		SubstrateKitties::on_finalize(System::block_number());
+		System::set_block_number(System::block_number() + 1);
+		SubstrateKitties::on_initialize(System::block_number());
+	}
+}
+
+// Advance the chain to the block at which the kitties created now are revealed.
+pub(crate) fn reveal_mints() {
+	run_to_block(System::block_number() + MintRevealDelay::get());
+}
+
 /*
 pub(crate) fn new_test_ext() -> sp_io::TestExternalities {
 	let mut t = frame_system::GenesisConfig::default().build_storage::<Test>().unwrap();
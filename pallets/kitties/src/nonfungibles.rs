@@ -0,0 +1,83 @@
+//! The kitties as generic NFTs, through the `nonfungibles` traits of `frame_support`.
+//!
+//! All kitties belong to a single implicit class `()`, without owner. The attributes `dna`,
+//! `gender` and `price` of a kitty can be read, SCALE encoded, through `Inspect::attribute`.
+
+use crate::pallet::{Auctions, Config, Error, Event, Kitties, Pallet};
+use codec::Encode;
+use frame_support::{
+	ensure,
+	traits::{
+		tokens::nonfungibles::{Create, Inspect, Mutate, Transfer},
+		Get,
+	},
+};
+use sp_runtime::{DispatchResult, TokenError};
+use sp_std::prelude::*;
+
+impl<T: Config> Inspect<T::AccountId> for Pallet<T> {
+	type InstanceId = T::KittyIndex;
+	type ClassId = ();
+
+	fn owner(_class: &(), instance: &T::KittyIndex) -> Option<T::AccountId> {
+		<Kitties<T>>::get(instance).map(|kitty| kitty.owner)
+	}
+
+	fn attribute(_class: &(), instance: &T::KittyIndex, key: &[u8]) -> Option<Vec<u8>> {
+		let kitty = <Kitties<T>>::get(instance)?;
+		match key {
+			b"dna" => Some(kitty.dna.encode()),
+			b"gender" => Some(kitty.gender.encode()),
+			b"price" => Some(kitty.price.encode()),
+			_ => None,
+		}
+	}
+
+	// A kitty in a running auction stays with its seller until the auction is settled
+	fn can_transfer(_class: &(), instance: &T::KittyIndex) -> bool {
+		<Kitties<T>>::contains_key(instance) && !<Auctions<T>>::contains_key(instance)
+	}
+}
+
+impl<T: Config> Transfer<T::AccountId> for Pallet<T> {
+	fn transfer(_class: &(), instance: &T::KittyIndex, destination: &T::AccountId) -> DispatchResult {
+		let owner = Self::owner(&(), instance).ok_or(<Error<T>>::KittyNotExist)?;
+		ensure!(owner != *destination, <Error<T>>::TransferToSelf);
+		ensure!(!<Auctions<T>>::contains_key(instance), <Error<T>>::KittyInAuction);
+		ensure!(
+			Self::owned_count(destination) < T::MaxKittyOwned::get(),
+			<Error<T>>::ExceedMaxKittyOwned
+		);
+
+		Self::transfer_kitty_to(instance, destination)?;
+
+		Self::deposit_event(Event::Transferred(owner, destination.clone(), *instance));
+		Ok(())
+	}
+}
+
+impl<T: Config> Mutate<T::AccountId> for Pallet<T> {
+	// Mint a generation 0 kitty of random dna and gender, without taking any deposit
+	fn mint_into(_class: &(), instance: &T::KittyIndex, who: &T::AccountId) -> DispatchResult {
+		let kitty = Self::new_kitty(who, None, None, None);
+		Self::insert_kitty(*instance, kitty)?;
+
+		Self::deposit_event(Event::Created(who.clone(), *instance));
+		Ok(())
+	}
+
+	// Burn a kitty, releasing its minting deposit if it has one
+	fn burn_from(_class: &(), instance: &T::KittyIndex) -> DispatchResult {
+		let owner = Self::do_burn(instance)?;
+
+		Self::deposit_event(Event::Burned(owner, *instance));
+		Ok(())
+	}
+}
+
+impl<T: Config> Create<T::AccountId> for Pallet<T> {
+	// The implicit class of the kitties is the only one
+	fn create_class(_class: &(), _who: &T::AccountId, _admin: &T::AccountId) -> DispatchResult {
+		Err(TokenError::Unsupported.into())
+	}
+}
@@ -1,8 +1,23 @@
 use crate::{
-	mock::*, pallet::{Error}
+	genetics::{self, EyeColour, FurColour, KittyTraits, Pattern, RarityTier},
+	migrations,
+	mock::*, pallet::{Error, Gender, Kitties, KittiesOwned, MintDeposits, OwnedCount, PendingMints, Sale},
+	weights::WeightInfo,
+};
+use codec::{Decode, Encode};
+use frame_support::{
+	assert_ok, assert_noop,
+	BoundedVec,
+	storage::migration,
+	traits::{
+		tokens::nonfungibles::{Create, Inspect, Mutate, Transfer},
+		GetStorageVersion, OnIdle, OnRuntimeUpgrade, StorageVersion,
+	},
+	weights::Weight,
+	StorageHasher, Twox64Concat,
 };
-use frame_support::{assert_ok, assert_noop};
 use sp_core::H256;
+use sp_runtime::Permill;
 // use super::*;
 
 fn events() -> Vec<Event> {
@@ -20,14 +35,15 @@ fn should_build_genesis_kitties() {
 		assert_eq!(SubstrateKitties::kitty_cnt(), 5);
 
 		// Check owners own the correct amount of kitties
-		let kitties_owned_by_1 = SubstrateKitties::kitties_owned(1);
+		let kitties_owned_by_1 = SubstrateKitties::kitties_owned(&1);
 		assert_eq!(kitties_owned_by_1.len(), 1);
 
-		let kitties_owned_by_2 = SubstrateKitties::kitties_owned(2);
+		let kitties_owned_by_2 = SubstrateKitties::kitties_owned(&2);
 		assert_eq!(kitties_owned_by_2.len(), 1);
 
-		let kitties_owned_by_3 = SubstrateKitties::kitties_owned(3);
+		let kitties_owned_by_3 = SubstrateKitties::kitties_owned(&3);
 		assert_eq!(kitties_owned_by_3.len(), 3);
+		assert_eq!(SubstrateKitties::owned_count(3), 3);
 
 		// Check that kitties are owned correctly
 		let kid1 = kitties_owned_by_1[0];
@@ -60,20 +76,63 @@ fn should_build_genesis_kitties() {
 #[test]
 fn create_kitty_test() {
 	new_test_ext().execute_with(|| {
-		let kitties_owned_by_1 = SubstrateKitties::kitties_owned(1);
+		let kitties_owned_by_1 = SubstrateKitties::kitties_owned(&1);
+
+		// account 1 create a kitty, revealed two blocks later
+		assert_ok!(SubstrateKitties::create_kitty(Origin::signed(1), Permill::zero()));
+		System::assert_last_event(Event::SubstrateKitties(crate::Event::MintRequested(1, 3)));
+		assert_eq!(SubstrateKitties::pending_mints(3).len(), 1);
+		assert_eq!(SubstrateKitties::kitties_owned(&1), kitties_owned_by_1);
+		assert_eq!(Balances::reserved_balance(1), 3);
 
-		// account 1 create a kitty
-		assert_ok!(SubstrateKitties::create_kitty(Origin::signed(1)));
+		reveal_mints();
+		assert!(SubstrateKitties::pending_mints(3).is_empty());
 
 		// get the kitties created by account 1
-		let kitties_owned_by_1_new = SubstrateKitties::kitties_owned(1);
+		let kitties_owned_by_1_new = SubstrateKitties::kitties_owned(&1);
 		assert_eq!(kitties_owned_by_1_new.len(), kitties_owned_by_1.len() + 1);
 
-		let events = events();
-		assert_eq!(
-			events[1],
-			Event::SubstrateKitties(crate::Event::Created(1, kitties_owned_by_1_new[1]))
-		);
+		let kitty_id = last_created();
+		assert!(!kitties_owned_by_1.contains(&kitty_id));
+		assert!(kitties_owned_by_1_new.contains(&kitty_id));
+		assert_eq!(SubstrateKitties::kitties(kitty_id).unwrap().birth_block, 3);
+
+		System::assert_last_event(Event::SubstrateKitties(crate::Event::Created(1, kitty_id)));
+	});
+}
+
+#[test]
+fn identical_kitties_get_distinct_ids() {
+	// identical genesis kitties, with the same dna, gender and owner
+	let twin = (1, dna(b"12345678901234"), Gender::Female);
+	new_test_ext_with_kitties(vec![twin.clone(), twin]).execute_with(|| {
+		let owned = SubstrateKitties::kitties_owned(&1);
+		assert_eq!(owned.len(), 2);
+		assert_ne!(owned[0], owned[1]);
+		assert_eq!(Kitties::<Test>::iter().count(), 2);
+		assert_eq!(SubstrateKitties::kitty_cnt(), 2);
+		assert_eq!(SubstrateKitties::owned_count(1), 2);
+		assert_eq!(SubstrateKitties::kitty_nonce(), 2);
+
+		// minting the same kitty again still gets a new id
+		assert_ok!(SubstrateKitties::mint(&1, Some(dna(b"12345678901234")), Some(Gender::Female), None));
+		assert_eq!(Kitties::<Test>::iter().count(), 3);
+		assert_eq!(SubstrateKitties::kitty_cnt(), 3);
+		assert_eq!(SubstrateKitties::owned_count(1), 3);
+
+		// as does creating kitties revealed in the same block, which get distinct dna
+		assert_ok!(SubstrateKitties::create_kitty(Origin::signed(1), Permill::zero()));
+		assert_ok!(SubstrateKitties::create_kitty(Origin::signed(1), Permill::zero()));
+		reveal_mints();
+		assert_eq!(Kitties::<Test>::iter().count(), 5);
+		assert_eq!(SubstrateKitties::kitties_owned(&1).len(), 5);
+
+		let created = Kitties::<Test>::iter()
+			.filter(|(kitty_id, _)| SubstrateKitties::mint_deposits(kitty_id).is_some())
+			.map(|(_, kitty)| kitty.dna)
+			.collect::<Vec<_>>();
+		assert_eq!(created.len(), 2);
+		assert_ne!(created[0], created[1]);
 	});
 }
 
@@ -82,7 +141,7 @@ fn create_kitty_not_enough_reserve_balance() {
 	new_test_ext().execute_with(|| {
 		// account 3 create a kitty with insufficient balance (owned 2, required 3)
 		assert_noop!(
-			SubstrateKitties::create_kitty(Origin::signed(3)),
+			SubstrateKitties::create_kitty(Origin::signed(3), Permill::zero()),
 			pallet_balances::Error::<Test>::InsufficientBalance
 		);
 	});
@@ -97,13 +156,13 @@ fn create_kitty_overflow() {
 
 		let mut i = 1;
 		while i <= count {
-			SubstrateKitties::create_kitty(Origin::signed(1));
+			SubstrateKitties::create_kitty(Origin::signed(1), Permill::zero());
 			i += 1;
 		}
 
 		// account 1 create serveral kitties that exceed the storage limit
 		assert_noop!(
-			SubstrateKitties::create_kitty(Origin::signed(1)),
+			SubstrateKitties::create_kitty(Origin::signed(1), Permill::zero()),
 			Error::<Test>::ExceedMaxKittyOwned
 		);
 	});
@@ -115,7 +174,7 @@ fn set_price_test() {
 
 		// account 1 set the price of his first kitty
 		let bid_price = 100;
-		let kitties_owned_by_1 = SubstrateKitties::kitties_owned(1);
+		let kitties_owned_by_1 = SubstrateKitties::kitties_owned(&1);
 		assert_ok!(SubstrateKitties::set_price(Origin::signed(1), kitties_owned_by_1[0], Some(bid_price)));
 
 		// query the metadata of the kitty
@@ -135,7 +194,7 @@ fn set_price_test() {
 #[test]
 fn set_price_not_owner() {
 	new_test_ext().execute_with(|| {
-		let kitties_owned_by_2 = SubstrateKitties::kitties_owned(2);
+		let kitties_owned_by_2 = SubstrateKitties::kitties_owned(&2);
 
 		// account 1 set the price of a kitty of account 2
 		assert_noop!(
@@ -163,16 +222,14 @@ fn set_price_kitty_not_exist() {
 fn transfer_test() {
 	new_test_ext().execute_with(|| {
 		// account transfer the ownership of his first kitty to account2
-		let kitties_owned_by_1 = SubstrateKitties::kitties_owned(1);
+		let kitties_owned_by_1 = SubstrateKitties::kitties_owned(&1);
 		let kitty_id_owned_by_1_old = kitties_owned_by_1[0];
 		assert_ok!(SubstrateKitties::transfer(Origin::signed(1), kitties_owned_by_1[0], 2));
 
 		// check the ID of the kitty transfered matched
-		let kitties_owned_by_2 = SubstrateKitties::kitties_owned(2);
-		assert_eq!(
-			kitty_id_owned_by_1_old,
-			kitties_owned_by_2[1]
-		);
+		let kitties_owned_by_2 = SubstrateKitties::kitties_owned(&2);
+		assert!(kitties_owned_by_2.contains(&kitty_id_owned_by_1_old));
+		assert!(SubstrateKitties::kitties_owned(&1).is_empty());
 
 		assert_eq!(
 			events(),
@@ -183,10 +240,39 @@ fn transfer_test() {
 	});
 }
 
+#[test]
+fn transfer_keeps_owned_count() {
+	new_test_ext().execute_with(|| {
+		// account 3 receives the kitties of accounts 1 and 2, reaching `MaxKittyOwned`
+		assert_ok!(SubstrateKitties::transfer(Origin::signed(1), SubstrateKitties::kitties_owned(&1)[0], 3));
+		assert_ok!(SubstrateKitties::transfer(Origin::signed(2), SubstrateKitties::kitties_owned(&2)[0], 3));
+		assert_eq!(SubstrateKitties::owned_count(1), 0);
+		assert_eq!(SubstrateKitties::owned_count(2), 0);
+		assert_eq!(SubstrateKitties::owned_count(3), 5);
+		assert_eq!(SubstrateKitties::kitties_owned(&3).len(), 5);
+
+		// a kitty going back and forth
+		assert_ok!(SubstrateKitties::transfer(Origin::signed(3), genesis_kitty(2), 4));
+		assert_eq!(SubstrateKitties::owned_count(3), 4);
+		assert_eq!(SubstrateKitties::owned_count(4), 1);
+		assert_ok!(SubstrateKitties::transfer(Origin::signed(4), genesis_kitty(2), 3));
+		assert_eq!(SubstrateKitties::owned_count(3), 5);
+		assert_eq!(SubstrateKitties::owned_count(4), 0);
+
+		// account 3 cannot receive any more kitty
+		assert_ok!(SubstrateKitties::create_kitty(Origin::signed(4), Permill::zero()));
+		reveal_mints();
+		assert_noop!(
+			SubstrateKitties::transfer(Origin::signed(4), last_created(), 3),
+			Error::<Test>::ExceedMaxKittyOwned
+		);
+	});
+}
+
 #[test]
 fn transfer_not_owner() {
 	new_test_ext().execute_with(|| {
-		let kitties_owned_by_2 = SubstrateKitties::kitties_owned(2);
+		let kitties_owned_by_2 = SubstrateKitties::kitties_owned(&2);
 
 		// account 1 transfer a kitty of account 2 to account 3
 		assert_noop!(
@@ -200,7 +286,7 @@ fn transfer_not_owner() {
 fn transfer_to_self() {
 	new_test_ext().execute_with(|| {
 		// account 1 transfer a kitty to himself
-		let kitties_owned_by_1 = SubstrateKitties::kitties_owned(1);
+		let kitties_owned_by_1 = SubstrateKitties::kitties_owned(&1);
 		assert_noop!(
 			SubstrateKitties::transfer(Origin::signed(1), kitties_owned_by_1[0], 1),
 			Error::<Test>::TransferToSelf
@@ -225,18 +311,18 @@ fn buy_kitty_test() {
 	new_test_ext().execute_with(|| {
 		// account 1 set the price of his first kitty
 		let sell_price = 8;
-		let kitties_owned_by_1 = SubstrateKitties::kitties_owned(1);
+		let kitties_owned_by_1 = SubstrateKitties::kitties_owned(&1);
 		let kitty_index_onsell = kitties_owned_by_1[0];
 		assert_ok!(SubstrateKitties::set_price(Origin::signed(1), kitty_index_onsell, Some(sell_price)));
 
 		// account 2 buy the kitty that selled by account 1
 		let bid_price = 8;
-		assert_ok!(SubstrateKitties::buy_kitty(Origin::signed(2), kitty_index_onsell, bid_price));
+		assert_ok!(SubstrateKitties::buy_kitty(Origin::signed(2), kitty_index_onsell, Some(bid_price)));
 
 		// check the ID of the kitty bought matched and it is not on sell
-		let kitties_owned_by_2 = SubstrateKitties::kitties_owned(2);
-		let kitty_index_bought = kitties_owned_by_2[1];
-		assert_eq!(kitty_index_onsell, kitty_index_bought);
+		let kitties_owned_by_2 = SubstrateKitties::kitties_owned(&2);
+		assert!(kitties_owned_by_2.contains(&kitty_index_onsell));
+		let kitty_index_bought = kitty_index_onsell;
 
 		let kitty = SubstrateKitties::kitties(kitty_index_bought)
 			.expect("Could have this kitty ID owned by acct 1");
@@ -245,18 +331,79 @@ fn buy_kitty_test() {
 		let events = events();
 		assert_eq!(
 			events[2],
-			Event::SubstrateKitties(crate::Event::Bought(1, 2, kitty_index_onsell, bid_price))
+			Event::SubstrateKitties(crate::Event::Bought(
+				1,
+				2,
+				kitty_index_onsell,
+				sell_price,
+				Sale { price: sell_price, protocol_fee: 0, royalty: None, proceeds: sell_price },
+			))
+		);
+	});
+}
+
+
+#[test]
+fn buy_kitty_settles_at_ask_price() {
+	new_test_ext().execute_with(|| {
+		let kitty_id = SubstrateKitties::kitties_owned(&1)[0];
+		assert_ok!(SubstrateKitties::set_price(Origin::signed(1), kitty_id, Some(6)));
+
+		// account 2 is ready to pay up to 8, but only pays the ask
+		assert_ok!(SubstrateKitties::buy_kitty(Origin::signed(2), kitty_id, Some(8)));
+		assert_eq!(Balances::free_balance(2), 4);
+		assert_eq!(Balances::free_balance(1), 106);
+		System::assert_last_event(Event::SubstrateKitties(crate::Event::Bought(
+			1,
+			2,
+			kitty_id,
+			6,
+			Sale { price: 6, protocol_fee: 0, royalty: None, proceeds: 6 },
+		)));
+	});
+}
+
+#[test]
+fn buy_kitty_price_changed_before_inclusion() {
+	new_test_ext().execute_with(|| {
+		let kitty_id = SubstrateKitties::kitties_owned(&1)[0];
+		assert_ok!(SubstrateKitties::set_price(Origin::signed(1), kitty_id, Some(6)));
+
+		// account 2 buys at most at the price it saw, and account 1 raises it first
+		assert_ok!(SubstrateKitties::set_price(Origin::signed(1), kitty_id, Some(9)));
+		assert_noop!(
+			SubstrateKitties::buy_kitty(Origin::signed(2), kitty_id, Some(6)),
+			Error::<Test>::KittyBidPriceTooLow
 		);
+
+		// a lowered price goes to the buyer
+		assert_ok!(SubstrateKitties::set_price(Origin::signed(1), kitty_id, Some(5)));
+		assert_ok!(SubstrateKitties::buy_kitty(Origin::signed(2), kitty_id, Some(6)));
+		assert_eq!(Balances::free_balance(2), 5);
+		assert_eq!(Balances::free_balance(1), 105);
 	});
 }
 
+#[test]
+fn buy_kitty_without_max_price() {
+	new_test_ext().execute_with(|| {
+		let kitty_id = SubstrateKitties::kitties_owned(&1)[0];
+		assert_ok!(SubstrateKitties::set_price(Origin::signed(1), kitty_id, Some(6)));
+		assert_ok!(SubstrateKitties::set_price(Origin::signed(1), kitty_id, Some(9)));
+
+		// without a max price the buyer pays whatever the ask is at inclusion
+		assert_ok!(SubstrateKitties::buy_kitty(Origin::signed(2), kitty_id, None));
+		assert_eq!(Balances::free_balance(2), 1);
+		assert_eq!(Balances::free_balance(1), 109);
+	});
+}
 
 #[test]
 fn buy_kitty_not_exit() {
 	new_test_ext().execute_with(|| {
 		// account 1 buy a kitty not exist
 		assert_noop!(
-			SubstrateKitties::buy_kitty(Origin::signed(2), H256::from_low_u64_be(2), 8),
+			SubstrateKitties::buy_kitty(Origin::signed(2), H256::from_low_u64_be(2), Some(8)),
 			Error::<Test>::KittyNotExist
 		);
 	});
@@ -267,14 +414,14 @@ fn buy_kitty_bid_price_low() {
 	new_test_ext().execute_with(|| {
 		// account 1 set the price of his first kitty at 100
 		let sell_price = 100;
-		let kitties_owned_by_1 = SubstrateKitties::kitties_owned(1);
+		let kitties_owned_by_1 = SubstrateKitties::kitties_owned(&1);
 		let kitty_index_onsell = kitties_owned_by_1[0];
 		assert_ok!(SubstrateKitties::set_price(Origin::signed(1), kitty_index_onsell, Some(sell_price)));
 
 		// account 2 bid at 8 less than 1oo
 		let bid_price = 8;
 		assert_noop!(
-			SubstrateKitties::buy_kitty(Origin::signed(2), kitty_index_onsell, bid_price),
+			SubstrateKitties::buy_kitty(Origin::signed(2), kitty_index_onsell, Some(bid_price)),
 			Error::<Test>::KittyBidPriceTooLow
 		);
 	});
@@ -284,13 +431,13 @@ fn buy_kitty_bid_price_low() {
 fn buy_kitty_not_for_sale() {
 	new_test_ext().execute_with(|| {
 		// get a kitty of account 1 which is not on sale
-		let kitties_owned_by_1 = SubstrateKitties::kitties_owned(1);
+		let kitties_owned_by_1 = SubstrateKitties::kitties_owned(&1);
 		let kitty_index_not_sell = kitties_owned_by_1[0];
 
 		// account 2 buy a kitty not for sell
 		let bid_price = 8;
 		assert_noop!(
-			SubstrateKitties::buy_kitty(Origin::signed(2), kitty_index_not_sell, bid_price),
+			SubstrateKitties::buy_kitty(Origin::signed(2), kitty_index_not_sell, Some(bid_price)),
 			Error::<Test>::KittyNotForSale
 		);
 	});
@@ -300,7 +447,7 @@ fn buy_kitty_not_for_sale() {
 fn kitty_on_sale() -> SubstrateKitties::Config {
 	// account 1 set the price of his first kitty at 8
 	let sell_price = 8;
-	let kitties_owned_by_1 = SubstrateKitties::kitties_owned(1);
+	let kitties_owned_by_1 = SubstrateKitties::kitties_owned(&1);
 	let kitty_index_onsell = kitties_owned_by_1[0];
 	assert_ok!(SubstrateKitties::set_price(Origin::signed(1), kitty_index_onsell, Some(sell_price)));
 	kitty_index_onsell
@@ -311,14 +458,14 @@ fn buy_kitty_of_own() {
 	new_test_ext().execute_with(|| {
 		// account 1 set the price of his first kitty at 8
 		let sell_price = 8;
-		let kitties_owned_by_1 = SubstrateKitties::kitties_owned(1);
+		let kitties_owned_by_1 = SubstrateKitties::kitties_owned(&1);
 		let kitty_index_onsell = kitties_owned_by_1[0];
 		assert_ok!(SubstrateKitties::set_price(Origin::signed(1), kitty_index_onsell, Some(sell_price)));
 
 		// account 1 but a kitty of his own
 		let bid_price = 8;
 		assert_noop!(
-			SubstrateKitties::buy_kitty(Origin::signed(1), kitty_index_onsell, bid_price),
+			SubstrateKitties::buy_kitty(Origin::signed(1), kitty_index_onsell, Some(bid_price)),
 			Error::<Test>::BuyerIsKittyOwner
 		);
 	});
@@ -327,16 +474,16 @@ fn buy_kitty_of_own() {
 #[test]
 fn buy_kitty_without_sufficient_balance() {
 	new_test_ext().execute_with(|| {
-		// account 1 set the price of his first kitty at 8
-		let sell_price = 8;
-		let kitties_owned_by_1 = SubstrateKitties::kitties_owned(1);
+		// account 1 set the price of his first kitty at 12
+		let sell_price = 12;
+		let kitties_owned_by_1 = SubstrateKitties::kitties_owned(&1);
 		let kitty_index_onsell = kitties_owned_by_1[0];
 		assert_ok!(SubstrateKitties::set_price(Origin::signed(1), kitty_index_onsell, Some(sell_price)));
 
 		// account 2 buy the kitty at 12
 		let bid_price = 12;
 		assert_noop!(
-			SubstrateKitties::buy_kitty(Origin::signed(2), kitty_index_onsell, bid_price),
+			SubstrateKitties::buy_kitty(Origin::signed(2), kitty_index_onsell, Some(bid_price)),
 			Error::<Test>::NotEnoughBalance
 		);
 	});
@@ -345,16 +492,16 @@ fn buy_kitty_without_sufficient_balance() {
 #[test]
 fn buy_kitty_not_alive() {
 	new_test_ext().execute_with(|| {
-		// account 1 set the price of his first kitty at 8
-		let sell_price = 8;
-		let kitties_owned_by_1 = SubstrateKitties::kitties_owned(1);
+		// account 1 set the price of his first kitty at 10
+		let sell_price = 10;
+		let kitties_owned_by_1 = SubstrateKitties::kitties_owned(&1);
 		let kitty_index_onsell = kitties_owned_by_1[0];
 		assert_ok!(SubstrateKitties::set_price(Origin::signed(1), kitty_index_onsell, Some(sell_price)));
 
 		// account 2 transfer a kitty
 		let bid_price = 10;
 		assert_noop!(
-			SubstrateKitties::buy_kitty(Origin::signed(2), kitty_index_onsell, bid_price),
+			SubstrateKitties::buy_kitty(Origin::signed(2), kitty_index_onsell, Some(bid_price)),
 			pallet_balances::Error::<Test>::KeepAlive
 		);
 	});
@@ -364,32 +511,120 @@ fn buy_kitty_not_alive() {
 fn breed_kitty_test() {
 	new_test_ext().execute_with(|| {
 		// account 3 breed a kitty
-		let kitties_owned_by_3 = SubstrateKitties::kitties_owned(3);
-		let parent_index_1 = kitties_owned_by_3[0]; // (3, *b"123456789012345e", Gender::Male)
-		let parent_index_2 = kitties_owned_by_3[2]; // (3, *b"1234567890123466", Gender::Female)
+		let parent_index_1 = genesis_kitty(2); // Gender::Male
+		let parent_index_2 = genesis_kitty(4); // Gender::Female
 
-		assert_ok!(SubstrateKitties::breed_kitty(Origin::signed(3), parent_index_1, parent_index_2));
+		assert_ok!(SubstrateKitties::breed_kitty(Origin::signed(3), parent_index_1, parent_index_2, Permill::zero()));
+		assert_eq!(
+			events(),
+			[
+				Event::SubstrateKitties(crate::Event::MintRequested(3, 3))
+			]
+		);
+		assert_eq!(SubstrateKitties::pending_mints(3)[0].parents, Some((parent_index_1, parent_index_2)));
 
-		let kitties_owned_by_3 = SubstrateKitties::kitties_owned(3);
-		let new_kitty_index = kitties_owned_by_3[3];
+		// the baby kitty is revealed two blocks later
+		reveal_mints();
+		let new_kitty_index = last_created();
 		assert_eq!(
 			events(),
 			[
 				Event::SubstrateKitties(crate::Event::Created(3, new_kitty_index))
 			]
 		);
+		assert_eq!(SubstrateKitties::mint_deposits(new_kitty_index), None);
+
+		// the baby kitty is one generation younger than its parents
+		let info = SubstrateKitties::kitty_info(new_kitty_index).expect("the baby kitty exists");
+		assert_eq!(info.traits.generation, 1);
+	});
+}
+
+#[test]
+fn bred_generation_follows_the_parents() {
+	new_test_ext().execute_with(|| {
+		// the generation of a kitty is the one it was stored with, not the one of its dna
+		let (father, mother) = (genesis_kitty(2), genesis_kitty(4));
+		Kitties::<Test>::mutate(father, |kitty| kitty.as_mut().unwrap().generation = 3);
+
+		assert_ok!(SubstrateKitties::breed_kitty(Origin::signed(3), father, mother, Permill::zero()));
+		reveal_mints();
+
+		let kitty = SubstrateKitties::kitties(last_created()).expect("the baby kitty exists");
+		assert_eq!(kitty.generation, 4);
+		assert_eq!(genetics::generation(&kitty.dna), 4);
+	});
+}
+
+#[test]
+fn genesis_kitties_decode() {
+	new_test_ext().execute_with(|| {
+		let kitty_id = SubstrateKitties::kitties_owned(&1)[0];
+		let info = SubstrateKitties::kitty_info(kitty_id).expect("Could have this kitty ID owned by acct 1");
+
+		// the dominant alleles are '1', '3' and '5'
+		assert_eq!(
+			info.traits,
+			KittyTraits {
+				fur_colour: FurColour::White,
+				eye_colour: EyeColour::Copper,
+				pattern: Pattern::Pointed,
+				rarity: RarityTier::Common,
+				generation: 0,
+			}
+		);
+
+		// all genesis kitties are generation 0, whatever their dna recorded in the generation bytes
+		for owner in 1..=3 {
+			assert!(SubstrateKitties::owned_kitty_infos(&owner)
+				.iter()
+				.all(|info| info.traits.generation == 0));
+		}
 	});
 }
 
+#[test]
+fn genetics_breed_inherits_alleles() {
+	let mut dna1 = dna(b"12345678901234");
+	let mut dna2 = dna(b"12345678901234");
+	// a rare fur allele in the dominant slot of both parents, a common one in the recessive
+	// slot of the second parent
+	dna1[0..2].copy_from_slice(&[0xe1, 0xf0]);
+	dna2[0..2].copy_from_slice(&[0x05, 0xe2]);
+	dna1[14..].copy_from_slice(&3u16.to_be_bytes());
+	dna2[14..].copy_from_slice(&1u16.to_be_bytes());
+
+	// both parents pass on a rare allele: the rare fur shows
+	let child = genetics::breed(&dna1, &dna2, [0b10; 16]);
+	assert_eq!(child[0..2], [0xe1, 0xe2]);
+	assert_eq!(genetics::decode(&child).fur_colour, FurColour::White);
+	assert_eq!(genetics::decode(&child).rarity, RarityTier::Uncommon);
+
+	// a common allele is dominant over a rare one, which is still carried
+	let child = genetics::breed(&dna1, &dna2, [0b00; 16]);
+	assert_eq!(child[0..2], [0x05, 0xe1]);
+	assert_eq!(genetics::decode(&child).fur_colour, FurColour::Chocolate);
+	assert_eq!(genetics::decode(&child).rarity, RarityTier::Common);
+
+	// one generation after the older parent
+	assert_eq!(genetics::decode(&child).generation, 4);
+	dna1[14..].copy_from_slice(&u16::MAX.to_be_bytes());
+	assert_eq!(genetics::generation(&genetics::breed(&dna1, &dna2, [0; 16])), u16::MAX);
+
+	// minted dna is generation 0, with the dominant allele first
+	let minted = genetics::from_random([0xff; 16]);
+	assert_eq!(genetics::generation(&minted), 0);
+	assert_eq!(genetics::decode(&minted).rarity, RarityTier::Legendary);
+}
+
 #[test]
 fn breed_kitty_from_same_kitty() {
 	new_test_ext().execute_with(|| {
-		let kitties_owned_by_3 = SubstrateKitties::kitties_owned(3);
-		let parent_index_1 = kitties_owned_by_3[0]; // (3, *b"123456789012345e", Gender::Male)
+		let parent_index_1 = genesis_kitty(2); // Gender::Male
 
 		// account 3 breed a kitty by the same kitty
 		assert_noop!(
-			SubstrateKitties::breed_kitty(Origin::signed(3), parent_index_1, parent_index_1),
+			SubstrateKitties::breed_kitty(Origin::signed(3), parent_index_1, parent_index_1, Permill::zero()),
 			Error::<Test>::SameParentKittyId
 		);
 	});
@@ -399,12 +634,11 @@ fn breed_kitty_from_same_kitty() {
 #[test]
 fn breed_kitty_not_exist() {
 	new_test_ext().execute_with(|| {
-		let kitties_owned_by_3 = SubstrateKitties::kitties_owned(3);
-		let parent_index_1 = kitties_owned_by_3[0];
+		let parent_index_1 = genesis_kitty(2);
 
 		// account 3 breed a kitty by the same kitty
 		assert_noop!(
-			SubstrateKitties::breed_kitty(Origin::signed(3), parent_index_1, parent_index_1),
+			SubstrateKitties::breed_kitty(Origin::signed(3), parent_index_1, parent_index_1, Permill::zero()),
 			Error::<Test>::KittyNotExist
 		);
 	});
@@ -413,14 +647,1465 @@ fn breed_kitty_not_exist() {
 #[test]
 fn breed_kitty_from_same_kitty_gender() {
 	new_test_ext().execute_with(|| {
-		let kitties_owned_by_3 = SubstrateKitties::kitties_owned(3);
-		let parent_index_1 = kitties_owned_by_3[0];
-		let parent_index_2 = kitties_owned_by_3[1]; // (3, *b"1234567890123462", Gender::Male)
+		let parent_index_1 = genesis_kitty(2);
+		let parent_index_2 = genesis_kitty(3); // Gender::Male
 
 		// account 3 breed a kitty by the same kitty
 		assert_noop!(
-			SubstrateKitties::breed_kitty(Origin::signed(3), parent_index_1, parent_index_2),
+			SubstrateKitties::breed_kitty(Origin::signed(3), parent_index_1, parent_index_2, Permill::zero()),
 			Error::<Test>::SameParentGender
 		);
 	});
 }
+
+#[test]
+fn breed_kitty_records_lineage() {
+	new_test_ext().execute_with(|| {
+		let (father, mother) = (genesis_kitty(2), genesis_kitty(4));
+
+		assert_ok!(SubstrateKitties::breed_kitty(Origin::signed(3), father, mother, Permill::zero()));
+		reveal_mints();
+
+		// the baby kitty is born at its reveal
+		let child = SubstrateKitties::kitties(last_created()).expect("the baby kitty exists");
+		assert_eq!(child.parents, Some((father, mother)));
+		assert_eq!(child.generation, 1);
+		assert_eq!(child.birth_block, 3);
+		// a generation 1 kitty waits twice the base cooldown
+		assert_eq!(child.cooldown_end, 3 + 2 * 2);
+
+		// the genesis kitties are generation 0, without parents
+		let father = SubstrateKitties::kitties(father).expect("the father exists");
+		assert_eq!(father.parents, None);
+		assert_eq!(father.generation, 0);
+		assert_eq!(father.cooldown_end, 1 + 2);
+	});
+}
+
+#[test]
+fn breed_kitty_on_cooldown() {
+	new_test_ext().execute_with(|| {
+		let (father, mother) = (genesis_kitty(2), genesis_kitty(4));
+
+		assert_ok!(SubstrateKitties::breed_kitty(Origin::signed(3), father, mother, Permill::zero()));
+
+		// the parents rest for 2 blocks
+		run_to_block(2);
+		assert_noop!(
+			SubstrateKitties::breed_kitty(Origin::signed(3), father, mother, Permill::zero()),
+			Error::<Test>::KittyOnCooldown
+		);
+
+		run_to_block(3);
+		assert_ok!(SubstrateKitties::breed_kitty(Origin::signed(3), father, mother, Permill::zero()));
+	});
+}
+
+#[test]
+fn breed_kitty_related() {
+	new_test_ext().execute_with(|| {
+		let (father, mother) = (genesis_kitty(2), genesis_kitty(4));
+
+		assert_ok!(SubstrateKitties::breed_kitty(Origin::signed(3), father, mother, Permill::zero()));
+		reveal_mints();
+		let first_child = last_created();
+		assert_ok!(SubstrateKitties::breed_kitty(Origin::signed(3), father, mother, Permill::zero()));
+		reveal_mints();
+		let second_child = last_created();
+
+		// wait until both children are out of their cooldown
+		run_to_block(9);
+
+		// a parent with its child
+		assert_noop!(
+			SubstrateKitties::breed_kitty(Origin::signed(3), mother, first_child, Permill::zero()),
+			Error::<Test>::KittiesRelated
+		);
+		assert_noop!(
+			SubstrateKitties::breed_kitty(Origin::signed(3), second_child, father, Permill::zero()),
+			Error::<Test>::KittiesRelated
+		);
+
+		// two siblings
+		assert_noop!(
+			SubstrateKitties::breed_kitty(Origin::signed(3), first_child, second_child, Permill::zero()),
+			Error::<Test>::KittiesRelated
+		);
+	});
+}
+
+#[test]
+fn ancestry_query_test() {
+	new_test_ext().execute_with(|| {
+		let (father, mother) = (genesis_kitty(2), genesis_kitty(4));
+		let other = SubstrateKitties::kitties_owned(&2)[0];
+
+		assert_ok!(SubstrateKitties::breed_kitty(Origin::signed(3), father, mother, Permill::zero()));
+		reveal_mints();
+		let child_id = last_created();
+
+		// a grandchild of `father` and `mother`, and a kitty with an unknown parent
+		let mut grandchild = SubstrateKitties::kitties(child_id).expect("the child exists");
+		grandchild.parents = Some((child_id, other));
+		let grandchild_id = H256::from_low_u64_be(100);
+		Kitties::<Test>::insert(grandchild_id, grandchild.clone());
+		grandchild.parents = Some((grandchild_id, H256::from_low_u64_be(999)));
+		let orphan_id = H256::from_low_u64_be(101);
+		Kitties::<Test>::insert(orphan_id, grandchild);
+
+		assert_eq!(SubstrateKitties::ancestry(grandchild_id, 0), vec![]);
+		assert_eq!(SubstrateKitties::ancestry(grandchild_id, 1), vec![(1, child_id), (1, other)]);
+		assert_eq!(
+			SubstrateKitties::ancestry(grandchild_id, 10),
+			vec![(1, child_id), (1, other), (2, father), (2, mother)]
+		);
+
+		// unknown ancestors are skipped
+		assert_eq!(SubstrateKitties::ancestry(orphan_id, 1), vec![(1, grandchild_id)]);
+
+		// a kitty of `grandchild` and `child`, whose shared ancestors are listed once
+		let mut inbred = SubstrateKitties::kitties(child_id).expect("the child exists");
+		inbred.parents = Some((grandchild_id, child_id));
+		let inbred_id = H256::from_low_u64_be(102);
+		Kitties::<Test>::insert(inbred_id, inbred);
+		assert_eq!(
+			SubstrateKitties::ancestry(inbred_id, 10),
+			vec![(1, grandchild_id), (1, child_id), (2, other), (2, father), (2, mother)]
+		);
+
+		// genesis and unknown kitties have no ancestry
+		assert_eq!(SubstrateKitties::ancestry(father, 10), vec![]);
+		assert_eq!(SubstrateKitties::ancestry(H256::from_low_u64_be(2), 10), vec![]);
+	});
+}
+
+#[test]
+fn migrate_to_v1() {
+	new_test_ext().execute_with(|| {
+		let mut old_dna = dna(b"12345678901234");
+		old_dna[14..].copy_from_slice(&5u16.to_be_bytes());
+		let old_kitty = migrations::v1::OldKitty { dna: old_dna, price: Some(7u64), gender: Gender::Male, owner: 4u64 };
+		let kitty_id = H256::from_low_u64_be(100);
+		frame_support::storage::unhashed::put(&Kitties::<Test>::hashed_key_for(kitty_id), &old_kitty);
+		StorageVersion::new(0).put::<SubstrateKitties>();
+
+		migrations::v1::migrate::<Test>();
+
+		assert_eq!(SubstrateKitties::on_chain_storage_version(), 1);
+		let kitty = SubstrateKitties::kitties(kitty_id).expect("the kitty is migrated");
+		assert_eq!(kitty.dna, dna(b"12345678901234"));
+		assert_eq!(kitty.price, Some(7));
+		assert_eq!(kitty.gender, Gender::Male);
+		assert_eq!(kitty.owner, 4);
+		assert_eq!(kitty.parents, None);
+		assert_eq!(kitty.generation, 0);
+		assert_eq!(kitty.birth_block, 0);
+		assert_eq!(kitty.cooldown_end, 0);
+
+		// the migration runs once
+		Kitties::<Test>::mutate(kitty_id, |kitty| kitty.as_mut().map(|kitty| kitty.cooldown_end = 9));
+		migrations::v1::migrate::<Test>();
+		assert_eq!(SubstrateKitties::kitties(kitty_id).map(|kitty| kitty.cooldown_end), Some(9));
+	});
+}
+
+// The storage key of the version 1 `KittiesOwned` entry of `owner`.
+fn v1_kitties_owned_key(owner: u64) -> Vec<u8> {
+	Twox64Concat::hash(&owner.encode())
+}
+
+// A snapshot of the mock genesis state in the storage version 1 layout, where `KittiesOwned` keeps a
+// `BoundedVec` per owner.
+fn v1_snapshot() -> sp_io::TestExternalities {
+	let mut ext = new_test_ext();
+	ext.execute_with(|| {
+		for owner in 1..=3 {
+			let owned = SubstrateKitties::kitties_owned(&owner);
+			KittiesOwned::<Test>::remove_prefix(&owner, None);
+			OwnedCount::<Test>::remove(&owner);
+			migration::put_storage_value(b"SubstrateKitties", b"KittiesOwned", &v1_kitties_owned_key(owner), owned);
+		}
+		StorageVersion::new(1).put::<SubstrateKitties>();
+	});
+	ext.commit_all().expect("the snapshot is committed");
+	ext
+}
+
+#[test]
+fn migrate_to_v2() {
+	v1_snapshot().execute_with(|| {
+		let snapshot = (1..=3)
+			.map(|owner| {
+				let mut owned = migration::get_storage_value::<Vec<H256>>(
+					b"SubstrateKitties", b"KittiesOwned", &v1_kitties_owned_key(owner)
+				).expect("the snapshot has the owned kitties");
+				owned.sort();
+				(owner, owned)
+			})
+			.collect::<Vec<_>>();
+		assert_eq!(SubstrateKitties::owned_count(3), 0);
+
+		#[cfg(feature = "try-runtime")]
+		assert_ok!(<SubstrateKitties as OnRuntimeUpgrade>::pre_upgrade());
+		<SubstrateKitties as OnRuntimeUpgrade>::on_runtime_upgrade();
+		#[cfg(feature = "try-runtime")]
+		assert_ok!(<SubstrateKitties as OnRuntimeUpgrade>::post_upgrade());
+
+		assert_eq!(SubstrateKitties::on_chain_storage_version(), 4);
+		for (owner, owned) in snapshot {
+			let mut migrated = SubstrateKitties::kitties_owned(&owner);
+			migrated.sort();
+			assert_eq!(migrated, owned);
+			assert_eq!(SubstrateKitties::owned_count(owner), owned.len() as u32);
+			assert_eq!(
+				migration::get_storage_value::<Vec<H256>>(b"SubstrateKitties", b"KittiesOwned", &v1_kitties_owned_key(owner)),
+				None
+			);
+		}
+
+		// the ownership index keeps working
+		assert_ok!(SubstrateKitties::transfer(Origin::signed(3), genesis_kitty(2), 1));
+		assert_eq!(SubstrateKitties::owned_count(3), 2);
+		assert_eq!(SubstrateKitties::owned_count(1), 2);
+
+		// and the migration runs once
+		<SubstrateKitties as OnRuntimeUpgrade>::on_runtime_upgrade();
+		assert_eq!(SubstrateKitties::owned_count(1), 2);
+		assert_eq!(SubstrateKitties::kitties_owned(&1).len(), 2);
+	});
+}
+
+#[test]
+fn migrate_to_v3() {
+	new_test_ext().execute_with(|| {
+		// account 1 created a kitty before its deposit was recorded
+		assert_ok!(SubstrateKitties::create_kitty(Origin::signed(1), Permill::zero()));
+		reveal_mints();
+		let kitty_id = last_created();
+		MintDeposits::<Test>::remove(kitty_id);
+		StorageVersion::new(2).put::<SubstrateKitties>();
+
+		// and has an unrelated bid reserved
+		assert_ok!(SubstrateKitties::create_auction(Origin::signed(2), genesis_kitty(1), 5, 3));
+		assert_ok!(SubstrateKitties::bid(Origin::signed(1), genesis_kitty(1), 10));
+		assert_eq!(Balances::reserved_balance(1), 13);
+
+		#[cfg(feature = "try-runtime")]
+		assert_ok!(<SubstrateKitties as OnRuntimeUpgrade>::pre_upgrade());
+		<SubstrateKitties as OnRuntimeUpgrade>::on_runtime_upgrade();
+		#[cfg(feature = "try-runtime")]
+		assert_ok!(<SubstrateKitties as OnRuntimeUpgrade>::post_upgrade());
+
+		// the reserve does not tell who paid the deposit, so none is recorded
+		assert_eq!(SubstrateKitties::on_chain_storage_version(), 4);
+		assert_eq!(SubstrateKitties::mint_deposits(kitty_id), None);
+		assert_eq!(MintDeposits::<Test>::iter().count(), 0);
+
+		// burning the kitty leaves the bid reserved
+		assert_ok!(SubstrateKitties::burn(Origin::signed(1), kitty_id));
+		assert_eq!(Balances::reserved_balance(1), 13);
+
+		// which still pays for the auction, the old deposit stays reserved
+		run_to_block(6);
+		assert_eq!(SubstrateKitties::kitties(genesis_kitty(1)).map(|kitty| kitty.owner), Some(1));
+		assert_eq!(Balances::reserved_balance(1), 3);
+	});
+}
+
+#[test]
+fn migrate_to_v4() {
+	new_test_ext().execute_with(|| {
+		// account 1 created a kitty revealed at block 3, before the sire fees were recorded
+		assert_ok!(SubstrateKitties::create_kitty(Origin::signed(1), Permill::from_percent(5)));
+		let old_pending = vec![migrations::v4::OldPendingMint {
+			owner: 1u64,
+			deposit: 3u64,
+			royalty: Permill::from_percent(5),
+			parents: None::<(H256, H256)>,
+		}];
+		frame_support::storage::unhashed::put(&PendingMints::<Test>::hashed_key_for(3), &old_pending);
+		StorageVersion::new(3).put::<SubstrateKitties>();
+
+		#[cfg(feature = "try-runtime")]
+		assert_ok!(<SubstrateKitties as OnRuntimeUpgrade>::pre_upgrade());
+		<SubstrateKitties as OnRuntimeUpgrade>::on_runtime_upgrade();
+		#[cfg(feature = "try-runtime")]
+		assert_ok!(<SubstrateKitties as OnRuntimeUpgrade>::post_upgrade());
+
+		assert_eq!(SubstrateKitties::on_chain_storage_version(), 4);
+		let pending = SubstrateKitties::pending_mints(3);
+		assert_eq!(pending.len(), 1);
+		assert_eq!(pending[0].owner, 1);
+		assert_eq!(pending[0].deposit, 3);
+		assert_eq!(pending[0].royalty, Permill::from_percent(5));
+		assert_eq!(pending[0].parents, None);
+		assert_eq!(pending[0].sire_fee, None);
+
+		// the kitty is still revealed
+		run_to_block(3);
+		let kitty_id = last_created();
+		assert_eq!(SubstrateKitties::kitties(kitty_id).map(|kitty| kitty.owner), Some(1));
+		assert_eq!(SubstrateKitties::mint_deposits(kitty_id), Some((1, 3)));
+		assert_eq!(Balances::reserved_balance(1), 3);
+	});
+}
+
+#[test]
+fn kitty_info_query_test() {
+	new_test_ext().execute_with(|| {
+		let kitty_id = SubstrateKitties::kitties_owned(&1)[0];
+
+		// query the decoded kitty by its id
+		let info = SubstrateKitties::kitty_info(kitty_id)
+			.expect("Could have this kitty ID owned by acct 1");
+		assert_eq!(info.id, kitty_id);
+		assert_eq!(info.dna, dna(b"12345678901234"));
+		assert_eq!(info.gender, Gender::Female);
+		assert_eq!(info.owner, 1);
+		assert_eq!(info.price, None);
+
+		// query a kitty not exist
+		assert_eq!(SubstrateKitties::kitty_info(H256::from_low_u64_be(2)), None);
+
+		// query all kitties owned by account 3
+		let owned = SubstrateKitties::owned_kitty_infos(&3);
+		assert_eq!(owned.len(), 3);
+		assert!(owned.iter().all(|info| info.owner == 3));
+	});
+}
+
+#[test]
+fn kitties_for_sale_query_test() {
+	new_test_ext().execute_with(|| {
+		assert_eq!(SubstrateKitties::kitties_for_sale(0, 10), vec![]);
+
+		// account 3 put all his kitties on sale
+		let kitties_owned_by_3 = SubstrateKitties::kitties_owned(&3);
+		for kitty_id in kitties_owned_by_3.iter() {
+			assert_ok!(SubstrateKitties::set_price(Origin::signed(3), *kitty_id, Some(5)));
+		}
+
+		let for_sale = SubstrateKitties::kitties_for_sale(0, 10);
+		assert_eq!(for_sale.len(), 3);
+		assert!(for_sale.iter().all(|info| info.price == Some(5)));
+
+		// paging through the listings
+		assert_eq!(SubstrateKitties::kitties_for_sale(1, 1), vec![for_sale[1].clone()]);
+		assert_eq!(SubstrateKitties::kitties_for_sale(3, 10), vec![]);
+	});
+}
+
+#[test]
+fn auction_test() {
+	new_test_ext().execute_with(|| {
+		// account 1 put his first kitty under the hammer at block 1, ending at block 4
+		let kitty_id = SubstrateKitties::kitties_owned(&1)[0];
+		assert_ok!(SubstrateKitties::create_auction(Origin::signed(1), kitty_id, 5, 3));
+		assert_eq!(SubstrateKitties::auctions_ending_at(4).into_inner(), vec![kitty_id]);
+
+		// account 2 bids and the amount is reserved
+		assert_ok!(SubstrateKitties::bid(Origin::signed(2), kitty_id, 6));
+		assert_eq!(Balances::reserved_balance(2), 6);
+
+		// account 4 must outbid account 2
+		assert_noop!(
+			SubstrateKitties::bid(Origin::signed(4), kitty_id, 6),
+			Error::<Test>::BidNotHighEnough
+		);
+		assert_ok!(SubstrateKitties::bid(Origin::signed(4), kitty_id, 8));
+
+		// the reserve of the outbid account 2 is released
+		assert_eq!(Balances::reserved_balance(2), 0);
+		assert_eq!(Balances::reserved_balance(4), 8);
+
+		// the auction is settled when reaching the end block
+		events();
+		run_to_block(4);
+		assert_eq!(SubstrateKitties::auctions(kitty_id), None);
+		assert_eq!(SubstrateKitties::kitties(kitty_id).unwrap().owner, 4);
+		assert_eq!(SubstrateKitties::kitties_owned(&4), vec![kitty_id]);
+		assert_eq!(Balances::reserved_balance(4), 0);
+		assert_eq!(Balances::free_balance(4), 42);
+		assert_eq!(Balances::free_balance(1), 108);
+
+		assert!(events().contains(
+			&Event::SubstrateKitties(crate::Event::AuctionSettled(
+				1,
+				kitty_id,
+				Some((4, Sale { price: 8, protocol_fee: 0, royalty: None, proceeds: 8 })),
+			))
+		));
+	});
+}
+
+#[test]
+fn auction_without_bid_test() {
+	new_test_ext().execute_with(|| {
+		let kitty_id = SubstrateKitties::kitties_owned(&1)[0];
+		assert_ok!(SubstrateKitties::create_auction(Origin::signed(1), kitty_id, 5, 3));
+
+		// nobody bids, the kitty stays with account 1 and is unlocked
+		run_to_block(4);
+		assert_eq!(SubstrateKitties::auctions(kitty_id), None);
+		assert_eq!(SubstrateKitties::kitties(kitty_id).unwrap().owner, 1);
+		assert_ok!(SubstrateKitties::transfer(Origin::signed(1), kitty_id, 2));
+	});
+}
+
+#[test]
+fn auction_invalid_duration() {
+	new_test_ext().execute_with(|| {
+		let kitty_id = SubstrateKitties::kitties_owned(&1)[0];
+		assert_noop!(
+			SubstrateKitties::create_auction(Origin::signed(1), kitty_id, 5, 0),
+			Error::<Test>::InvalidAuctionDuration
+		);
+		assert_noop!(
+			SubstrateKitties::create_auction(Origin::signed(1), kitty_id, 5, MaxAuctionDuration::get() + 1),
+			Error::<Test>::InvalidAuctionDuration
+		);
+	});
+}
+
+#[test]
+fn auction_not_owner() {
+	new_test_ext().execute_with(|| {
+		let kitty_id = SubstrateKitties::kitties_owned(&2)[0];
+		assert_noop!(
+			SubstrateKitties::create_auction(Origin::signed(1), kitty_id, 5, 3),
+			Error::<Test>::NotKittyOwner
+		);
+	});
+}
+
+#[test]
+fn bid_invalid() {
+	new_test_ext().execute_with(|| {
+		let kitty_id = SubstrateKitties::kitties_owned(&1)[0];
+
+		// no auction for the kitty yet
+		assert_noop!(
+			SubstrateKitties::bid(Origin::signed(2), kitty_id, 6),
+			Error::<Test>::AuctionNotExist
+		);
+
+		assert_ok!(SubstrateKitties::create_auction(Origin::signed(1), kitty_id, 5, 3));
+
+		// the seller cannot bid
+		assert_noop!(
+			SubstrateKitties::bid(Origin::signed(1), kitty_id, 6),
+			Error::<Test>::BuyerIsKittyOwner
+		);
+
+		// the bid must reach the reserve
+		assert_noop!(
+			SubstrateKitties::bid(Origin::signed(2), kitty_id, 4),
+			Error::<Test>::BidBelowReserve
+		);
+
+		// the bidder must be able to reserve the amount
+		assert_noop!(
+			SubstrateKitties::bid(Origin::signed(2), kitty_id, 11),
+			pallet_balances::Error::<Test>::InsufficientBalance
+		);
+
+		// no bid is accepted at the end block
+		System::set_block_number(4);
+		assert_noop!(
+			SubstrateKitties::bid(Origin::signed(2), kitty_id, 6),
+			Error::<Test>::AuctionEnded
+		);
+	});
+}
+
+#[test]
+fn auction_locks_kitty() {
+	new_test_ext().execute_with(|| {
+		let kitty_id = genesis_kitty(2); // Gender::Male
+		let mate_id = genesis_kitty(4); // Gender::Female
+		assert_ok!(SubstrateKitties::create_auction(Origin::signed(3), kitty_id, 5, 3));
+
+		assert_noop!(
+			SubstrateKitties::create_auction(Origin::signed(3), kitty_id, 5, 3),
+			Error::<Test>::KittyInAuction
+		);
+		assert_noop!(
+			SubstrateKitties::transfer(Origin::signed(3), kitty_id, 1),
+			Error::<Test>::KittyInAuction
+		);
+		assert_noop!(
+			SubstrateKitties::set_price(Origin::signed(3), kitty_id, Some(5)),
+			Error::<Test>::KittyInAuction
+		);
+		assert_noop!(
+			SubstrateKitties::breed_kitty(Origin::signed(3), kitty_id, mate_id, Permill::zero()),
+			Error::<Test>::KittyInAuction
+		);
+	});
+}
+
+#[test]
+fn offer_sire_test() {
+	new_test_ext().execute_with(|| {
+		let sire_id = SubstrateKitties::kitties_owned(&2)[0]; // (2, *b"123456789012345a", Gender::Male)
+
+		assert_noop!(
+			SubstrateKitties::offer_sire(Origin::signed(1), sire_id, Some(5)),
+			Error::<Test>::NotKittyOwner
+		);
+
+		assert_ok!(SubstrateKitties::offer_sire(Origin::signed(2), sire_id, Some(5)));
+		assert_eq!(SubstrateKitties::sire_offers(sire_id), Some(5));
+
+		// withdraw the offer
+		assert_ok!(SubstrateKitties::offer_sire(Origin::signed(2), sire_id, None));
+		assert_eq!(SubstrateKitties::sire_offers(sire_id), None);
+
+		assert_eq!(
+			events(),
+			[
+				Event::SubstrateKitties(crate::Event::SireOffered(2, sire_id, Some(5))),
+				Event::SubstrateKitties(crate::Event::SireOffered(2, sire_id, None)),
+			]
+		);
+	});
+}
+
+#[test]
+fn breed_with_sire_test() {
+	new_test_ext().execute_with(|| {
+		let dam_id = SubstrateKitties::kitties_owned(&1)[0]; // (1, *b"1234567890123456", Gender::Female)
+		let sire_id = SubstrateKitties::kitties_owned(&2)[0]; // (2, *b"123456789012345a", Gender::Male)
+
+		// only offered sires can be bred with
+		assert_noop!(
+			SubstrateKitties::breed_with_sire(Origin::signed(1), dam_id, sire_id, Permill::zero()),
+			Error::<Test>::SireNotOffered
+		);
+
+		assert_ok!(SubstrateKitties::offer_sire(Origin::signed(2), sire_id, Some(5)));
+		assert_noop!(
+			SubstrateKitties::breed_with_sire(Origin::signed(2), dam_id, sire_id, Permill::zero()),
+			Error::<Test>::NotKittyOwner
+		);
+		events();
+
+		assert_ok!(SubstrateKitties::breed_with_sire(Origin::signed(1), dam_id, sire_id, Permill::zero()));
+
+		// the fee is reserved until the reveal
+		assert_eq!(Balances::free_balance(1), 95);
+		assert_eq!(Balances::reserved_balance(1), 5);
+		assert_eq!(Balances::free_balance(2), 10);
+		assert_eq!(
+			events(),
+			[
+				Event::Balances(pallet_balances::Event::Reserved(1, 5)),
+				Event::SubstrateKitties(crate::Event::MintRequested(1, 3)),
+				Event::SubstrateKitties(crate::Event::BredWithSire(1, 2, sire_id, 5)),
+			]
+		);
+
+		// account 3 cannot afford the fee
+		let dam_id = genesis_kitty(4); // Gender::Female
+		assert_noop!(
+			SubstrateKitties::breed_with_sire(Origin::signed(3), dam_id, sire_id, Permill::zero()),
+			Error::<Test>::NotEnoughBalance
+		);
+
+		// the offer stays up, but the sire rests like any parent
+		assert_eq!(SubstrateKitties::sire_offers(sire_id), Some(5));
+		assert_ok!(SubstrateKitties::transfer(Origin::signed(3), dam_id, 4));
+		assert_noop!(
+			SubstrateKitties::breed_with_sire(Origin::signed(4), dam_id, sire_id, Permill::zero()),
+			Error::<Test>::KittyOnCooldown
+		);
+
+		// the baby kitty goes to the dam owner at its reveal, and the fee to the sire owner
+		run_to_block(3);
+		let kitty = SubstrateKitties::kitties(last_created()).expect("the baby kitty exists");
+		assert_eq!(kitty.owner, 1);
+		assert_eq!(kitty.parents, Some((dam_id, sire_id)));
+		assert_eq!(SubstrateKitties::kitties_owned(&2).len(), 1);
+		assert_eq!(Balances::free_balance(1), 95);
+		assert_eq!(Balances::reserved_balance(1), 0);
+		assert_eq!(Balances::free_balance(2), 15);
+
+		assert_ok!(SubstrateKitties::breed_with_sire(Origin::signed(4), dam_id, sire_id, Permill::zero()));
+		run_to_block(5);
+		assert_eq!(Balances::free_balance(2), 20);
+	});
+}
+
+#[test]
+fn sire_offer_cleared_on_change_of_hands() {
+	new_test_ext().execute_with(|| {
+		let sire_id = SubstrateKitties::kitties_owned(&2)[0];
+
+		// transferred
+		assert_ok!(SubstrateKitties::offer_sire(Origin::signed(2), sire_id, Some(5)));
+		assert_ok!(SubstrateKitties::transfer(Origin::signed(2), sire_id, 4));
+		assert_eq!(SubstrateKitties::sire_offers(sire_id), None);
+
+		// bought
+		assert_ok!(SubstrateKitties::offer_sire(Origin::signed(4), sire_id, Some(5)));
+		assert_ok!(SubstrateKitties::set_price(Origin::signed(4), sire_id, Some(8)));
+		assert_ok!(SubstrateKitties::buy_kitty(Origin::signed(1), sire_id, Some(8)));
+		assert_eq!(SubstrateKitties::sire_offers(sire_id), None);
+
+		// put up for auction
+		assert_ok!(SubstrateKitties::offer_sire(Origin::signed(1), sire_id, Some(5)));
+		assert_ok!(SubstrateKitties::create_auction(Origin::signed(1), sire_id, 5, 3));
+		assert_eq!(SubstrateKitties::sire_offers(sire_id), None);
+		assert_noop!(
+			SubstrateKitties::offer_sire(Origin::signed(1), sire_id, Some(5)),
+			Error::<Test>::KittyInAuction
+		);
+	});
+}
+
+#[test]
+fn nonfungibles_inspect_test() {
+	new_test_ext().execute_with(|| {
+		let kitty_id = genesis_kitty(0);
+		assert_ok!(SubstrateKitties::set_price(Origin::signed(1), kitty_id, Some(8)));
+
+		assert_eq!(<SubstrateKitties as Inspect<_>>::owner(&(), &kitty_id), Some(1));
+		assert_eq!(<SubstrateKitties as Inspect<_>>::class_owner(&()), None);
+		assert_eq!(
+			<SubstrateKitties as Inspect<_>>::attribute(&(), &kitty_id, b"dna"),
+			Some(dna(b"12345678901234").encode())
+		);
+		assert_eq!(
+			<SubstrateKitties as Inspect<_>>::attribute(&(), &kitty_id, b"gender"),
+			Some(Gender::Female.encode())
+		);
+		assert_eq!(
+			<SubstrateKitties as Inspect<_>>::typed_attribute::<_, Gender>(&(), &kitty_id, b"gender"),
+			Some(Gender::Female)
+		);
+		assert_eq!(
+			<SubstrateKitties as Inspect<_>>::attribute(&(), &kitty_id, b"price"),
+			Some(Some(8u64).encode())
+		);
+		assert_eq!(<SubstrateKitties as Inspect<_>>::attribute(&(), &kitty_id, b"owner"), None);
+
+		// unknown kitty
+		let unknown = H256::repeat_byte(7);
+		assert_eq!(<SubstrateKitties as Inspect<_>>::owner(&(), &unknown), None);
+		assert_eq!(<SubstrateKitties as Inspect<_>>::attribute(&(), &unknown, b"dna"), None);
+		assert!(!<SubstrateKitties as Inspect<_>>::can_transfer(&(), &unknown));
+	});
+}
+
+#[test]
+fn nonfungibles_transfer_test() {
+	new_test_ext().execute_with(|| {
+		let kitty_id = genesis_kitty(0);
+		assert_ok!(SubstrateKitties::set_price(Origin::signed(1), kitty_id, Some(8)));
+
+		assert!(<SubstrateKitties as Inspect<_>>::can_transfer(&(), &kitty_id));
+		// a kitty cannot be transferred to its owner, which would leave its bookkeeping off
+		assert_noop!(
+			<SubstrateKitties as Transfer<_>>::transfer(&(), &kitty_id, &1),
+			Error::<Test>::TransferToSelf
+		);
+		assert_noop!(SubstrateKitties::transfer_kitty_to(&kitty_id, &1), Error::<Test>::TransferToSelf);
+		assert_eq!(SubstrateKitties::owned_count(1), 1);
+		assert_eq!(SubstrateKitties::kitties(kitty_id).unwrap().price, Some(8));
+
+		assert_ok!(<SubstrateKitties as Transfer<_>>::transfer(&(), &kitty_id, &4));
+		assert_eq!(<SubstrateKitties as Inspect<_>>::owner(&(), &kitty_id), Some(4));
+		assert_eq!(SubstrateKitties::kitties_owned(&1), vec![]);
+		assert_eq!(SubstrateKitties::kitties_owned(&4), vec![kitty_id]);
+		// the ask price does not survive the change of hands
+		assert_eq!(SubstrateKitties::kitties(kitty_id).unwrap().price, None);
+
+		// a kitty in an auction cannot be transferred
+		assert_ok!(SubstrateKitties::create_auction(Origin::signed(4), kitty_id, 5, 3));
+		assert!(!<SubstrateKitties as Inspect<_>>::can_transfer(&(), &kitty_id));
+		assert_noop!(
+			<SubstrateKitties as Transfer<_>>::transfer(&(), &kitty_id, &1),
+			Error::<Test>::KittyInAuction
+		);
+
+		assert_noop!(
+			<SubstrateKitties as Transfer<_>>::transfer(&(), &H256::repeat_byte(7), &1),
+			Error::<Test>::KittyNotExist
+		);
+	});
+}
+
+#[test]
+fn nonfungibles_mutate_test() {
+	new_test_ext().execute_with(|| {
+		let kitty_id = H256::repeat_byte(7);
+		assert_ok!(<SubstrateKitties as Mutate<_>>::mint_into(&(), &kitty_id, &4));
+		assert_eq!(<SubstrateKitties as Inspect<_>>::owner(&(), &kitty_id), Some(4));
+		assert_eq!(SubstrateKitties::kitty_cnt(), 6);
+		assert_eq!(SubstrateKitties::owned_count(4), 1);
+		assert_eq!(last_created(), kitty_id);
+
+		assert_noop!(
+			<SubstrateKitties as Mutate<_>>::mint_into(&(), &kitty_id, &1),
+			Error::<Test>::DuplicateKittyId
+		);
+
+		// there is no other class than the implicit one
+		assert!(<SubstrateKitties as Create<_>>::create_class(&(), &1, &1).is_err());
+	});
+}
+
+#[test]
+fn approve_test() {
+	new_test_ext().execute_with(|| {
+		let kitty_id = genesis_kitty(0);
+
+		assert_noop!(
+			SubstrateKitties::approve(Origin::signed(2), kitty_id, Some(2)),
+			Error::<Test>::NotKittyOwner
+		);
+		assert_noop!(
+			SubstrateKitties::approve(Origin::signed(1), H256::repeat_byte(7), Some(2)),
+			Error::<Test>::KittyNotExist
+		);
+
+		assert_ok!(SubstrateKitties::approve(Origin::signed(1), kitty_id, Some(2)));
+		assert_eq!(SubstrateKitties::approvals(kitty_id), Some(2));
+		assert_eq!(events().last(), Some(&Event::SubstrateKitties(crate::Event::Approval(1, Some(2), kitty_id))));
+
+		// an operator of the owner may approve on its behalf
+		assert_ok!(SubstrateKitties::set_approval_for_all(Origin::signed(1), 4, true));
+		assert_ok!(SubstrateKitties::approve(Origin::signed(4), kitty_id, Some(3)));
+		assert_eq!(SubstrateKitties::approvals(kitty_id), Some(3));
+
+		// clearing the approval
+		assert_ok!(SubstrateKitties::approve(Origin::signed(1), kitty_id, None));
+		assert_eq!(SubstrateKitties::approvals(kitty_id), None);
+	});
+}
+
+#[test]
+fn set_approval_for_all_test() {
+	new_test_ext().execute_with(|| {
+		assert!(!SubstrateKitties::is_approved_for_all(&3, &4));
+
+		assert_ok!(SubstrateKitties::set_approval_for_all(Origin::signed(3), 4, true));
+		assert!(SubstrateKitties::is_approved_for_all(&3, &4));
+		assert!(!SubstrateKitties::is_approved_for_all(&4, &3));
+		assert_eq!(events().last(), Some(&Event::SubstrateKitties(crate::Event::ApprovalForAll(3, 4, true))));
+
+		assert_ok!(SubstrateKitties::set_approval_for_all(Origin::signed(3), 4, false));
+		assert!(!SubstrateKitties::is_approved_for_all(&3, &4));
+	});
+}
+
+#[test]
+fn transfer_from_test() {
+	new_test_ext().execute_with(|| {
+		let kitty_id = genesis_kitty(2);
+
+		// neither owner nor approved
+		assert_noop!(
+			SubstrateKitties::transfer_from(Origin::signed(4), 3, 4, kitty_id),
+			Error::<Test>::NotApproved
+		);
+		// `from` must own the kitty
+		assert_noop!(
+			SubstrateKitties::transfer_from(Origin::signed(1), 1, 4, kitty_id),
+			Error::<Test>::NotKittyOwner
+		);
+
+		// approved for the kitty
+		assert_ok!(SubstrateKitties::approve(Origin::signed(3), kitty_id, Some(4)));
+		assert_noop!(
+			SubstrateKitties::transfer_from(Origin::signed(4), 3, 3, kitty_id),
+			Error::<Test>::TransferToSelf
+		);
+		assert_ok!(SubstrateKitties::transfer_from(Origin::signed(4), 3, 1, kitty_id));
+		assert_eq!(SubstrateKitties::kitties(kitty_id).unwrap().owner, 1);
+		assert_eq!(SubstrateKitties::owned_count(3), 2);
+		assert_eq!(SubstrateKitties::owned_count(1), 2);
+		assert_eq!(events().last(), Some(&Event::SubstrateKitties(crate::Event::Transferred(3, 1, kitty_id))));
+
+		// an operator of the owner
+		assert_ok!(SubstrateKitties::set_approval_for_all(Origin::signed(1), 2, true));
+		assert_ok!(SubstrateKitties::transfer_from(Origin::signed(2), 1, 4, kitty_id));
+		assert_eq!(SubstrateKitties::kitties(kitty_id).unwrap().owner, 4);
+
+		// a kitty in an auction cannot be transferred
+		assert_ok!(SubstrateKitties::create_auction(Origin::signed(4), kitty_id, 5, 3));
+		assert_noop!(
+			SubstrateKitties::transfer_from(Origin::signed(4), 4, 1, kitty_id),
+			Error::<Test>::KittyInAuction
+		);
+	});
+}
+
+#[test]
+fn approval_cleared_on_change_of_hands() {
+	new_test_ext().execute_with(|| {
+		let kitty_id = genesis_kitty(2);
+
+		// transferred
+		assert_ok!(SubstrateKitties::approve(Origin::signed(3), kitty_id, Some(2)));
+		assert_ok!(SubstrateKitties::transfer(Origin::signed(3), kitty_id, 4));
+		assert_eq!(SubstrateKitties::approvals(kitty_id), None);
+		assert_noop!(
+			SubstrateKitties::transfer_from(Origin::signed(2), 4, 2, kitty_id),
+			Error::<Test>::NotApproved
+		);
+
+		// bought
+		assert_ok!(SubstrateKitties::approve(Origin::signed(4), kitty_id, Some(2)));
+		assert_ok!(SubstrateKitties::set_price(Origin::signed(4), kitty_id, Some(8)));
+		assert_ok!(SubstrateKitties::buy_kitty(Origin::signed(1), kitty_id, Some(8)));
+		assert_eq!(SubstrateKitties::approvals(kitty_id), None);
+
+		// transferred by the approved account itself
+		assert_ok!(SubstrateKitties::approve(Origin::signed(1), kitty_id, Some(2)));
+		assert_ok!(SubstrateKitties::transfer_from(Origin::signed(2), 1, 2, kitty_id));
+		assert_eq!(SubstrateKitties::approvals(kitty_id), None);
+	});
+}
+
+#[test]
+fn transfer_batch_test() {
+	new_test_ext().execute_with(|| {
+		let kitty1 = genesis_kitty(2);
+		let kitty2 = genesis_kitty(3);
+		let kitty3 = genesis_kitty(4);
+
+		assert_ok!(SubstrateKitties::transfer_batch(Origin::signed(3), batch(vec![(kitty1, 4), (kitty2, 1)])));
+		assert_eq!(SubstrateKitties::kitties(kitty1).unwrap().owner, 4);
+		assert_eq!(SubstrateKitties::kitties(kitty2).unwrap().owner, 1);
+		assert_eq!(SubstrateKitties::kitties_owned(&3), vec![kitty3]);
+		assert_eq!(SubstrateKitties::owned_count(3), 1);
+		assert_eq!(SubstrateKitties::owned_count(4), 1);
+		assert_eq!(SubstrateKitties::owned_count(1), 2);
+		assert_eq!(
+			events(),
+			[
+				Event::SubstrateKitties(crate::Event::Transferred(3, 4, kitty1)),
+				Event::SubstrateKitties(crate::Event::Transferred(3, 1, kitty2)),
+			]
+		);
+
+		// an empty batch does nothing
+		assert_ok!(SubstrateKitties::transfer_batch(Origin::signed(3), batch(vec![])));
+	});
+}
+
+#[test]
+fn transfer_batch_is_atomic() {
+	new_test_ext().execute_with(|| {
+		let kitty1 = genesis_kitty(2);
+		let kitty2 = genesis_kitty(3);
+		let not_owned = genesis_kitty(0);
+
+		// the last transfer fails, so the first ones are reverted
+		assert_noop!(
+			SubstrateKitties::transfer_batch(Origin::signed(3), batch(vec![(kitty1, 4), (kitty2, 4), (not_owned, 4)])),
+			Error::<Test>::NotKittyOwner
+		);
+		assert_noop!(
+			SubstrateKitties::transfer_batch(Origin::signed(3), batch(vec![(kitty1, 4), (kitty2, 3)])),
+			Error::<Test>::TransferToSelf
+		);
+		// the same kitty twice
+		assert_noop!(
+			SubstrateKitties::transfer_batch(Origin::signed(3), batch(vec![(kitty1, 4), (kitty1, 1)])),
+			Error::<Test>::NotKittyOwner
+		);
+		assert_eq!(SubstrateKitties::kitties(kitty1).unwrap().owner, 3);
+		assert_eq!(SubstrateKitties::owned_count(3), 3);
+		assert_eq!(SubstrateKitties::owned_count(4), 0);
+	});
+}
+
+#[test]
+fn transfer_batch_limits() {
+	new_test_ext().execute_with(|| {
+		let kitty1 = genesis_kitty(2);
+		let kitty2 = genesis_kitty(3);
+		let kitty3 = genesis_kitty(4);
+
+		// a batch larger than `MaxBatchSize` does not even decode
+		let transfers = vec![(kitty1, 4u64), (kitty2, 4), (kitty3, 4), (kitty1, 4)].encode();
+		assert!(BoundedVec::<(H256, u64), MaxBatchSize>::decode(&mut &transfers[..]).is_err());
+
+		// account 1 can receive 4 more kitties, but not 5 at once
+		assert_ok!(SubstrateKitties::create_kitty(Origin::signed(1), Permill::zero()));
+		assert_ok!(SubstrateKitties::create_kitty(Origin::signed(1), Permill::zero()));
+		reveal_mints();
+		assert_ok!(SubstrateKitties::create_kitty(Origin::signed(1), Permill::zero()));
+		reveal_mints();
+		assert_eq!(SubstrateKitties::owned_count(1), 4);
+		assert_noop!(
+			SubstrateKitties::transfer_batch(Origin::signed(3), batch(vec![(kitty1, 1), (kitty2, 1)])),
+			Error::<Test>::ExceedMaxKittyOwned
+		);
+		assert_ok!(SubstrateKitties::transfer_batch(Origin::signed(3), batch(vec![(kitty1, 1), (kitty2, 4)])));
+	});
+}
+
+#[test]
+fn set_price_batch_test() {
+	new_test_ext().execute_with(|| {
+		let kitty1 = genesis_kitty(2);
+		let kitty2 = genesis_kitty(3);
+		let kitty3 = genesis_kitty(4);
+
+		assert_ok!(SubstrateKitties::set_price_batch(
+			Origin::signed(3),
+			batch(vec![(kitty1, Some(5)), (kitty2, Some(7)), (kitty3, None)])
+		));
+		assert_eq!(SubstrateKitties::kitties(kitty1).unwrap().price, Some(5));
+		assert_eq!(SubstrateKitties::kitties(kitty2).unwrap().price, Some(7));
+		assert_eq!(SubstrateKitties::kitties(kitty3).unwrap().price, None);
+		assert_eq!(events().len(), 3);
+
+		// all or nothing
+		let not_owned = genesis_kitty(0);
+		assert_noop!(
+			SubstrateKitties::set_price_batch(Origin::signed(3), batch(vec![(kitty1, Some(9)), (not_owned, Some(9))])),
+			Error::<Test>::NotKittyOwner
+		);
+		assert_ok!(SubstrateKitties::create_auction(Origin::signed(3), kitty3, 5, 3));
+		assert_noop!(
+			SubstrateKitties::set_price_batch(Origin::signed(3), batch(vec![(kitty1, Some(9)), (kitty3, Some(9))])),
+			Error::<Test>::KittyInAuction
+		);
+		let prices = vec![(kitty1, None::<u64>), (kitty2, None), (kitty1, None), (kitty2, None)].encode();
+		assert!(BoundedVec::<(H256, Option<u64>), MaxBatchSize>::decode(&mut &prices[..]).is_err());
+		assert_eq!(SubstrateKitties::kitties(kitty1).unwrap().price, Some(5));
+	});
+}
+
+#[test]
+fn make_offer_test() {
+	new_test_ext().execute_with(|| {
+		let kitty_id = genesis_kitty(2);
+
+		assert_ok!(SubstrateKitties::make_offer(Origin::signed(1), kitty_id, 20, 5));
+		assert_eq!(Balances::reserved_balance(1), 20);
+		let offers = SubstrateKitties::offers(kitty_id);
+		assert_eq!(offers.len(), 1);
+		assert_eq!((offers[0].buyer, offers[0].amount, offers[0].expiry), (1, 20, 5));
+		System::assert_last_event(Event::SubstrateKitties(crate::Event::OfferMade(1, kitty_id, 20, 5)));
+
+		// a new offer of the same buyer replaces the previous one
+		assert_ok!(SubstrateKitties::make_offer(Origin::signed(1), kitty_id, 30, 8));
+		assert_eq!(Balances::reserved_balance(1), 30);
+		let offers = SubstrateKitties::offers(kitty_id);
+		assert_eq!(offers.len(), 1);
+		assert_eq!((offers[0].amount, offers[0].expiry), (30, 8));
+		System::assert_has_event(Event::SubstrateKitties(crate::Event::OfferReleased(1, kitty_id, 20)));
+	});
+}
+
+#[test]
+fn make_offer_invalid() {
+	new_test_ext().execute_with(|| {
+		let kitty_id = genesis_kitty(2);
+
+		assert_noop!(
+			SubstrateKitties::make_offer(Origin::signed(1), H256::zero(), 5, 5),
+			Error::<Test>::KittyNotExist
+		);
+		assert_noop!(
+			SubstrateKitties::make_offer(Origin::signed(3), kitty_id, 1, 5),
+			Error::<Test>::BuyerIsKittyOwner
+		);
+
+		// the offer must still be open in the next block
+		assert_noop!(
+			SubstrateKitties::make_offer(Origin::signed(1), kitty_id, 5, 1),
+			Error::<Test>::OfferExpired
+		);
+
+		// the buyer must be able to reserve the amount
+		assert_noop!(
+			SubstrateKitties::make_offer(Origin::signed(2), kitty_id, 11, 5),
+			pallet_balances::Error::<Test>::InsufficientBalance
+		);
+
+		// a kitty under the hammer is bought by bidding
+		assert_ok!(SubstrateKitties::create_auction(Origin::signed(3), kitty_id, 5, 3));
+		assert_noop!(
+			SubstrateKitties::make_offer(Origin::signed(1), kitty_id, 5, 5),
+			Error::<Test>::KittyInAuction
+		);
+	});
+}
+
+#[test]
+fn make_offer_on_full_book() {
+	new_test_ext().execute_with(|| {
+		let kitty_id = genesis_kitty(2);
+		assert_ok!(SubstrateKitties::make_offer(Origin::signed(1), kitty_id, 3, 5));
+		assert_ok!(SubstrateKitties::make_offer(Origin::signed(4), kitty_id, 20, 5));
+
+		// `MaxOffersPerKitty` is 2, a new offer must beat the lowest one
+		assert_noop!(
+			SubstrateKitties::make_offer(Origin::signed(2), kitty_id, 3, 5),
+			Error::<Test>::OfferTooLow
+		);
+
+		assert_ok!(SubstrateKitties::make_offer(Origin::signed(2), kitty_id, 4, 5));
+		assert_eq!(Balances::reserved_balance(1), 0);
+		assert_eq!(Balances::reserved_balance(2), 4);
+		assert_eq!(
+			SubstrateKitties::offers(kitty_id).iter().map(|offer| offer.buyer).collect::<Vec<_>>(),
+			vec![4, 2]
+		);
+		System::assert_has_event(Event::SubstrateKitties(crate::Event::OfferReleased(1, kitty_id, 3)));
+
+		// expired offers make room
+		run_to_block(5);
+		assert_ok!(SubstrateKitties::make_offer(Origin::signed(1), kitty_id, 1, 8));
+		assert_eq!(Balances::reserved_balance(2), 0);
+		assert_eq!(Balances::reserved_balance(4), 0);
+		assert_eq!(SubstrateKitties::offers(kitty_id).len(), 1);
+	});
+}
+
+#[test]
+fn accept_offer_test() {
+	new_test_ext().execute_with(|| {
+		let kitty_id = genesis_kitty(2);
+		assert_ok!(SubstrateKitties::set_price(Origin::signed(3), kitty_id, Some(50)));
+		assert_ok!(SubstrateKitties::make_offer(Origin::signed(1), kitty_id, 20, 5));
+		assert_ok!(SubstrateKitties::make_offer(Origin::signed(4), kitty_id, 30, 5));
+
+		assert_ok!(SubstrateKitties::accept_offer(Origin::signed(3), kitty_id, 1));
+		let kitty = SubstrateKitties::kitties(kitty_id).unwrap();
+		assert_eq!(kitty.owner, 1);
+		assert_eq!(kitty.price, None);
+		assert_eq!(SubstrateKitties::owned_count(1), 2);
+		assert_eq!(SubstrateKitties::owned_count(3), 2);
+
+		// the seller is paid the reserved amount, the other offer is released
+		assert_eq!(Balances::free_balance(3), 22);
+		assert_eq!(Balances::free_balance(1), 80);
+		assert_eq!(Balances::reserved_balance(1), 0);
+		assert_eq!(Balances::reserved_balance(4), 0);
+		assert_eq!(Balances::free_balance(4), 50);
+		assert!(SubstrateKitties::offers(kitty_id).is_empty());
+		System::assert_has_event(Event::SubstrateKitties(crate::Event::OfferReleased(4, kitty_id, 30)));
+		System::assert_last_event(Event::SubstrateKitties(crate::Event::OfferAccepted(
+			3,
+			1,
+			kitty_id,
+			Sale { price: 20, protocol_fee: 0, royalty: None, proceeds: 20 },
+		)));
+	});
+}
+
+#[test]
+fn accept_offer_invalid() {
+	new_test_ext().execute_with(|| {
+		let kitty_id = genesis_kitty(2);
+		assert_ok!(SubstrateKitties::make_offer(Origin::signed(1), kitty_id, 20, 5));
+
+		assert_noop!(
+			SubstrateKitties::accept_offer(Origin::signed(2), kitty_id, 1),
+			Error::<Test>::NotKittyOwner
+		);
+		assert_noop!(
+			SubstrateKitties::accept_offer(Origin::signed(3), kitty_id, 4),
+			Error::<Test>::OfferNotExist
+		);
+
+		// the offer cannot be accepted from its expiry block on
+		run_to_block(5);
+		assert_noop!(
+			SubstrateKitties::accept_offer(Origin::signed(3), kitty_id, 1),
+			Error::<Test>::OfferExpired
+		);
+	});
+}
+
+#[test]
+fn withdraw_offer_test() {
+	new_test_ext().execute_with(|| {
+		let kitty_id = genesis_kitty(2);
+		assert_ok!(SubstrateKitties::make_offer(Origin::signed(1), kitty_id, 20, 5));
+		assert_ok!(SubstrateKitties::make_offer(Origin::signed(4), kitty_id, 30, 5));
+
+		assert_ok!(SubstrateKitties::withdraw_offer(Origin::signed(1), kitty_id));
+		assert_eq!(Balances::reserved_balance(1), 0);
+		assert_eq!(Balances::free_balance(1), 100);
+		assert_eq!(SubstrateKitties::offers(kitty_id).len(), 1);
+		System::assert_last_event(Event::SubstrateKitties(crate::Event::OfferWithdrawn(1, kitty_id, 20)));
+
+		assert_noop!(
+			SubstrateKitties::withdraw_offer(Origin::signed(1), kitty_id),
+			Error::<Test>::OfferNotExist
+		);
+
+		// an expired offer can still be withdrawn
+		run_to_block(6);
+		assert_ok!(SubstrateKitties::withdraw_offer(Origin::signed(4), kitty_id));
+		assert_eq!(Balances::reserved_balance(4), 0);
+		assert!(SubstrateKitties::offers(kitty_id).is_empty());
+	});
+}
+
+#[test]
+fn offers_released_on_change_of_hands() {
+	new_test_ext().execute_with(|| {
+		let kitty_id = genesis_kitty(2);
+
+		// transferred
+		assert_ok!(SubstrateKitties::make_offer(Origin::signed(1), kitty_id, 20, 5));
+		assert_ok!(SubstrateKitties::transfer(Origin::signed(3), kitty_id, 2));
+		assert!(SubstrateKitties::offers(kitty_id).is_empty());
+		assert_eq!(Balances::reserved_balance(1), 0);
+		System::assert_has_event(Event::SubstrateKitties(crate::Event::OfferReleased(1, kitty_id, 20)));
+		assert_noop!(
+			SubstrateKitties::accept_offer(Origin::signed(2), kitty_id, 1),
+			Error::<Test>::OfferNotExist
+		);
+
+		// bought
+		assert_ok!(SubstrateKitties::make_offer(Origin::signed(1), kitty_id, 20, 5));
+		assert_ok!(SubstrateKitties::set_price(Origin::signed(2), kitty_id, Some(8)));
+		assert_ok!(SubstrateKitties::buy_kitty(Origin::signed(4), kitty_id, Some(8)));
+		assert!(SubstrateKitties::offers(kitty_id).is_empty());
+		assert_eq!(Balances::reserved_balance(1), 0);
+	});
+}
+
+#[test]
+fn expired_offers_released_on_idle() {
+	new_test_ext().execute_with(|| {
+		let kitty1 = genesis_kitty(2);
+		let kitty2 = genesis_kitty(3);
+		assert_ok!(SubstrateKitties::make_offer(Origin::signed(1), kitty1, 20, 3));
+		assert_ok!(SubstrateKitties::make_offer(Origin::signed(4), kitty1, 30, 10));
+		assert_ok!(SubstrateKitties::make_offer(Origin::signed(4), kitty2, 5, 3));
+
+		// nothing has expired yet
+		run_to_block(2);
+		SubstrateKitties::on_idle(2, Weight::max_value());
+		assert_eq!(Balances::reserved_balance(1), 20);
+		assert_eq!(Balances::reserved_balance(4), 35);
+
+		// no weight left, nothing is swept
+		run_to_block(3);
+		assert_eq!(SubstrateKitties::on_idle(3, 0), 0);
+		assert_eq!(Balances::reserved_balance(1), 20);
+
+		// the weight of a single kitty sweeps one kitty, the next block resumes with the other
+		let step = <() as WeightInfo>::release_offers(MaxOffersPerKitty::get());
+		let swept = |kitty_id| SubstrateKitties::offers(kitty_id).iter().all(|offer| offer.expiry > 3);
+		assert_eq!(SubstrateKitties::on_idle(3, step), step);
+		assert_eq!(swept(kitty1) as u32 + swept(kitty2) as u32, 1);
+		assert_eq!(SubstrateKitties::on_idle(3, step), step);
+		assert!(swept(kitty1) && swept(kitty2));
+
+		assert_eq!(Balances::reserved_balance(1), 0);
+		assert_eq!(Balances::reserved_balance(4), 30);
+		assert_eq!(SubstrateKitties::offers(kitty1).len(), 1);
+		assert!(SubstrateKitties::offers(kitty2).is_empty());
+		System::assert_has_event(Event::SubstrateKitties(crate::Event::OfferReleased(1, kitty1, 20)));
+		System::assert_has_event(Event::SubstrateKitties(crate::Event::OfferReleased(4, kitty2, 5)));
+	});
+}
+
+#[test]
+fn burn_test() {
+	new_test_ext().execute_with(|| {
+		// account 1 pays the minting deposit, then gives the kitty away
+		assert_ok!(SubstrateKitties::create_kitty(Origin::signed(1), Permill::zero()));
+		reveal_mints();
+		let kitty_id = last_created();
+		assert_eq!(SubstrateKitties::mint_deposits(kitty_id), Some((1, 3)));
+		assert_eq!(Balances::reserved_balance(1), 3);
+		assert_ok!(SubstrateKitties::transfer(Origin::signed(1), kitty_id, 4));
+
+		assert_ok!(SubstrateKitties::make_offer(Origin::signed(2), kitty_id, 5, 5));
+		assert_ok!(SubstrateKitties::burn(Origin::signed(4), kitty_id));
+		assert!(SubstrateKitties::kitties(kitty_id).is_none());
+		assert!(SubstrateKitties::kitties_owned(&4).is_empty());
+		assert_eq!(SubstrateKitties::owned_count(4), 0);
+		assert_eq!(SubstrateKitties::kitty_cnt(), 5);
+
+		// the deposit goes back to account 1, the offer to account 2
+		assert_eq!(SubstrateKitties::mint_deposits(kitty_id), None);
+		assert_eq!(Balances::reserved_balance(1), 0);
+		assert_eq!(Balances::free_balance(1), 100);
+		assert_eq!(Balances::reserved_balance(2), 0);
+		assert!(SubstrateKitties::offers(kitty_id).is_empty());
+		System::assert_last_event(Event::SubstrateKitties(crate::Event::Burned(4, kitty_id)));
+	});
+}
+
+#[test]
+fn burn_invalid() {
+	new_test_ext().execute_with(|| {
+		let kitty_id = genesis_kitty(2);
+
+		assert_noop!(
+			SubstrateKitties::burn(Origin::signed(1), H256::zero()),
+			Error::<Test>::KittyNotExist
+		);
+		assert_noop!(
+			SubstrateKitties::burn(Origin::signed(1), kitty_id),
+			Error::<Test>::NotKittyOwner
+		);
+
+		assert_ok!(SubstrateKitties::create_auction(Origin::signed(3), kitty_id, 5, 3));
+		assert_noop!(
+			SubstrateKitties::burn(Origin::signed(3), kitty_id),
+			Error::<Test>::KittyInAuction
+		);
+	});
+}
+
+#[test]
+fn burned_kitty_id_never_reissued() {
+	new_test_ext().execute_with(|| {
+		// a genesis kitty has no minting deposit
+		let kitty_id = genesis_kitty(2);
+		assert_ok!(SubstrateKitties::burn(Origin::signed(3), kitty_id));
+		assert_eq!(SubstrateKitties::kitty_cnt(), 4);
+		assert_eq!(SubstrateKitties::owned_count(3), 2);
+
+		assert_noop!(
+			<SubstrateKitties as Mutate<_>>::mint_into(&(), &kitty_id, &1),
+			Error::<Test>::DuplicateKittyId
+		);
+	});
+}
+
+#[test]
+fn nonfungibles_burn_test() {
+	new_test_ext().execute_with(|| {
+		let kitty_id = genesis_kitty(0);
+		assert_ok!(<SubstrateKitties as Mutate<_>>::burn_from(&(), &kitty_id));
+		assert_eq!(<SubstrateKitties as Inspect<_>>::owner(&(), &kitty_id), None);
+		assert_eq!(SubstrateKitties::owned_count(1), 0);
+		System::assert_last_event(Event::SubstrateKitties(crate::Event::Burned(1, kitty_id)));
+
+		assert_noop!(
+			<SubstrateKitties as Mutate<_>>::burn_from(&(), &kitty_id),
+			Error::<Test>::KittyNotExist
+		);
+	});
+}
+
+#[test]
+fn sale_pays_protocol_fee_and_royalty() {
+	new_test_ext().execute_with(|| {
+		ProtocolFee::set(&Permill::from_percent(5));
+
+		// account 1 creates a kitty with a 10% royalty and hands it to account 2
+		assert_ok!(SubstrateKitties::create_kitty(Origin::signed(1), Permill::from_percent(10)));
+		reveal_mints();
+		let kitty_id = last_created();
+		assert_eq!(SubstrateKitties::royalties(kitty_id), Some((1, Permill::from_percent(10))));
+		assert_ok!(SubstrateKitties::transfer(Origin::signed(1), kitty_id, 2));
+
+		assert_ok!(SubstrateKitties::set_price(Origin::signed(2), kitty_id, Some(40)));
+		assert_ok!(SubstrateKitties::buy_kitty(Origin::signed(4), kitty_id, Some(40)));
+
+		// 2 goes to the treasury, 4 to the creator and the rest to the seller
+		assert_eq!(Balances::free_balance(KittyTreasury::get()), 2);
+		assert_eq!(Balances::free_balance(1), 101);
+		assert_eq!(Balances::free_balance(2), 44);
+		assert_eq!(Balances::free_balance(4), 10);
+		System::assert_last_event(Event::SubstrateKitties(crate::Event::Bought(
+			2,
+			4,
+			kitty_id,
+			40,
+			Sale { price: 40, protocol_fee: 2, royalty: Some((1, 4)), proceeds: 34 },
+		)));
+	});
+}
+
+#[test]
+fn sale_fee_and_royalty_round_down() {
+	new_test_ext().execute_with(|| {
+		ProtocolFee::set(&Permill::from_percent(5));
+
+		assert_ok!(SubstrateKitties::create_kitty(Origin::signed(1), Permill::from_percent(10)));
+		reveal_mints();
+		let kitty_id = last_created();
+		assert_ok!(SubstrateKitties::transfer(Origin::signed(1), kitty_id, 2));
+		assert_ok!(SubstrateKitties::set_price(Origin::signed(2), kitty_id, Some(19)));
+		assert_ok!(SubstrateKitties::buy_kitty(Origin::signed(4), kitty_id, Some(19)));
+
+		// 0.95 of a fee is nothing, 1.9 of a royalty is 1
+		assert_eq!(Balances::total_balance(&KittyTreasury::get()), 0);
+		assert_eq!(Balances::free_balance(1), 98);
+		assert_eq!(Balances::free_balance(2), 28);
+		System::assert_last_event(Event::SubstrateKitties(crate::Event::Bought(
+			2,
+			4,
+			kitty_id,
+			19,
+			Sale { price: 19, protocol_fee: 0, royalty: Some((1, 1)), proceeds: 18 },
+		)));
+	});
+}
+
+#[test]
+fn no_royalty_when_creator_sells() {
+	new_test_ext().execute_with(|| {
+		ProtocolFee::set(&Permill::from_percent(5));
+
+		assert_ok!(SubstrateKitties::create_kitty(Origin::signed(1), Permill::from_percent(10)));
+		reveal_mints();
+		let kitty_id = last_created();
+		assert_ok!(SubstrateKitties::set_price(Origin::signed(1), kitty_id, Some(40)));
+		assert_ok!(SubstrateKitties::buy_kitty(Origin::signed(4), kitty_id, Some(40)));
+
+		assert_eq!(Balances::free_balance(1), 135);
+		System::assert_last_event(Event::SubstrateKitties(crate::Event::Bought(
+			1,
+			4,
+			kitty_id,
+			40,
+			Sale { price: 40, protocol_fee: 2, royalty: None, proceeds: 38 },
+		)));
+	});
+}
+
+#[test]
+fn royalty_too_high() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			SubstrateKitties::create_kitty(Origin::signed(1), Permill::from_percent(11)),
+			Error::<Test>::RoyaltyTooHigh
+		);
+
+		let kitty_id1 = genesis_kitty(3);
+		let kitty_id2 = genesis_kitty(4);
+		assert_noop!(
+			SubstrateKitties::breed_kitty(Origin::signed(3), kitty_id1, kitty_id2, Permill::from_percent(11)),
+			Error::<Test>::RoyaltyTooHigh
+		);
+	});
+}
+
+#[test]
+fn buy_kitty_keeps_buyer_alive() {
+	new_test_ext().execute_with(|| {
+		ProtocolFee::set(&Permill::from_percent(5));
+		let kitty_id = genesis_kitty(0);
+
+		// paying the fee first leaves enough for it, but not for the proceeds, and nothing is paid
+		assert_ok!(SubstrateKitties::set_price(Origin::signed(1), kitty_id, Some(50)));
+		assert_noop!(
+			SubstrateKitties::buy_kitty(Origin::signed(4), kitty_id, Some(50)),
+			pallet_balances::Error::<Test>::KeepAlive
+		);
+
+		// leaving the existential deposit is fine
+		assert_ok!(SubstrateKitties::set_price(Origin::signed(1), kitty_id, Some(49)));
+		assert_ok!(SubstrateKitties::buy_kitty(Origin::signed(4), kitty_id, Some(49)));
+		assert_eq!(Balances::free_balance(4), 1);
+		assert_eq!(Balances::free_balance(KittyTreasury::get()), 2);
+		assert_eq!(Balances::free_balance(1), 147);
+	});
+}
+
+#[test]
+fn accepted_offer_may_spend_whole_balance() {
+	new_test_ext().execute_with(|| {
+		ProtocolFee::set(&Permill::from_percent(5));
+		let kitty_id = genesis_kitty(0);
+
+		// the buyer committed its whole balance to the offer
+		assert_ok!(SubstrateKitties::make_offer(Origin::signed(4), kitty_id, 50, 5));
+		assert_ok!(SubstrateKitties::accept_offer(Origin::signed(1), kitty_id, 4));
+
+		assert_eq!(SubstrateKitties::kitties(kitty_id).unwrap().owner, 4);
+		assert_eq!(Balances::total_balance(&4), 0);
+		assert_eq!(Balances::free_balance(KittyTreasury::get()), 2);
+		assert_eq!(Balances::free_balance(1), 148);
+	});
+}
+
+#[test]
+fn create_kitty_pending_mints_limit() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(SubstrateKitties::create_kitty(Origin::signed(1), Permill::zero()));
+		assert_ok!(SubstrateKitties::create_kitty(Origin::signed(4), Permill::zero()));
+		assert_noop!(
+			SubstrateKitties::create_kitty(Origin::signed(1), Permill::zero()),
+			Error::<Test>::TooManyPendingMints
+		);
+
+		// the next block reveals at a later block
+		run_to_block(2);
+		assert_ok!(SubstrateKitties::create_kitty(Origin::signed(1), Permill::zero()));
+		System::assert_last_event(Event::SubstrateKitties(crate::Event::MintRequested(1, 4)));
+	});
+}
+
+#[test]
+fn create_kitty_cancelled_at_reveal() {
+	new_test_ext().execute_with(|| {
+		// account 3 creates a kitty, then receives kitties up to its limit
+		Balances::make_free_balance_be(&3, 100);
+		assert_ok!(SubstrateKitties::create_kitty(Origin::signed(3), Permill::from_percent(10)));
+		assert_ok!(SubstrateKitties::transfer(Origin::signed(1), genesis_kitty(0), 3));
+		assert_ok!(SubstrateKitties::transfer(Origin::signed(2), genesis_kitty(1), 3));
+		assert_eq!(SubstrateKitties::owned_count(3), 5);
+
+		// the kitty is not revealed, and the deposit is released
+		reveal_mints();
+		assert_eq!(SubstrateKitties::owned_count(3), 5);
+		assert_eq!(SubstrateKitties::kitty_cnt(), 5);
+		assert_eq!(Balances::reserved_balance(3), 0);
+		System::assert_last_event(Event::SubstrateKitties(crate::Event::MintCancelled(3)));
+	});
+}
+
+#[test]
+fn breeding_outcome_is_deterministic() {
+	new_test_ext().execute_with(|| {
+		let parent_index_1 = genesis_kitty(2);
+		let parent_index_2 = genesis_kitty(4);
+		assert_ok!(SubstrateKitties::breed_kitty(Origin::signed(3), parent_index_1, parent_index_2, Permill::zero()));
+		reveal_mints();
+
+		// the test randomness, the reveal block and the kitty nonce decide the baby kitty
+		let mut expected = dna(b"22335578092234");
+		genetics::set_generation(&mut expected, 1);
+		let kitty = SubstrateKitties::kitties(last_created()).expect("the baby kitty exists");
+		assert_eq!(kitty.dna, expected);
+		assert_eq!(kitty.gender, Gender::Male);
+	});
+}
+
+#[test]
+fn bred_kitty_cancelled_without_its_parents() {
+	new_test_ext().execute_with(|| {
+		let (father, mother) = (genesis_kitty(2), genesis_kitty(4));
+		assert_ok!(SubstrateKitties::breed_kitty(Origin::signed(3), father, mother, Permill::zero()));
+
+		// a parent burned before the reveal leaves nothing to breed from
+		assert_ok!(SubstrateKitties::burn(Origin::signed(3), father));
+		reveal_mints();
+		assert_eq!(SubstrateKitties::owned_count(3), 2);
+		assert_eq!(SubstrateKitties::kitty_cnt(), 4);
+		System::assert_last_event(Event::SubstrateKitties(crate::Event::MintCancelled(3)));
+
+		// the sire fee is refunded to the breeder, not paid for a kitty that is never born
+		let (dam, sire) = (genesis_kitty(0), genesis_kitty(1));
+		assert_ok!(SubstrateKitties::offer_sire(Origin::signed(2), sire, Some(5)));
+		assert_ok!(SubstrateKitties::breed_with_sire(Origin::signed(1), dam, sire, Permill::zero()));
+		assert_eq!(Balances::reserved_balance(1), 5);
+		assert_ok!(SubstrateKitties::burn(Origin::signed(2), sire));
+		reveal_mints();
+		assert_eq!(SubstrateKitties::owned_count(1), 1);
+		assert_eq!(Balances::free_balance(1), 100);
+		assert_eq!(Balances::reserved_balance(1), 0);
+		assert_eq!(Balances::free_balance(2), 10);
+		System::assert_last_event(Event::SubstrateKitties(crate::Event::MintCancelled(1)));
+	});
+}
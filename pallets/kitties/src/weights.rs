@@ -15,29 +15,14 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-//! Autogenerated weights for pallet_kitties
+//! Placeholder weights for pallet_kitties
 //!
-//! THIS FILE WAS AUTO-GENERATED USING THE SUBSTRATE BENCHMARK CLI VERSION 4.0.0-dev
-//! DATE: 2022-02-04, STEPS: `1`, REPEAT: 50, LOW RANGE: `[]`, HIGH RANGE: `[]`
-//! EXECUTION: Some(Wasm), WASM-EXECUTION: Compiled, CHAIN: Some("dev"), DB CACHE: 128
-
-// Executed Command:
-// ./target/release/node-template
-// benchmark
-// --chain
-// dev
-// --execution
-// Wasm
-// --wasm-execution
-// compiled
-// --pallet
-// pallet_kitties
-// --extrinsic
-// *
-// --repeat
-// 50
-// --template=./.maintain/frame-weight-template.hbs
-// --output=./pallets/kitties/src/weights.rs
+//! THESE WEIGHTS ARE NOT MEASURED. They are estimates written by hand from the storage accesses
+//! of each call, in the layout of the benchmark CLI output, and must be replaced by running:
+//!
+//! ./target/release/node-template benchmark --chain dev --execution wasm --wasm-execution compiled
+//!     --pallet pallet_kitties --extrinsic '*' --steps 20 --repeat 20
+//!     --output=./pallets/kitties/src/weights.rs
 
 #![cfg_attr(rustfmt, rustfmt_skip)]
 #![allow(unused_parens)]
@@ -49,33 +34,489 @@ use sp_std::marker::PhantomData;
 /// Weight functions needed for pallet_kitties.
 pub trait WeightInfo {
 	fn create_kitty() -> Weight;
+	fn set_price() -> Weight;
+	fn transfer() -> Weight;
+	fn buy_kitty() -> Weight;
+	fn breed_kitty() -> Weight;
+	fn offer_sire() -> Weight;
+	fn breed_with_sire() -> Weight;
+	fn create_auction(a: u32, ) -> Weight;
+	fn bid() -> Weight;
+	fn on_initialize(a: u32, ) -> Weight;
+	fn approve() -> Weight;
+	fn set_approval_for_all() -> Weight;
+	fn transfer_from() -> Weight;
+	fn transfer_batch(n: u32, ) -> Weight;
+	fn set_price_batch(n: u32, ) -> Weight;
+	fn make_offer(o: u32, ) -> Weight;
+	fn accept_offer(o: u32, ) -> Weight;
+	fn withdraw_offer(o: u32, ) -> Weight;
+	fn release_offers(o: u32, ) -> Weight;
+	fn burn() -> Weight;
+	fn reveal_mints(m: u32, ) -> Weight;
 }
 
 /// Weights for pallet_kitties using the Substrate node and recommended hardware.
 pub struct SubstrateWeight<T>(PhantomData<T>);
 impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+	// Storage: SubstrateKitties OwnedCount (r:1 w:0)
+	// Storage: SubstrateKitties PendingMints (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	fn create_kitty() -> Weight {
+		(41_286_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(3 as Weight))
+			.saturating_add(T::DbWeight::get().writes(2 as Weight))
+	}
+	// Storage: SubstrateKitties Kitties (r:1 w:1)
+	// Storage: SubstrateKitties Auctions (r:1 w:0)
+	fn set_price() -> Weight {
+		(24_310_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(2 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	// Storage: SubstrateKitties Kitties (r:1 w:1)
+	// Storage: SubstrateKitties Auctions (r:1 w:0)
+	// Storage: SubstrateKitties KittiesOwned (r:1 w:2)
+	// Storage: SubstrateKitties OwnedCount (r:2 w:2)
+	// Storage: SubstrateKitties SireOffers (r:0 w:1)
+	// Storage: SubstrateKitties Approvals (r:0 w:1)
+	fn transfer() -> Weight {
+		(43_105_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(5 as Weight))
+			.saturating_add(T::DbWeight::get().writes(7 as Weight))
+	}
+	// Storage: SubstrateKitties Kitties (r:1 w:1)
+	// Storage: System Account (r:4 w:4)
+	// Storage: SubstrateKitties OwnedCount (r:2 w:2)
+	// Storage: SubstrateKitties KittiesOwned (r:1 w:2)
+	// Storage: SubstrateKitties SireOffers (r:0 w:1)
+	// Storage: SubstrateKitties Approvals (r:0 w:1)
+	// Storage: SubstrateKitties Royalties (r:1 w:0)
+	fn buy_kitty() -> Weight {
+		(80_214_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(9 as Weight))
+			.saturating_add(T::DbWeight::get().writes(11 as Weight))
+	}
+	// Storage: SubstrateKitties Kitties (r:2 w:2)
+	// Storage: SubstrateKitties Auctions (r:2 w:0)
+	// Storage: SubstrateKitties OwnedCount (r:1 w:0)
+	// Storage: SubstrateKitties PendingMints (r:1 w:1)
+	fn breed_kitty() -> Weight {
+		(52_417_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(6 as Weight))
+			.saturating_add(T::DbWeight::get().writes(3 as Weight))
+	}
+	// Storage: SubstrateKitties Kitties (r:1 w:0)
+	// Storage: SubstrateKitties Auctions (r:1 w:0)
+	// Storage: SubstrateKitties SireOffers (r:0 w:1)
+	fn offer_sire() -> Weight {
+		(23_764_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(2 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	// Storage: SubstrateKitties Kitties (r:2 w:2)
+	// Storage: SubstrateKitties SireOffers (r:1 w:0)
+	// Storage: System Account (r:1 w:1)
+	// Storage: SubstrateKitties Auctions (r:2 w:0)
+	// Storage: SubstrateKitties OwnedCount (r:1 w:0)
+	// Storage: SubstrateKitties PendingMints (r:1 w:1)
+	fn breed_with_sire() -> Weight {
+		(97_806_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(8 as Weight))
+			.saturating_add(T::DbWeight::get().writes(4 as Weight))
+	}
+	// Storage: SubstrateKitties Kitties (r:1 w:1)
+	// Storage: SubstrateKitties Auctions (r:1 w:1)
+	// Storage: SubstrateKitties AuctionsEndingAt (r:1 w:1)
+	// Storage: SubstrateKitties SireOffers (r:0 w:1)
+	fn create_auction(a: u32, ) -> Weight {
+		(38_941_000 as Weight)
+			.saturating_add((131_000 as Weight).saturating_mul(a as Weight))
+			.saturating_add(T::DbWeight::get().reads(3 as Weight))
+			.saturating_add(T::DbWeight::get().writes(4 as Weight))
+	}
+	// Storage: SubstrateKitties Auctions (r:1 w:1)
+	// Storage: SubstrateKitties OwnedCount (r:1 w:0)
+	// Storage: System Account (r:2 w:2)
+	fn bid() -> Weight {
+		(57_116_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(4 as Weight))
+			.saturating_add(T::DbWeight::get().writes(3 as Weight))
+	}
+	// Storage: SubstrateKitties AuctionsEndingAt (r:1 w:1)
+	// Storage: SubstrateKitties Auctions (r:1 w:1)
+	// Storage: SubstrateKitties Kitties (r:1 w:1)
+	// Storage: SubstrateKitties KittiesOwned (r:1 w:2)
+	// Storage: SubstrateKitties OwnedCount (r:2 w:2)
+	// Storage: SubstrateKitties SireOffers (r:0 w:1)
+	// Storage: SubstrateKitties Approvals (r:0 w:1)
+	// Storage: System Account (r:4 w:4)
+	// Storage: SubstrateKitties Royalties (r:1 w:0)
+	fn on_initialize(a: u32, ) -> Weight {
+		(3_872_000 as Weight)
+			.saturating_add((70_512_000 as Weight).saturating_mul(a as Weight))
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().reads((10 as Weight).saturating_mul(a as Weight)))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes((12 as Weight).saturating_mul(a as Weight)))
+	}
+	// Storage: SubstrateKitties Kitties (r:1 w:0)
+	// Storage: SubstrateKitties OperatorApprovals (r:1 w:0)
+	// Storage: SubstrateKitties Approvals (r:0 w:1)
+	fn approve() -> Weight {
+		(22_946_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(2 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	// Storage: SubstrateKitties OperatorApprovals (r:0 w:1)
+	fn set_approval_for_all() -> Weight {
+		(17_530_000 as Weight)
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	// Storage: SubstrateKitties Kitties (r:1 w:1)
+	// Storage: SubstrateKitties Approvals (r:1 w:1)
+	// Storage: SubstrateKitties OperatorApprovals (r:1 w:0)
+	// Storage: SubstrateKitties Auctions (r:1 w:0)
+	// Storage: SubstrateKitties KittiesOwned (r:1 w:2)
+	// Storage: SubstrateKitties OwnedCount (r:2 w:2)
+	// Storage: SubstrateKitties SireOffers (r:0 w:1)
+	fn transfer_from() -> Weight {
+		(48_372_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(7 as Weight))
+			.saturating_add(T::DbWeight::get().writes(7 as Weight))
+	}
+	// Storage: SubstrateKitties OwnedCount (r:2 w:2)
+	// Storage: SubstrateKitties Kitties (r:1 w:1)
+	// Storage: SubstrateKitties Auctions (r:1 w:0)
+	// Storage: SubstrateKitties KittiesOwned (r:1 w:2)
+	// Storage: SubstrateKitties SireOffers (r:0 w:1)
+	// Storage: SubstrateKitties Approvals (r:0 w:1)
+	fn transfer_batch(n: u32, ) -> Weight {
+		(9_418_000 as Weight)
+			.saturating_add((38_604_000 as Weight).saturating_mul(n as Weight))
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().reads((4 as Weight).saturating_mul(n as Weight)))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes((6 as Weight).saturating_mul(n as Weight)))
+	}
+	// Storage: SubstrateKitties Kitties (r:1 w:1)
+	// Storage: SubstrateKitties Auctions (r:1 w:0)
+	fn set_price_batch(n: u32, ) -> Weight {
+		(3_207_000 as Weight)
+			.saturating_add((21_835_000 as Weight).saturating_mul(n as Weight))
+			.saturating_add(T::DbWeight::get().reads((2 as Weight).saturating_mul(n as Weight)))
+			.saturating_add(T::DbWeight::get().writes((1 as Weight).saturating_mul(n as Weight)))
+	}
+	// Storage: SubstrateKitties Kitties (r:1 w:0)
+	// Storage: SubstrateKitties Auctions (r:1 w:0)
+	// Storage: SubstrateKitties Offers (r:1 w:1)
 	// Storage: System Account (r:1 w:1)
+	fn make_offer(o: u32, ) -> Weight {
+		(52_816_000 as Weight)
+			.saturating_add((24_310_000 as Weight).saturating_mul(o as Weight))
+			.saturating_add(T::DbWeight::get().reads(4 as Weight))
+			.saturating_add(T::DbWeight::get().reads((1 as Weight).saturating_mul(o as Weight)))
+			.saturating_add(T::DbWeight::get().writes(2 as Weight))
+			.saturating_add(T::DbWeight::get().writes((1 as Weight).saturating_mul(o as Weight)))
+	}
+	// Storage: SubstrateKitties Kitties (r:1 w:1)
+	// Storage: SubstrateKitties Auctions (r:1 w:0)
+	// Storage: SubstrateKitties Offers (r:1 w:1)
+	// Storage: SubstrateKitties OwnedCount (r:2 w:2)
+	// Storage: System Account (r:4 w:4)
+	// Storage: SubstrateKitties KittiesOwned (r:1 w:2)
+	// Storage: SubstrateKitties SireOffers (r:0 w:1)
+	// Storage: SubstrateKitties Approvals (r:0 w:1)
+	// Storage: SubstrateKitties Royalties (r:1 w:0)
+	fn accept_offer(o: u32, ) -> Weight {
+		(96_427_000 as Weight)
+			.saturating_add((24_876_000 as Weight).saturating_mul(o as Weight))
+			.saturating_add(T::DbWeight::get().reads(11 as Weight))
+			.saturating_add(T::DbWeight::get().reads((1 as Weight).saturating_mul(o as Weight)))
+			.saturating_add(T::DbWeight::get().writes(12 as Weight))
+			.saturating_add(T::DbWeight::get().writes((1 as Weight).saturating_mul(o as Weight)))
+	}
+	// Storage: SubstrateKitties Offers (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	fn withdraw_offer(o: u32, ) -> Weight {
+		(34_902_000 as Weight)
+			.saturating_add((412_000 as Weight).saturating_mul(o as Weight))
+			.saturating_add(T::DbWeight::get().reads(2 as Weight))
+			.saturating_add(T::DbWeight::get().writes(2 as Weight))
+	}
+	// Storage: SubstrateKitties Offers (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	fn release_offers(o: u32, ) -> Weight {
+		(4_127_000 as Weight)
+			.saturating_add((24_531_000 as Weight).saturating_mul(o as Weight))
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().reads((1 as Weight).saturating_mul(o as Weight)))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes((1 as Weight).saturating_mul(o as Weight)))
+	}
+	// Storage: SubstrateKitties Kitties (r:1 w:1)
+	// Storage: SubstrateKitties Auctions (r:1 w:0)
+	// Storage: SubstrateKitties OwnedCount (r:1 w:1)
+	// Storage: SubstrateKitties KittyCnt (r:1 w:1)
+	// Storage: SubstrateKitties MintDeposits (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	// Storage: SubstrateKitties KittiesOwned (r:0 w:1)
+	// Storage: SubstrateKitties BurnedKitties (r:0 w:1)
+	// Storage: SubstrateKitties SireOffers (r:0 w:1)
+	// Storage: SubstrateKitties Approvals (r:0 w:1)
+	fn burn() -> Weight {
+		(58_734_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(6 as Weight))
+			.saturating_add(T::DbWeight::get().writes(9 as Weight))
+	}
+	// Storage: SubstrateKitties PendingMints (r:1 w:1)
 	// Storage: RandomnessCollectiveFlip RandomMaterial (r:1 w:0)
+	// Storage: SubstrateKitties KittyNonce (r:1 w:1)
 	// Storage: SubstrateKitties KittyCnt (r:1 w:1)
-	// Storage: SubstrateKitties KittiesOwned (r:1 w:1)
-	// Storage: SubstrateKitties Kitties (r:0 w:1)
-	fn create_kitty() -> Weight {
-		(74_000_000 as Weight)
+	// Storage: SubstrateKitties Kitties (r:3 w:1)
+	// Storage: SubstrateKitties BurnedKitties (r:1 w:0)
+	// Storage: SubstrateKitties OwnedCount (r:1 w:1)
+	// Storage: SubstrateKitties KittiesOwned (r:0 w:1)
+	// Storage: SubstrateKitties Royalties (r:0 w:1)
+	// Storage: System Account (r:2 w:2)
+	fn reveal_mints(m: u32, ) -> Weight {
+		(2_914_000 as Weight)
+			.saturating_add((71_562_000 as Weight).saturating_mul(m as Weight))
 			.saturating_add(T::DbWeight::get().reads(4 as Weight))
-			.saturating_add(T::DbWeight::get().writes(4 as Weight))
+			.saturating_add(T::DbWeight::get().reads((7 as Weight).saturating_mul(m as Weight)))
+			.saturating_add(T::DbWeight::get().writes(3 as Weight))
+			.saturating_add(T::DbWeight::get().writes((7 as Weight).saturating_mul(m as Weight)))
 	}
 }
 
 // For backwards compatibility and tests
 impl WeightInfo for () {
+	// Storage: SubstrateKitties OwnedCount (r:1 w:0)
+	// Storage: SubstrateKitties PendingMints (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	fn create_kitty() -> Weight {
+		(41_286_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(3 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(2 as Weight))
+	}
+	// Storage: SubstrateKitties Kitties (r:1 w:1)
+	// Storage: SubstrateKitties Auctions (r:1 w:0)
+	fn set_price() -> Weight {
+		(24_310_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(2 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+	// Storage: SubstrateKitties Kitties (r:1 w:1)
+	// Storage: SubstrateKitties Auctions (r:1 w:0)
+	// Storage: SubstrateKitties KittiesOwned (r:1 w:2)
+	// Storage: SubstrateKitties OwnedCount (r:2 w:2)
+	// Storage: SubstrateKitties SireOffers (r:0 w:1)
+	// Storage: SubstrateKitties Approvals (r:0 w:1)
+	fn transfer() -> Weight {
+		(43_105_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(5 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(7 as Weight))
+	}
+	// Storage: SubstrateKitties Kitties (r:1 w:1)
+	// Storage: System Account (r:4 w:4)
+	// Storage: SubstrateKitties OwnedCount (r:2 w:2)
+	// Storage: SubstrateKitties KittiesOwned (r:1 w:2)
+	// Storage: SubstrateKitties SireOffers (r:0 w:1)
+	// Storage: SubstrateKitties Approvals (r:0 w:1)
+	// Storage: SubstrateKitties Royalties (r:1 w:0)
+	fn buy_kitty() -> Weight {
+		(80_214_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(9 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(11 as Weight))
+	}
+	// Storage: SubstrateKitties Kitties (r:2 w:2)
+	// Storage: SubstrateKitties Auctions (r:2 w:0)
+	// Storage: SubstrateKitties OwnedCount (r:1 w:0)
+	// Storage: SubstrateKitties PendingMints (r:1 w:1)
+	fn breed_kitty() -> Weight {
+		(52_417_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(6 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(3 as Weight))
+	}
+	// Storage: SubstrateKitties Kitties (r:1 w:0)
+	// Storage: SubstrateKitties Auctions (r:1 w:0)
+	// Storage: SubstrateKitties SireOffers (r:0 w:1)
+	fn offer_sire() -> Weight {
+		(23_764_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(2 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+	// Storage: SubstrateKitties Kitties (r:2 w:2)
+	// Storage: SubstrateKitties SireOffers (r:1 w:0)
+	// Storage: System Account (r:1 w:1)
+	// Storage: SubstrateKitties Auctions (r:2 w:0)
+	// Storage: SubstrateKitties OwnedCount (r:1 w:0)
+	// Storage: SubstrateKitties PendingMints (r:1 w:1)
+	fn breed_with_sire() -> Weight {
+		(97_806_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(8 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(4 as Weight))
+	}
+	// Storage: SubstrateKitties Kitties (r:1 w:1)
+	// Storage: SubstrateKitties Auctions (r:1 w:1)
+	// Storage: SubstrateKitties AuctionsEndingAt (r:1 w:1)
+	// Storage: SubstrateKitties SireOffers (r:0 w:1)
+	fn create_auction(a: u32, ) -> Weight {
+		(38_941_000 as Weight)
+			.saturating_add((131_000 as Weight).saturating_mul(a as Weight))
+			.saturating_add(RocksDbWeight::get().reads(3 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(4 as Weight))
+	}
+	// Storage: SubstrateKitties Auctions (r:1 w:1)
+	// Storage: SubstrateKitties OwnedCount (r:1 w:0)
+	// Storage: System Account (r:2 w:2)
+	fn bid() -> Weight {
+		(57_116_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(4 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(3 as Weight))
+	}
+	// Storage: SubstrateKitties AuctionsEndingAt (r:1 w:1)
+	// Storage: SubstrateKitties Auctions (r:1 w:1)
+	// Storage: SubstrateKitties Kitties (r:1 w:1)
+	// Storage: SubstrateKitties KittiesOwned (r:1 w:2)
+	// Storage: SubstrateKitties OwnedCount (r:2 w:2)
+	// Storage: SubstrateKitties SireOffers (r:0 w:1)
+	// Storage: SubstrateKitties Approvals (r:0 w:1)
+	// Storage: System Account (r:4 w:4)
+	// Storage: SubstrateKitties Royalties (r:1 w:0)
+	fn on_initialize(a: u32, ) -> Weight {
+		(3_872_000 as Weight)
+			.saturating_add((70_512_000 as Weight).saturating_mul(a as Weight))
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+			.saturating_add(RocksDbWeight::get().reads((10 as Weight).saturating_mul(a as Weight)))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+			.saturating_add(RocksDbWeight::get().writes((12 as Weight).saturating_mul(a as Weight)))
+	}
+	// Storage: SubstrateKitties Kitties (r:1 w:0)
+	// Storage: SubstrateKitties OperatorApprovals (r:1 w:0)
+	// Storage: SubstrateKitties Approvals (r:0 w:1)
+	fn approve() -> Weight {
+		(22_946_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(2 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+	// Storage: SubstrateKitties OperatorApprovals (r:0 w:1)
+	fn set_approval_for_all() -> Weight {
+		(17_530_000 as Weight)
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+	// Storage: SubstrateKitties Kitties (r:1 w:1)
+	// Storage: SubstrateKitties Approvals (r:1 w:1)
+	// Storage: SubstrateKitties OperatorApprovals (r:1 w:0)
+	// Storage: SubstrateKitties Auctions (r:1 w:0)
+	// Storage: SubstrateKitties KittiesOwned (r:1 w:2)
+	// Storage: SubstrateKitties OwnedCount (r:2 w:2)
+	// Storage: SubstrateKitties SireOffers (r:0 w:1)
+	fn transfer_from() -> Weight {
+		(48_372_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(7 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(7 as Weight))
+	}
+	// Storage: SubstrateKitties OwnedCount (r:2 w:2)
+	// Storage: SubstrateKitties Kitties (r:1 w:1)
+	// Storage: SubstrateKitties Auctions (r:1 w:0)
+	// Storage: SubstrateKitties KittiesOwned (r:1 w:2)
+	// Storage: SubstrateKitties SireOffers (r:0 w:1)
+	// Storage: SubstrateKitties Approvals (r:0 w:1)
+	fn transfer_batch(n: u32, ) -> Weight {
+		(9_418_000 as Weight)
+			.saturating_add((38_604_000 as Weight).saturating_mul(n as Weight))
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+			.saturating_add(RocksDbWeight::get().reads((4 as Weight).saturating_mul(n as Weight)))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+			.saturating_add(RocksDbWeight::get().writes((6 as Weight).saturating_mul(n as Weight)))
+	}
+	// Storage: SubstrateKitties Kitties (r:1 w:1)
+	// Storage: SubstrateKitties Auctions (r:1 w:0)
+	fn set_price_batch(n: u32, ) -> Weight {
+		(3_207_000 as Weight)
+			.saturating_add((21_835_000 as Weight).saturating_mul(n as Weight))
+			.saturating_add(RocksDbWeight::get().reads((2 as Weight).saturating_mul(n as Weight)))
+			.saturating_add(RocksDbWeight::get().writes((1 as Weight).saturating_mul(n as Weight)))
+	}
+	// Storage: SubstrateKitties Kitties (r:1 w:0)
+	// Storage: SubstrateKitties Auctions (r:1 w:0)
+	// Storage: SubstrateKitties Offers (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	fn make_offer(o: u32, ) -> Weight {
+		(52_816_000 as Weight)
+			.saturating_add((24_310_000 as Weight).saturating_mul(o as Weight))
+			.saturating_add(RocksDbWeight::get().reads(4 as Weight))
+			.saturating_add(RocksDbWeight::get().reads((1 as Weight).saturating_mul(o as Weight)))
+			.saturating_add(RocksDbWeight::get().writes(2 as Weight))
+			.saturating_add(RocksDbWeight::get().writes((1 as Weight).saturating_mul(o as Weight)))
+	}
+	// Storage: SubstrateKitties Kitties (r:1 w:1)
+	// Storage: SubstrateKitties Auctions (r:1 w:0)
+	// Storage: SubstrateKitties Offers (r:1 w:1)
+	// Storage: SubstrateKitties OwnedCount (r:2 w:2)
+	// Storage: System Account (r:4 w:4)
+	// Storage: SubstrateKitties KittiesOwned (r:1 w:2)
+	// Storage: SubstrateKitties SireOffers (r:0 w:1)
+	// Storage: SubstrateKitties Approvals (r:0 w:1)
+	// Storage: SubstrateKitties Royalties (r:1 w:0)
+	fn accept_offer(o: u32, ) -> Weight {
+		(96_427_000 as Weight)
+			.saturating_add((24_876_000 as Weight).saturating_mul(o as Weight))
+			.saturating_add(RocksDbWeight::get().reads(11 as Weight))
+			.saturating_add(RocksDbWeight::get().reads((1 as Weight).saturating_mul(o as Weight)))
+			.saturating_add(RocksDbWeight::get().writes(12 as Weight))
+			.saturating_add(RocksDbWeight::get().writes((1 as Weight).saturating_mul(o as Weight)))
+	}
+	// Storage: SubstrateKitties Offers (r:1 w:1)
 	// Storage: System Account (r:1 w:1)
+	fn withdraw_offer(o: u32, ) -> Weight {
+		(34_902_000 as Weight)
+			.saturating_add((412_000 as Weight).saturating_mul(o as Weight))
+			.saturating_add(RocksDbWeight::get().reads(2 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(2 as Weight))
+	}
+	// Storage: SubstrateKitties Offers (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	fn release_offers(o: u32, ) -> Weight {
+		(4_127_000 as Weight)
+			.saturating_add((24_531_000 as Weight).saturating_mul(o as Weight))
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+			.saturating_add(RocksDbWeight::get().reads((1 as Weight).saturating_mul(o as Weight)))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+			.saturating_add(RocksDbWeight::get().writes((1 as Weight).saturating_mul(o as Weight)))
+	}
+	// Storage: SubstrateKitties Kitties (r:1 w:1)
+	// Storage: SubstrateKitties Auctions (r:1 w:0)
+	// Storage: SubstrateKitties OwnedCount (r:1 w:1)
+	// Storage: SubstrateKitties KittyCnt (r:1 w:1)
+	// Storage: SubstrateKitties MintDeposits (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	// Storage: SubstrateKitties KittiesOwned (r:0 w:1)
+	// Storage: SubstrateKitties BurnedKitties (r:0 w:1)
+	// Storage: SubstrateKitties SireOffers (r:0 w:1)
+	// Storage: SubstrateKitties Approvals (r:0 w:1)
+	fn burn() -> Weight {
+		(58_734_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(6 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(9 as Weight))
+	}
+	// Storage: SubstrateKitties PendingMints (r:1 w:1)
 	// Storage: RandomnessCollectiveFlip RandomMaterial (r:1 w:0)
+	// Storage: SubstrateKitties KittyNonce (r:1 w:1)
 	// Storage: SubstrateKitties KittyCnt (r:1 w:1)
-	// Storage: SubstrateKitties KittiesOwned (r:1 w:1)
-	// Storage: SubstrateKitties Kitties (r:0 w:1)
-	fn create_kitty() -> Weight {
-		(74_000_000 as Weight)
+	// Storage: SubstrateKitties Kitties (r:3 w:1)
+	// Storage: SubstrateKitties BurnedKitties (r:1 w:0)
+	// Storage: SubstrateKitties OwnedCount (r:1 w:1)
+	// Storage: SubstrateKitties KittiesOwned (r:0 w:1)
+	// Storage: SubstrateKitties Royalties (r:0 w:1)
+	// Storage: System Account (r:2 w:2)
+	fn reveal_mints(m: u32, ) -> Weight {
+		(2_914_000 as Weight)
+			.saturating_add((71_562_000 as Weight).saturating_mul(m as Weight))
 			.saturating_add(RocksDbWeight::get().reads(4 as Weight))
-			.saturating_add(RocksDbWeight::get().writes(4 as Weight))
+			.saturating_add(RocksDbWeight::get().reads((7 as Weight).saturating_mul(m as Weight)))
+			.saturating_add(RocksDbWeight::get().writes(3 as Weight))
+			.saturating_add(RocksDbWeight::get().writes((7 as Weight).saturating_mul(m as Weight)))
 	}
 }
@@ -0,0 +1,24 @@
+//! Runtime API definition for the price history of the ocw pallet.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+// The `too_many_arguments` warning originates from `decl_runtime_apis` macro.
+#![allow(clippy::too_many_arguments)]
+// The `unnecessary_mut_passed` warning originates from `decl_runtime_apis` macro.
+#![allow(clippy::unnecessary_mut_passed)]
+
+use codec::Codec;
+use sp_std::vec::Vec;
+
+pub use pallet_ocw::{Price, PriceEntry};
+
+sp_api::decl_runtime_apis! {
+	pub trait PriceOracleApi<BlockNumber, AccountId> where
+		BlockNumber: Codec,
+		AccountId: Codec,
+	{
+		/// Get the aggregated prices kept in the history, from the oldest to the newest.
+		fn price_history() -> Vec<PriceEntry<BlockNumber, AccountId>>;
+		/// Get the time-weighted average price over the blocks `[from, to)`.
+		fn time_weighted_average_price(from: BlockNumber, to: BlockNumber) -> Option<Price>;
+	}
+}
@@ -0,0 +1,157 @@
+//! Benchmarking setup for pallet-ocw
+
+use super::*;
+
+use crate::Pallet as OcwDemo;
+use frame_benchmarking::{account, benchmarks, whitelisted_caller};
+use frame_support::{
+	pallet_prelude::*,
+	traits::{Hooks, UnfilteredDispatchable},
+};
+use frame_system::{offchain::SigningTypes, RawOrigin};
+use parity_scale_codec::Decode;
+use sp_arithmetic::FixedPointNumber;
+use sp_runtime::traits::{IdentifyAccount, TrailingZeroInput, Zero};
+use sp_std::{convert::TryFrom, prelude::*, vec};
+
+// The signatures of the unsigned calls are checked in `validate_unsigned` only, any value does.
+fn public<T: Config>() -> T::Public {
+	<T as SigningTypes>::Public::decode(&mut TrailingZeroInput::new(&[][..]))
+		.expect("a public key decodes from zeroes")
+}
+
+fn signature<T: Config>() -> T::Signature {
+	<T as SigningTypes>::Signature::decode(&mut TrailingZeroInput::new(&[][..]))
+		.expect("a signature decodes from zeroes")
+}
+
+// Fill the number history with `h` numbers.
+fn fill_numbers<T: Config>(h: u32) {
+	Numbers::<T>::mutate(|numbers| (0..h).for_each(|n| numbers.push(n as u64)));
+}
+
+// Fill the price history with `h` prices.
+fn fill_prices<T: Config>(h: u32) {
+	Prices::<T>::mutate(|entries| {
+		for n in 0..h {
+			entries.push(PriceEntry {
+				price: Price::saturating_from_integer(n),
+				block_number: Zero::zero(),
+				submitter: account("reporter", n, 0),
+			});
+		}
+	});
+}
+
+// Put `r` reports from accounts other than the oracle authorities into the current round.
+fn fill_reports<T: Config>(r: u32) -> Result<(), &'static str> {
+	let reports: Vec<_> = (0..r)
+		.map(|n| (account("reporter", n, 0), Price::saturating_from_integer(n)))
+		.collect();
+	RoundReports::<T>::put(BoundedVec::try_from(reports).map_err(|_| "too many reports")?);
+	Ok(())
+}
+
+// A price source whose url and JSON path are as long as allowed.
+fn price_source<T: Config>() -> (Vec<u8>, Vec<u8>) {
+	let len = T::MaxSourceLen::get() as usize;
+	(vec![b'u'; len], vec![b'p'; len])
+}
+
+benchmarks! {
+	submit_number_signed {
+		let h in 0 .. T::MaxHistory::get();
+		fill_numbers::<T>(h);
+		let caller: T::AccountId = whitelisted_caller();
+	}: _(RawOrigin::Signed(caller), 42)
+	verify {
+		assert_eq!(Numbers::<T>::get().back(), Some(&42));
+	}
+
+	submit_number_unsigned {
+		let h in 0 .. T::MaxHistory::get();
+		fill_numbers::<T>(h);
+	}: _(RawOrigin::None, 42)
+	verify {
+		assert_eq!(Numbers::<T>::get().back(), Some(&42));
+	}
+
+	submit_number_unsigned_with_signed_payload {
+		let h in 0 .. T::MaxHistory::get();
+		fill_numbers::<T>(h);
+		let payload = Payload { number: 42, public: public::<T>() };
+	}: _(RawOrigin::None, payload, signature::<T>())
+	verify {
+		assert_eq!(Numbers::<T>::get().back(), Some(&42));
+	}
+
+	// The report completes the round, which is aggregated over the `r` reports before it.
+	submit_price_unsigned_with_signed_payload {
+		let r in T::MinReports::get().saturating_sub(1) .. T::MaxReports::get().saturating_sub(1);
+		let h in 0 .. T::MaxHistory::get();
+		fill_prices::<T>(h);
+		fill_reports::<T>(r)?;
+
+		let public = public::<T>();
+		OracleAuthorities::<T>::insert(public.clone().into_account(), ());
+		let round = CurrentRound::<T>::get();
+		let payload = PayloadPrice { round, price: Price::saturating_from_integer(r), public };
+	}: _(RawOrigin::None, payload, signature::<T>())
+	verify {
+		assert_eq!(CurrentRound::<T>::get(), round + 1);
+	}
+
+	set_price_sources {
+		let s in 0 .. T::MaxPriceSources::get();
+		let sources = vec![price_source::<T>(); s as usize];
+		let origin = T::AdminOrigin::successful_origin();
+		let call = Call::<T>::set_price_sources { sources };
+	}: { call.dispatch_bypass_filter(origin)? }
+	verify {
+		assert_eq!(PriceSources::<T>::get().len() as u32, s);
+	}
+
+	add_authority {
+		let who: T::AccountId = account("authority", 0, 0);
+		let origin = T::AdminOrigin::successful_origin();
+		let call = Call::<T>::add_authority { who: who.clone() };
+	}: { call.dispatch_bypass_filter(origin)? }
+	verify {
+		assert!(OcwDemo::<T>::is_oracle_authority(&who));
+	}
+
+	// The removed authority is the last one and has a pending report, the `r` other reports
+	// are then enough to aggregate the round.
+	remove_authority {
+		let r in 1 .. T::MaxReports::get().saturating_sub(1);
+		let h in 0 .. T::MaxHistory::get();
+		fill_prices::<T>(h);
+		fill_reports::<T>(r)?;
+
+		let who: T::AccountId = account("authority", 0, 0);
+		OracleAuthorities::<T>::insert(&who, ());
+		AuthorityCount::<T>::put(1);
+		RoundReports::<T>::try_append((who.clone(), Price::saturating_from_integer(r)))
+			.map_err(|_| "too many reports")?;
+		let round = CurrentRound::<T>::get();
+		let origin = T::AdminOrigin::successful_origin();
+		let call = Call::<T>::remove_authority { who: who.clone() };
+	}: { call.dispatch_bypass_filter(origin)? }
+	verify {
+		assert!(!OcwDemo::<T>::is_oracle_authority(&who));
+		assert_eq!(CurrentRound::<T>::get(), round + 1);
+	}
+
+	expire_round {
+		let r in 1 .. T::MaxReports::get();
+		fill_reports::<T>(r)?;
+		RoundOpenedAt::<T>::put(T::BlockNumber::zero());
+		let round = CurrentRound::<T>::get();
+	}: { OcwDemo::<T>::on_initialize(T::RoundTimeout::get()); }
+	verify {
+		assert_eq!(CurrentRound::<T>::get(), round + 1);
+		assert!(RoundReports::<T>::get().is_empty());
+	}
+
+	impl_benchmark_test_suite!(OcwDemo, crate::mock::new_test_ext(), crate::mock::Test);
+}
@@ -1,7 +1,15 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
+pub mod migrations;
+pub mod ring_buffer;
+pub mod weights;
+
+#[cfg(test)]
+mod mock;
 #[cfg(test)]
 mod test;
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
 
 pub use pallet::*;
 
@@ -10,7 +18,7 @@ pub mod pallet {
 	//! A demonstration of an offchain worker that sends onchain callbacks
 	use core::{convert::TryInto, fmt};
 	use parity_scale_codec::{Decode, Encode};
-	use frame_support::pallet_prelude::*;
+	use frame_support::{pallet_prelude::*, traits::{StorageVersion, UnixTime}};
 	use frame_system::{
 		pallet_prelude::*,
 		offchain::{
@@ -19,11 +27,11 @@ pub mod pallet {
 		},
 	};
 	use sp_core::{crypto::KeyTypeId};
-	use sp_arithmetic::per_things::Permill;
+	use sp_arithmetic::{FixedPointNumber, FixedU128};
 	use sp_runtime::{
 		offchain as rt_offchain,
 		traits::{
-			BlockNumberProvider
+			BlockNumberProvider, IdentifyAccount, SaturatedConversion, Saturating, Zero,
 		},
 		offchain::{
 			storage::StorageValueRef,
@@ -34,9 +42,13 @@ pub mod pallet {
 		},
 		RuntimeDebug,
 	};
-	use sp_std::{collections::vec_deque::VecDeque, prelude::*, str};
+	use sp_std::{convert::TryFrom, prelude::*, str};
+
+	use crate::ring_buffer::RingBuffer;
+	use crate::weights::WeightInfo;
 
 	use serde::{Deserialize, Deserializer};
+	use serde_json::Value as JsonValue;
 
 	use serde::__private::ToString;
 	use scale_info::prelude::string::String;
@@ -49,22 +61,23 @@ pub mod pallet {
 	/// `KeyTypeId` via the keystore to sign the transaction.
 	/// The keys can be inserted manually via RPC (see `author_insertKey`).
 	pub const KEY_TYPE: KeyTypeId = KeyTypeId(*b"demo");
-	const NUM_VEC_LEN: usize = 10;
 	/// The type to sign and send transactions.
 	const UNSIGNED_TXS_PRIORITY: u64 = 100;
+	/// The custom `InvalidTransaction` code of a price report signed by a key outside
+	/// `OracleAuthorities`.
+	pub const INVALID_ORACLE_AUTHORITY: u8 = 1;
 
 	// We are fetching information from the github public API about organization`substrate-developer-hub`.
 	// const HTTP_REMOTE_REQUEST: &str = "https://api.github.com/orgs/substrate-developer-hub";
 	const HTTP_GITHUB_REMOTE_REQUEST: &str = "https://api.github.com/orgs/substrate-developer-hub";
-	const HTTP_PRICE_REMOTE_REQUEST: &str = "https://api.coincap.io/v2/assets/polkadot";
 	const HTTP_HEADER_USER_AGENT: &str = "tofu2022";
 
 	const FETCH_TIMEOUT_PERIOD: u64 = 3000; // in milli-seconds
 	const LOCK_TIMEOUT_EXPIRATION: u64 = FETCH_TIMEOUT_PERIOD + 1000; // in milli-seconds
 	const LOCK_BLOCK_EXPIRATION: u32 = 3; // in block number
 
-	// the maximum accuracy of Permill::from_parts
-	const PERMILL_MAX: u64 = 1000000;
+	/// The number of decimals of the inner value of `Price`, i.e. the most decimals a price keeps.
+	pub const PRICE_MAX_DECIMALS: u32 = 18;
 
 	/// Based on the above `KeyTypeId` we need to generate a pallet-specific crypto type wrapper.
 	/// We can utilize the supported crypto kinds (`sr25519`, `ed25519` and `ecdsa`) and augment
@@ -98,8 +111,8 @@ pub mod pallet {
 	// Define the unsigned transactions payload
 	#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, scale_info::TypeInfo)]
 	pub struct Payload<Public> {
-		number: u64,
-		public: Public,
+		pub number: u64,
+		pub public: Public,
 	}
 
 	impl<T: SigningTypes> SignedPayload<T> for Payload<T::Public> {
@@ -108,10 +121,12 @@ pub mod pallet {
 		}
 	}
 
+	/// The price report of one oracle key for an aggregation round.
 	#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, scale_info::TypeInfo)]
 	pub struct PayloadPrice<Public> {
-		number: Price,
-		public: Public,
+		pub round: RoundIndex,
+		pub price: Price,
+		pub public: Public,
 	}
 
 	impl<T: SigningTypes> SignedPayload<T> for PayloadPrice<T::Public> {
@@ -154,63 +169,121 @@ pub mod pallet {
 		}
 	}
 
-	/// Define a struct to fetch the price info from polkadot
-	// Json format ref: https://api.coincap.io/v2/assets/polkadot
-	#[derive(Deserialize, Encode, Decode, Default, scale_info::TypeInfo)]
-	struct PriceInfoOuter {
-		data: PriceInfo,
-		timestamp: u64,
-	}
+	/// A price as a fixed-point number with `PRICE_MAX_DECIMALS` decimals.
+	pub type Price = FixedU128;
 
-	#[derive(Deserialize, Encode, Decode, Default, scale_info::TypeInfo)]
-	struct PriceInfo {
-		#[serde(deserialize_with = "de_string_to_tuple")]
-		priceUsd: Price,
-	}
+	/// Format a price as a decimal string without trailing zeros, e.g. "22.6045" or "22".
+	///
+	/// This is the inverse of `Pallet::parse_price`: parsing the result of any price with at most
+	/// `PriceDecimals` decimals gives back the same price.
+	pub fn format_price(price: Price) -> String {
+		let inner = price.into_inner();
+		let mut result = (inner / Price::DIV).to_string();
+
+		let mut fraction = inner % Price::DIV;
+		if fraction == 0 {
+			return result;
+		}
+		let mut digits = PRICE_MAX_DECIMALS as usize;
+		while fraction % 10 == 0 {
+			fraction /= 10;
+			digits -= 1;
+		}
 
-	// Todo: move to pallet::config
-	type Price = (u64, Permill);
-	fn format_price(data: Price) -> String {
-		data.0.to_string() + "." + &data.1.deconstruct().to_string()
+		let fraction = fraction.to_string();
+		result.push('.');
+		for _ in fraction.len()..digits {
+			result.push('0');
+		}
+		result.push_str(&fraction);
+		result
 	}
 
-	pub fn de_string_to_tuple<'de, D>(de: D) -> Result<Price, D::Error>
-		where
-			D: Deserializer<'de>,
-	{
-		let s: &str = Deserialize::deserialize(de)?;
-		let partition: Vec<&str> = s.split(".").collect();
+	/// The median of a list of prices. For an even number of prices the lower one of the two
+	/// middle prices is taken, so the result is always a reported value.
+	pub fn median(prices: Vec<Price>) -> Option<Price> {
+		median_by_key(prices, |price| *price)
+	}
 
-		// todo: avoid overflow from string to integer
-		let integer = partition[0].parse::<u64>().unwrap();
-		let decimal = cut_u64_to_u32_permill(
-			partition[1].parse::<u64>().unwrap());
-		let permill = Permill::from_parts(decimal);
-		log::info!("Price parsing result: ({}, {})", integer, decimal);
+	/// The median of a list of items by their key, taken the same way as `median`.
+	pub fn median_by_key<Item, K: Ord>(mut items: Vec<Item>, key: impl Fn(&Item) -> K) -> Option<Item> {
+		if items.is_empty() {
+			return None;
+		}
+		items.sort_by_key(key);
+		Some(items.swap_remove((items.len() - 1) / 2))
+	}
 
-		let price: Price = (integer, permill);
-		log::info!("Price decode result: ({})", format_price(price));
+	/// An aggregated price kept in the price history.
+	#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, scale_info::TypeInfo, MaxEncodedLen)]
+	pub struct PriceEntry<BlockNumber, AccountId> {
+		/// The median price of the round.
+		pub price: Price,
+		/// The block the round was aggregated in.
+		pub block_number: BlockNumber,
+		/// The oracle key that reported the median price.
+		pub submitter: AccountId,
+	}
 
-		Ok(price)
+	pub type PriceEntryOf<T> =
+		PriceEntry<<T as frame_system::Config>::BlockNumber, <T as frame_system::Config>::AccountId>;
+
+	/// The jobs of the offchain worker, each run on its own interval.
+	#[derive(Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+	pub enum OffchainJob {
+		/// Submit the block number in a signed transaction.
+		SignedTx,
+		/// Submit the block number in an unsigned transaction.
+		UnsignedTx,
+		/// Submit the block number in an unsigned transaction with a signed payload.
+		UnsignedTxSignedPayload,
+		/// Fetch and cache the github info.
+		GithubInfo,
+		/// Fetch the price and report it for the current round.
+		Price,
 	}
 
-	fn cut_u64_to_u32_permill(mut num: u64) -> u32 {
-		while num > PERMILL_MAX {
-			num /= 10;
+	impl OffchainJob {
+		/// All the jobs, in the order they are run within a block.
+		pub const ALL: [OffchainJob; 5] = [
+			OffchainJob::SignedTx,
+			OffchainJob::UnsignedTx,
+			OffchainJob::UnsignedTxSignedPayload,
+			OffchainJob::GithubInfo,
+			OffchainJob::Price,
+		];
+
+		/// The name of the job, used to key its local storage entries.
+		fn name(&self) -> &'static [u8] {
+			match self {
+				OffchainJob::SignedTx => b"signed-tx",
+				OffchainJob::UnsignedTx => b"unsigned-tx",
+				OffchainJob::UnsignedTxSignedPayload => b"unsigned-tx-signed-payload",
+				OffchainJob::GithubInfo => b"gh-info",
+				OffchainJob::Price => b"price",
+			}
 		}
-		return num as u32
 	}
 
-	// Debug trait for type PriceInfo is required by log::info! macro
-	impl fmt::Debug for PriceInfo {
-		// `fmt` converts the vector of bytes inside the struct back to string for
-		//   more friendly display.
-		fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-			write!(
-				f,
-				"{{ price used: {} }}",
-				format_price(self.priceUsd)
-			)
+	/// The index of a price aggregation round.
+	pub type RoundIndex = u32;
+
+	/// A remote endpoint to fetch the price from, together with the JSON path of the price field.
+	#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, scale_info::TypeInfo, MaxEncodedLen)]
+	#[scale_info(skip_type_params(T))]
+	pub struct PriceSource<T: Config> {
+		pub url: BoundedVec<u8, T::MaxSourceLen>,
+		pub json_path: BoundedVec<u8, T::MaxSourceLen>,
+	}
+
+	impl<T: Config> TryFrom<(Vec<u8>, Vec<u8>)> for PriceSource<T> {
+		type Error = Error<T>;
+
+		fn try_from((url, json_path): (Vec<u8>, Vec<u8>)) -> Result<Self, Self::Error> {
+			Ok(PriceSource {
+				url: url.try_into().map_err(|_| Error::<T>::PriceSourceTooLong)?,
+				json_path: json_path.try_into().map_err(|_| Error::<T>::PriceSourceTooLong)?,
+			})
 		}
 	}
 
@@ -223,10 +296,63 @@ pub mod pallet {
 		type Call: From<Call<Self>>;
 		/// The identifier type for an offchain worker.
 		type AuthorityId: AppCrypto<Self::Public, Self::Signature>;
+		/// The origin which may manage the price sources and the oracle authorities.
+		type AdminOrigin: EnsureOrigin<Self::Origin>;
+		/// The time provider used to stamp the aggregated prices.
+		type UnixTime: UnixTime;
+
+		/// The maximum number of price sources.
+		#[pallet::constant]
+		type MaxPriceSources: Get<u32>;
+		/// The maximum length of the url and the JSON path of a price source.
+		#[pallet::constant]
+		type MaxSourceLen: Get<u32>;
+		/// The number of reports from different keys needed to aggregate a round, or all of the
+		/// oracle authorities if there are fewer of them.
+		#[pallet::constant]
+		type MinReports: Get<u32>;
+		/// The maximum number of reports kept for a round, must not be less than `MinReports`.
+		#[pallet::constant]
+		type MaxReports: Get<u32>;
+		/// The number of decimals kept when parsing a price, at most `PRICE_MAX_DECIMALS`.
+		#[pallet::constant]
+		type PriceDecimals: Get<u32>;
+		/// The number of latest numbers and prices kept in the history.
+		#[pallet::constant]
+		type MaxHistory: Get<u32>;
+		/// The number of blocks a round stays open after its first report, before it expires
+		/// without being aggregated.
+		#[pallet::constant]
+		type RoundTimeout: Get<Self::BlockNumber>;
+
+		/// The number of blocks between two runs of the signed transaction job, zero to disable it.
+		#[pallet::constant]
+		type SignedTxInterval: Get<Self::BlockNumber>;
+		/// The number of blocks between two runs of the unsigned transaction job, zero to disable it.
+		#[pallet::constant]
+		type UnsignedTxInterval: Get<Self::BlockNumber>;
+		/// The number of blocks between two runs of the unsigned transaction with signed payload
+		/// job, zero to disable it.
+		#[pallet::constant]
+		type UnsignedTxSignedPayloadInterval: Get<Self::BlockNumber>;
+		/// The number of blocks between two runs of the github info job, zero to disable it.
+		#[pallet::constant]
+		type GithubInfoInterval: Get<Self::BlockNumber>;
+		/// The number of blocks between two runs of the price job, zero to disable it.
+		#[pallet::constant]
+		type PriceInterval: Get<Self::BlockNumber>;
+
+		/// Weight information for the extrinsics of this pallet.
+		type WeightInfo: WeightInfo;
 	}
 
+	/// The current storage version.
+	const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
+
 	#[pallet::pallet]
 	#[pallet::generate_store(pub(super) trait Store)]
+	#[pallet::generate_storage_info]
+	#[pallet::storage_version(STORAGE_VERSION)]
 	pub struct Pallet<T>(_);
 
 	// The pallet's runtime storage items.
@@ -235,25 +361,104 @@ pub mod pallet {
 	#[pallet::getter(fn numbers)]
 	// Learn more about declaring storage items:
 	// https://substrate.dev/docs/en/knowledgebase/runtime/storage#declaring-storage-items
-	pub type Numbers<T> = StorageValue<_, VecDeque<u64>, ValueQuery>;
+	pub type Numbers<T: Config> = StorageValue<_, RingBuffer<u64, T::MaxHistory>, ValueQuery>;
 
+	/// The latest aggregated prices, from the oldest to the newest.
 	#[pallet::storage]
 	#[pallet::getter(fn prices)]
-	pub type Prices<T> = StorageValue<_, VecDeque<(u64, Permill)>, ValueQuery>;
+	pub type Prices<T: Config> =
+		StorageValue<_, RingBuffer<PriceEntryOf<T>, T::MaxHistory>, ValueQuery>;
+
+	/// The endpoints the offchain workers fetch the price from.
+	#[pallet::storage]
+	#[pallet::getter(fn price_sources)]
+	pub type PriceSources<T: Config> =
+		StorageValue<_, BoundedVec<PriceSource<T>, T::MaxPriceSources>, ValueQuery>;
+
+	/// The round the offchain workers are currently reporting prices for.
+	#[pallet::storage]
+	#[pallet::getter(fn current_round)]
+	pub type CurrentRound<T> = StorageValue<_, RoundIndex, ValueQuery>;
+
+	/// The prices reported in the current round, by reporter.
+	#[pallet::storage]
+	#[pallet::getter(fn round_reports)]
+	pub type RoundReports<T: Config> =
+		StorageValue<_, BoundedVec<(T::AccountId, Price), T::MaxReports>, ValueQuery>;
+
+	/// The block the first report of the current round was submitted in, if any.
+	#[pallet::storage]
+	#[pallet::getter(fn round_opened_at)]
+	pub type RoundOpenedAt<T: Config> = StorageValue<_, T::BlockNumber>;
+
+	/// The latest aggregated price and the unix time in milli-seconds it was aggregated at.
+	#[pallet::storage]
+	#[pallet::getter(fn aggregated_price)]
+	pub type AggregatedPrice<T> = StorageValue<_, (Price, u64)>;
+
+	/// The accounts whose keys are allowed to report prices.
+	#[pallet::storage]
+	pub type OracleAuthorities<T: Config> = StorageMap<_, Blake2_128Concat, T::AccountId, ()>;
+
+	/// The number of `OracleAuthorities`.
+	#[pallet::storage]
+	#[pallet::getter(fn authority_count)]
+	pub type AuthorityCount<T> = StorageValue<_, u32, ValueQuery>;
+
+	#[pallet::genesis_config]
+	pub struct GenesisConfig<T: Config> {
+		/// The initial price sources as `(url, json_path)` pairs.
+		pub price_sources: Vec<(Vec<u8>, Vec<u8>)>,
+		/// The initial oracle authorities.
+		pub authorities: Vec<T::AccountId>,
+	}
+
+	#[cfg(feature = "std")]
+	impl<T: Config> Default for GenesisConfig<T> {
+		fn default() -> Self {
+			GenesisConfig { price_sources: vec![], authorities: vec![] }
+		}
+	}
+
+	#[pallet::genesis_build]
+	impl<T: Config> GenesisBuild<T> for GenesisConfig<T> {
+		fn build(&self) {
+			for who in &self.authorities {
+				OracleAuthorities::<T>::insert(who, ());
+			}
+			AuthorityCount::<T>::put(OracleAuthorities::<T>::iter_keys().count() as u32);
+
+			let sources = self.price_sources.iter().cloned()
+				.map(PriceSource::<T>::try_from)
+				.collect::<Result<Vec<_>, _>>()
+				.expect("genesis price sources exceed `MaxSourceLen`");
+			let sources: BoundedVec<_, T::MaxPriceSources> = sources.try_into()
+				.expect("genesis price sources exceed `MaxPriceSources`");
+			PriceSources::<T>::put(sources);
+		}
+	}
 
 	#[pallet::event]
 	#[pallet::generate_deposit(pub(super) fn deposit_event)]
 	pub enum Event<T: Config> {
 		NewNumber(Option<T::AccountId>, u64),
-		NewPrice(Option<T::AccountId>, Price),
+		/// The price sources were replaced. \[count\]
+		PriceSourcesSet(u32),
+		/// A price was reported for a round. \[reporter, round, price\]
+		PriceReported(T::AccountId, RoundIndex, Price),
+		/// A round was aggregated into a median price. \[round, price, timestamp\]
+		PriceAggregated(RoundIndex, Price, u64),
+		/// A round expired before enough keys reported, its reports are dropped. \[round, reports\]
+		RoundExpired(RoundIndex, u32),
+		/// An account was allowed to report prices. \[who\]
+		AuthorityAdded(T::AccountId),
+		/// An account was no longer allowed to report prices. \[who\]
+		AuthorityRemoved(T::AccountId),
 	}
 
 	// Errors inform users that something went wrong.
 	#[pallet::error]
 	pub enum Error<T> {
-		// Error returned when not sure which ocw function to executed
-		UnknownOffchainMux,
-
 		// Error returned when making signed transactions in off-chain worker
 		NoLocalAcctForSigning,
 		OffchainSignedTxError,
@@ -270,12 +475,56 @@ pub mod pallet {
 		// Error returned when try the lock of local persistent storage
 		TryLockFailed,
 
-		// Error returned when failed to deserialize the json string to type
+		// Error returned when failed to deserialize the json string to type, or to parse a price
 		DeserializeError,
+
+		// Error returned when there are more price sources than `MaxPriceSources`
+		TooManyPriceSources,
+
+		// Error returned when the url or JSON path of a price source exceeds `MaxSourceLen`
+		PriceSourceTooLong,
+
+		// Error returned when no price source is configured or none of them answered
+		NoPriceAvailable,
+
+		// Error returned when a price report is not for the current round
+		StaleRound,
+
+		// Error returned when a key reports twice in the same round
+		DuplicateReport,
+
+		// Error returned when the reports of the round exceed `MaxReports`
+		TooManyReports,
+
+		// Error returned when a price report is signed by a key outside `OracleAuthorities`
+		NotOracleAuthority,
+
+		// Error returned when adding an account which is already an oracle authority
+		AlreadyOracleAuthority,
 	}
 
 	#[pallet::hooks]
 	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		fn on_runtime_upgrade() -> Weight {
+			crate::migrations::v1::migrate::<T>()
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade() -> Result<(), &'static str> {
+			crate::migrations::v1::post_upgrade::<T>()
+		}
+
+		/// Expire the current round once `RoundTimeout` blocks passed since its first report.
+		fn on_initialize(now: T::BlockNumber) -> Weight {
+			match Self::round_opened_at() {
+				Some(opened_at) if now >= opened_at.saturating_add(T::RoundTimeout::get()) => {
+					let reports = Self::expire_round(Self::current_round());
+					T::WeightInfo::expire_round(reports)
+				},
+				_ => T::DbWeight::get().reads(1),
+			}
+		}
+
 		/// Offchain Worker entry point.
 		///
 		/// By implementing `fn offchain_worker` you declare a new offchain worker.
@@ -293,19 +542,11 @@ pub mod pallet {
 			// 2. Sending unsigned transaction from ocw
 			// 3. Sending unsigned transactions with signed payloads from ocw
 			// 4. Fetching JSON via http requests in ocw
-			const TX_TYPES: u32 = 5;
-			let modu = block_number.try_into().map_or(TX_TYPES, |bn: usize| (bn as u32) % TX_TYPES);
-			let result = match modu {
-				0 => Self::offchain_signed_tx(block_number),
-				1 => Self::offchain_unsigned_tx(block_number),
-				2 => Self::offchain_unsigned_tx_signed_payload(block_number),
-				3 => Self::fetch_github_info(),
-				4 => Self::fetch_price_n_submit_tx(),
-				_ => Err(Error::<T>::UnknownOffchainMux),
-			};
-
-			if let Err(e) = result {
-				log::error!("offchain_worker error: {:?}", e);
+			// Every job that is due in this block is run, see `OffchainJob`.
+			for job in OffchainJob::ALL.iter() {
+				if let Err(e) = Self::run_job_if_due(*job, block_number) {
+					log::error!("offchain_worker job {:?} error: {:?}", job, e);
+				}
 			}
 
 			log::info!("End of offchain workers!");
@@ -343,7 +584,19 @@ pub mod pallet {
 					if !SignedPayload::<T>::verify::<T::AuthorityId>(payload, signature.clone()) {
 						return InvalidTransaction::BadProof.into();
 					}
-					valid_tx(b"submit_number_unsigned_with_signed_payload".to_vec())
+					if !Self::is_oracle_authority(&payload.public.clone().into_account()) {
+						return InvalidTransaction::Custom(INVALID_ORACLE_AUTHORITY).into();
+					}
+					if payload.round != Self::current_round() {
+						return InvalidTransaction::Stale.into();
+					}
+					// Each key can only report once per round
+					ValidTransaction::with_tag_prefix("ocw-demo-price")
+						.priority(UNSIGNED_TXS_PRIORITY)
+						.and_provides((payload.round, payload.public.clone()))
+						.longevity(3)
+						.propagate(true)
+						.build()
 				},
 				_ => InvalidTransaction::Call.into(),
 			}
@@ -352,7 +605,7 @@ pub mod pallet {
 
 	#[pallet::call]
 	impl<T: Config> Pallet<T> {
-		#[pallet::weight(10000)]
+		#[pallet::weight(T::WeightInfo::submit_number_signed(T::MaxHistory::get()))]
 		pub fn submit_number_signed(origin: OriginFor<T>, number: u64) -> DispatchResult {
 			let who = ensure_signed(origin)?;
 			log::info!("submit_number_signed: ({}, {:?})", number, who);
@@ -362,7 +615,7 @@ pub mod pallet {
 			Ok(())
 		}
 
-		#[pallet::weight(10000)]
+		#[pallet::weight(T::WeightInfo::submit_number_unsigned(T::MaxHistory::get()))]
 		pub fn submit_number_unsigned(origin: OriginFor<T>, number: u64) -> DispatchResult {
 			let _ = ensure_none(origin)?;
 			log::info!("submit_number_unsigned: {}", number);
@@ -372,7 +625,7 @@ pub mod pallet {
 			Ok(())
 		}
 
-		#[pallet::weight(10000)]
+		#[pallet::weight(T::WeightInfo::submit_number_unsigned_with_signed_payload(T::MaxHistory::get()))]
 		pub fn submit_number_unsigned_with_signed_payload(origin: OriginFor<T>, payload: Payload<T::Public>,
 			_signature: T::Signature) -> DispatchResult
 		{
@@ -390,18 +643,93 @@ pub mod pallet {
 		/// 作业回答：
 		/// 选择unsigned transaction是将其与普通交易区分开
 		/// 选择signed payload是因为price等信息需要有对应的问责对象，否则可能导致spamming
-		#[pallet::weight(10000)]
+		#[pallet::weight(T::WeightInfo::submit_price_unsigned_with_signed_payload(
+			T::MaxReports::get(),
+			T::MaxHistory::get(),
+		))]
 		pub fn submit_price_unsigned_with_signed_payload(origin: OriginFor<T>, payload: PayloadPrice<T::Public>,
 														  _signature: T::Signature) -> DispatchResult
 		{
 			let _ = ensure_none(origin)?;
 			// we don't need to verify the signature here because it has been verified in
 			//   `validate_unsigned` function when sending out the unsigned tx.
-			let PayloadPrice { number, public } = payload;
-			log::info!("submit_number_unsigned_with_signed_payload: ({}, {:?})", format_price(number), public);
-			Self::append_or_replace_price(number);
+			let PayloadPrice { round, price, public } = payload;
+			log::info!("submit_price_unsigned_with_signed_payload: ({}, {}, {:?})", round, format_price(price), public);
+
+			let reporter = public.into_account();
+			ensure!(Self::is_oracle_authority(&reporter), Error::<T>::NotOracleAuthority);
+			ensure!(round == Self::current_round(), Error::<T>::StaleRound);
+
+			let reports = RoundReports::<T>::try_mutate(|reports| {
+				ensure!(reports.iter().all(|(who, _)| *who != reporter), Error::<T>::DuplicateReport);
+				reports.try_push((reporter.clone(), price)).map_err(|_| Error::<T>::TooManyReports)?;
+				Ok::<_, Error<T>>(reports.len() as u32)
+			})?;
+			if !RoundOpenedAt::<T>::exists() {
+				RoundOpenedAt::<T>::put(<frame_system::Pallet<T>>::block_number());
+			}
+			Self::deposit_event(Event::PriceReported(reporter, round, price));
+
+			// Aggregate the round once enough keys reported
+			if reports >= Self::min_reports() {
+				Self::aggregate_round(round);
+			}
+			Ok(())
+		}
+
+		/// Replace the price sources as `(url, json_path)` pairs.
+		#[pallet::weight(T::WeightInfo::set_price_sources(sources.len() as u32))]
+		pub fn set_price_sources(origin: OriginFor<T>, sources: Vec<(Vec<u8>, Vec<u8>)>) -> DispatchResult {
+			T::AdminOrigin::ensure_origin(origin)?;
+
+			let sources = sources.into_iter()
+				.map(PriceSource::<T>::try_from)
+				.collect::<Result<Vec<_>, _>>()?;
+			let sources: BoundedVec<_, T::MaxPriceSources> = sources.try_into()
+				.map_err(|_| Error::<T>::TooManyPriceSources)?;
+
+			let count = sources.len() as u32;
+			PriceSources::<T>::put(sources);
+
+			Self::deposit_event(Event::PriceSourcesSet(count));
+			Ok(())
+		}
+
+		/// Allow the keys of `who` to report prices.
+		#[pallet::weight(T::WeightInfo::add_authority())]
+		pub fn add_authority(origin: OriginFor<T>, who: T::AccountId) -> DispatchResult {
+			T::AdminOrigin::ensure_origin(origin)?;
 
-			Self::deposit_event(Event::NewPrice(None, number));
+			ensure!(!Self::is_oracle_authority(&who), Error::<T>::AlreadyOracleAuthority);
+			OracleAuthorities::<T>::insert(&who, ());
+			AuthorityCount::<T>::mutate(|count| *count = count.saturating_add(1));
+
+			Self::deposit_event(Event::AuthorityAdded(who));
+			Ok(())
+		}
+
+		/// Disallow the keys of `who` to report prices.
+		///
+		/// The pending report of `who` is dropped from the current round, which is aggregated if
+		/// the remaining reports are now enough.
+		#[pallet::weight(T::WeightInfo::remove_authority(T::MaxReports::get(), T::MaxHistory::get()))]
+		pub fn remove_authority(origin: OriginFor<T>, who: T::AccountId) -> DispatchResult {
+			T::AdminOrigin::ensure_origin(origin)?;
+
+			ensure!(Self::is_oracle_authority(&who), Error::<T>::NotOracleAuthority);
+			OracleAuthorities::<T>::remove(&who);
+			AuthorityCount::<T>::mutate(|count| *count = count.saturating_sub(1));
+			Self::deposit_event(Event::AuthorityRemoved(who.clone()));
+
+			let reports = RoundReports::<T>::mutate(|reports| {
+				reports.retain(|(reporter, _)| *reporter != who);
+				reports.len() as u32
+			});
+			if reports == 0 {
+				RoundOpenedAt::<T>::kill();
+			} else if reports >= Self::min_reports() {
+				Self::aggregate_round(Self::current_round());
+			}
 			Ok(())
 		}
 	}
@@ -411,52 +739,268 @@ pub mod pallet {
 		///   the bounded length.
 		fn append_or_replace_number(number: u64) {
 			Numbers::<T>::mutate(|numbers| {
-				if numbers.len() == NUM_VEC_LEN {
-					let _ = numbers.pop_front();
-				}
-				numbers.push_back(number);
+				numbers.push(number);
 				log::info!("Number vector: {:?}", numbers);
 			});
 		}
 
-		fn append_or_replace_price(value: Price) {
-			Prices::<T>::mutate(|values| {
-				if values.len() == NUM_VEC_LEN {
-					let _ = values.pop_front();
-				}
-				values.push_back(value);
-				// log::info!("New price vector: {:?}", values);
-			});
+		/// Whether the keys of `who` are allowed to report prices.
+		pub fn is_oracle_authority(who: &T::AccountId) -> bool {
+			OracleAuthorities::<T>::contains_key(who)
 		}
 
-		// todo generalize append_or_replace function
-		/*
-		fn append_or_replace<P: S, U>(number: U) {
-			P::<T>::mutate(|values| {
-				if values.len() == NUM_VEC_LEN {
-					let _ = values.pop_front();
-				}
-				values.push_back(number);
-				log::info!("New vector: {:?}", values);
-			});
+		/// The number of reports needed to aggregate a round: `MinReports`, or all of the oracle
+		/// authorities if there are fewer of them.
+		pub fn min_reports() -> u32 {
+			T::MinReports::get().min(Self::authority_count()).max(1)
 		}
-		 */
 
-		/// fetch the price and generate an unsigned transaction
-		fn fetch_price_n_submit_tx() -> Result<(), Error<T>> {
-			match Self::fetch_price_info() {
-				Ok(price) => {
-					log::info!("fetch price successfully: ({:?})", price);
-					return Self::offchain_unsigned_tx_signed_payload_price(price.priceUsd)
-				}
-				Err(err) => {
-					return Err(err);
+		/// Parse a decimal string such as "22.604569" into a price.
+		///
+		/// Only plain decimals are accepted: digits with an optional fraction, without sign or
+		/// exponent. Decimals beyond `PriceDecimals` are truncated.
+		pub fn parse_price(s: &str) -> Result<Price, Error<T>> {
+			let is_digits = |part: &str| !part.is_empty() && part.bytes().all(|b| b.is_ascii_digit());
+
+			let mut partition = s.splitn(2, '.');
+			let integer = partition.next().unwrap_or_default();
+			let fraction = partition.next().unwrap_or("0");
+			if !is_digits(integer) || !is_digits(fraction) {
+				return Err(<Error<T>>::DeserializeError);
+			}
+
+			// Keep at most `PriceDecimals` decimals, scaled to the inner precision of `Price`
+			let decimals = T::PriceDecimals::get().min(PRICE_MAX_DECIMALS) as usize;
+			let fraction = &fraction[..fraction.len().min(decimals)];
+			let mut inner_fraction: u128 = 0;
+			for digit in fraction.bytes() {
+				inner_fraction = inner_fraction * 10 + (digit - b'0') as u128;
+			}
+			for _ in fraction.len()..PRICE_MAX_DECIMALS as usize {
+				inner_fraction *= 10;
+			}
+
+			let inner = integer.parse::<u128>().ok()
+				.and_then(|integer| integer.checked_mul(Price::DIV))
+				.and_then(|inner| inner.checked_add(inner_fraction))
+				.ok_or(<Error<T>>::DeserializeError)?;
+
+			Ok(Price::from_inner(inner))
+		}
+
+		/// Extract the price found at `path` in a JSON document.
+		///
+		/// The path is a list of keys separated by dots, where numeric keys index into arrays,
+		/// e.g. `data.priceUsd` or `result.0.last`. The value can be either a JSON string holding a plain
+		/// decimal or a JSON number, with or without an exponent.
+		pub fn extract_price(json: &str, path: &str) -> Result<Price, Error<T>> {
+			let doc: JsonValue = serde_json::from_str(json).map_err(|_| <Error<T>>::DeserializeError)?;
+
+			let mut node = &doc;
+			for key in path.split('.').filter(|key| !key.is_empty()) {
+				node = match node {
+					JsonValue::Object(map) => map.get(key),
+					JsonValue::Array(items) => key.parse::<usize>().ok().and_then(|index| items.get(index)),
+					_ => None,
+				}.ok_or(<Error<T>>::DeserializeError)?;
+			}
+
+			match node {
+				JsonValue::String(s) => Self::parse_price(s),
+				JsonValue::Number(n) => Self::parse_number(&n.to_string()),
+				_ => Err(<Error<T>>::DeserializeError),
+			}
+		}
+
+		/// Parse a JSON number into a price, where small and large numbers are printed with an
+		/// exponent, e.g. "1.2e-5" or "1e21".
+		fn parse_number(s: &str) -> Result<Price, Error<T>> {
+			let mut partition = s.splitn(2, |c| c == 'e' || c == 'E');
+			let mantissa = partition.next().unwrap_or_default();
+			let exponent = match partition.next() {
+				Some(exponent) => exponent.parse::<i32>().map_err(|_| <Error<T>>::DeserializeError)?,
+				None => return Self::parse_price(mantissa),
+			};
+			// The mantissa is a plain decimal itself
+			Self::parse_price(mantissa)?;
+
+			let mut partition = mantissa.splitn(2, '.');
+			let integer = partition.next().unwrap_or_default();
+			let fraction = partition.next().unwrap_or_default();
+			let digits: String = integer.chars().chain(fraction.chars()).collect();
+
+			// Move the decimal point by the exponent, padding with zeros. Decimals beyond
+			// `PRICE_MAX_DECIMALS` are truncated anyway, and integer parts longer than an
+			// `u128` overflow anyway.
+			let point = (integer.len() as i64 + exponent as i64)
+				.max(-(PRICE_MAX_DECIMALS as i64))
+				.min(digits.len() as i64 + 40);
+			let zeros = |n: i64| "0".repeat(n as usize);
+			let plain = if point <= 0 {
+				"0.".to_string() + &zeros(-point) + &digits
+			} else if point as usize >= digits.len() {
+				digits.clone() + &zeros(point - digits.len() as i64)
+			} else {
+				let (integer, fraction) = digits.split_at(point as usize);
+				integer.to_string() + "." + fraction
+			};
+
+			Self::parse_price(&plain)
+		}
+
+		/// Aggregate the reports of `round` into their median and move on to the next round.
+		fn aggregate_round(round: RoundIndex) {
+			let reports = RoundReports::<T>::take().into_inner();
+			RoundOpenedAt::<T>::kill();
+
+			// The median is always a reported price, so the history can tell who reported it
+			if let Some((submitter, price)) = median_by_key(reports, |(_, price)| *price) {
+				let now = T::UnixTime::now().as_millis() as u64;
+				AggregatedPrice::<T>::put((price, now));
+				Self::append_or_replace_price(PriceEntry {
+					price,
+					block_number: <frame_system::Pallet<T>>::block_number(),
+					submitter,
+				});
+				Self::deposit_event(Event::PriceAggregated(round, price, now));
+			}
+
+			CurrentRound::<T>::put(round.wrapping_add(1));
+		}
+
+		/// Drop the reports of `round` and move on to the next round, returning the number of
+		/// reports dropped.
+		fn expire_round(round: RoundIndex) -> u32 {
+			let reports = RoundReports::<T>::take().len() as u32;
+			RoundOpenedAt::<T>::kill();
+			CurrentRound::<T>::put(round.wrapping_add(1));
+
+			Self::deposit_event(Event::RoundExpired(round, reports));
+			reports
+		}
+
+		fn append_or_replace_price(entry: PriceEntryOf<T>) {
+			Prices::<T>::mutate(|entries| entries.push(entry));
+		}
+
+		/// The aggregated prices kept in the history, from the oldest to the newest.
+		pub fn price_history() -> Vec<PriceEntryOf<T>> {
+			Self::prices().to_vec()
+		}
+
+		/// The time-weighted average of the price history over the blocks `[from, to)`.
+		///
+		/// Each price holds from the block it was aggregated in until the block of the next one.
+		///   Returns `None` if the range is empty or no price held within it.
+		pub fn time_weighted_average_price(from: T::BlockNumber, to: T::BlockNumber) -> Option<Price> {
+			if to <= from {
+				return None;
+			}
+
+			let history = Self::price_history();
+			let mut weighted_sum: u128 = 0;
+			let mut total_weight: u128 = 0;
+			for (i, entry) in history.iter().enumerate() {
+				let start = entry.block_number.max(from);
+				let end = history.get(i + 1).map_or(to, |next| next.block_number.min(to));
+				if end <= start {
+					continue;
 				}
+
+				let weight = (end - start).saturated_into::<u128>();
+				weighted_sum = weighted_sum.saturating_add(entry.price.into_inner().saturating_mul(weight));
+				total_weight = total_weight.saturating_add(weight);
+			}
+
+			if total_weight == 0 {
+				return None;
+			}
+			Some(Price::from_inner(weighted_sum / total_weight))
+		}
+
+		/// The number of blocks between two runs of `job`, zero if the job is disabled.
+		pub fn job_interval(job: OffchainJob) -> T::BlockNumber {
+			match job {
+				OffchainJob::SignedTx => T::SignedTxInterval::get(),
+				OffchainJob::UnsignedTx => T::UnsignedTxInterval::get(),
+				OffchainJob::UnsignedTxSignedPayload => T::UnsignedTxSignedPayloadInterval::get(),
+				OffchainJob::GithubInfo => T::GithubInfoInterval::get(),
+				OffchainJob::Price => T::PriceInterval::get(),
+			}
+		}
+
+		/// The key of a local storage entry of `job`.
+		fn job_storage_key(job: OffchainJob, entry: &[u8]) -> Vec<u8> {
+			[b"offchain-demo::job::".as_ref(), job.name(), b"::", entry].concat()
+		}
+
+		/// Whether `job` is due at `block_number`, i.e. it is enabled and has not run within its
+		///   interval according to its last-run marker.
+		pub(crate) fn is_job_due(job: OffchainJob, block_number: T::BlockNumber) -> bool {
+			let interval = Self::job_interval(job);
+			if interval.is_zero() {
+				return false;
+			}
+
+			let last_run = StorageValueRef::persistent(&Self::job_storage_key(job, b"last-run"));
+			match last_run.get::<T::BlockNumber>() {
+				Ok(Some(last_run)) => block_number >= last_run.saturating_add(interval),
+				// Never run before, or the marker is unreadable
+				_ => true,
+			}
+		}
+
+		/// Record that `job` ran at `block_number`.
+		pub(crate) fn set_job_last_run(job: OffchainJob, block_number: T::BlockNumber) {
+			StorageValueRef::persistent(&Self::job_storage_key(job, b"last-run")).set(&block_number);
+		}
+
+		/// Run `job` if it is due at `block_number`.
+		///
+		/// Offchain workers of different blocks may run concurrently, so each job is guarded by
+		///   its own lock in the local storage: a job still running in another worker is skipped,
+		///   while the other jobs go on.
+		fn run_job_if_due(job: OffchainJob, block_number: T::BlockNumber) -> Result<(), Error<T>> {
+			if !Self::is_job_due(job, block_number) {
+				return Ok(());
+			}
+
+			let lock_key = Self::job_storage_key(job, b"lock");
+			let mut lock = StorageLock::<BlockAndTime<Self>>::with_block_and_time_deadline(
+				&lock_key, LOCK_BLOCK_EXPIRATION,
+				rt_offchain::Duration::from_millis(LOCK_TIMEOUT_EXPIRATION)
+			);
+			let _guard = lock.try_lock().map_err(|_| <Error<T>>::TryLockFailed)?;
+
+			// Another worker may have run the job while we were waiting for the lock
+			if !Self::is_job_due(job, block_number) {
+				return Ok(());
 			}
+
+			let result = match job {
+				OffchainJob::SignedTx => Self::offchain_signed_tx(block_number),
+				OffchainJob::UnsignedTx => Self::offchain_unsigned_tx(block_number),
+				OffchainJob::UnsignedTxSignedPayload => Self::offchain_unsigned_tx_signed_payload(block_number),
+				OffchainJob::GithubInfo => Self::fetch_github_info(),
+				OffchainJob::Price => Self::fetch_price_n_submit_tx(),
+			};
+
+			// A failed run is recorded too, so the job is retried on its next interval instead of
+			//   every block
+			Self::set_job_last_run(job, block_number);
+			result
 		}
 
-		/// fetch the price from a local storage or a remote url
-		fn fetch_price_info() -> Result<PriceInfo, Error<T>> {
+		/// fetch the price from every source and report their median for the current round
+		pub(crate) fn fetch_price_n_submit_tx() -> Result<(), Error<T>> {
+			let price = Self::fetch_price_info()?;
+			log::info!("fetch price successfully: ({})", format_price(price));
+
+			Self::offchain_unsigned_tx_signed_payload_price(Self::current_round(), price)
+		}
+
+		/// fetch the price from all the remote sources
+		fn fetch_price_info() -> Result<Price, Error<T>> {
 
 			// Note: 建立持久化存储的意义在于部分数据更新频次低，但链上访问频繁
 			// 因此通过本地持久化存储的方式，减少远程通信开销，同时提高访问速度
@@ -470,21 +1014,15 @@ pub mod pallet {
 				return Ok(price_info);
 			}*/
 
-			// if not, try to lock the storage and fetch the price remotely
-			let mut lock = StorageLock::<BlockAndTime<Self>>::with_block_and_time_deadline(
-				b"offchain-demo::price_lock", LOCK_BLOCK_EXPIRATION,
-				rt_offchain::Duration::from_millis(LOCK_TIMEOUT_EXPIRATION)
-			);
-
-			lock.try_lock().map_err(|_| <Error<T>>::TryLockFailed)?;
+			// if not, fetch the price remotely, the job lock keeps concurrent workers from doing it twice
+			// A failing source is skipped, the median of the others is still meaningful
+			let prices = Self::price_sources().iter()
+				.filter_map(|source| Self::fetch_n_parse_price(source).map_err(|e| {
+					log::warn!("skipping price source: {:?}", e);
+				}).ok())
+				.collect::<Vec<_>>();
 
-			match Self::fetch_n_parse_price() {
-				Ok(price_info) => {
-					s_info.set(&price_info);
-					return Ok(price_info);
-				}
-				Err(err) => { return Err(err); }
-			}
+			median(prices).ok_or(<Error<T>>::NoPriceAvailable)
 		}
 
 
@@ -553,8 +1091,9 @@ pub mod pallet {
 			Ok(gh_info)
 		}
 
-		fn fetch_n_parse_price() -> Result<PriceInfo, Error<T>> {
-			let resp_bytes = Self::fetch_from_remote(HTTP_PRICE_REMOTE_REQUEST).map_err(|e| {
+		fn fetch_n_parse_price(source: &PriceSource<T>) -> Result<Price, Error<T>> {
+			let url = str::from_utf8(&source.url).map_err(|_| <Error<T>>::HttpFetchingError)?;
+			let resp_bytes = Self::fetch_from_remote(url).map_err(|e| {
 				log::error!("fetch_from_remote error: {:?}", e);
 				<Error<T>>::HttpFetchingError
 			})?;
@@ -563,13 +1102,11 @@ pub mod pallet {
 			// Print out our fetched JSON string
 			log::info!("{}", resp_str);
 
-			// Deserializing JSON to struct, thanks to `serde` and `serde_derive`
-			let price_info: PriceInfoOuter =
-				serde_json::from_str(&resp_str).map_err(|e| {
-					log::error!("deserialize PriceInfoOuter error: {:?}", e);
-					<Error<T>>::DeserializeError
-				})?;
-			Ok(price_info.data)
+			let path = str::from_utf8(&source.json_path).map_err(|_| <Error<T>>::DeserializeError)?;
+			Self::extract_price(resp_str, path).map_err(|e| {
+				log::error!("no price found at `{}` in response of {}", path, url);
+				e
+			})
 		}
 
 		/// todo: generalize the fetch_n_parse function
@@ -701,7 +1238,7 @@ pub mod pallet {
 			Err(<Error<T>>::NoLocalAcctForSigning)
 		}
 
-		fn offchain_unsigned_tx_signed_payload_price(price: Price) -> Result<(), Error<T>> {
+		fn offchain_unsigned_tx_signed_payload_price(round: RoundIndex, price: Price) -> Result<(), Error<T>> {
 			// Retrieve the signer to sign the payload
 			let signer = Signer::<T, T::AuthorityId>::any_account();
 
@@ -711,7 +1248,7 @@ pub mod pallet {
 			//   - `Some((account, Ok(())))`: transaction is successfully sent
 			//   - `Some((account, Err(())))`: error occured when sending the transaction
 			if let Some((_, res)) = signer.send_unsigned_transaction(
-				|acct| PayloadPrice { number: price, public: acct.public.clone() },
+				|acct| PayloadPrice { round, price, public: acct.public.clone() },
 				|payload, signature| Call::submit_price_unsigned_with_signed_payload { payload, signature }
 			) {
 				return res.map_err(|_| {
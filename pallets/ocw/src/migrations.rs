@@ -0,0 +1,65 @@
+//! Storage migrations for the ocw pallet.
+
+/// Migration to storage version 1: `Numbers` and `Prices` move from unbounded `VecDeque`s to
+/// `RingBuffer`s of at most `MaxHistory` items.
+pub mod v1 {
+	use crate::{
+		pallet::{Config, Numbers, Pallet, Prices},
+		ring_buffer::RingBuffer,
+	};
+	use frame_support::{
+		traits::{GetStorageVersion, StorageVersion},
+		weights::Weight,
+	};
+	use sp_std::prelude::*;
+
+	/// Keep the latest `MaxHistory` numbers, and drop the prices.
+	pub fn migrate<T: Config>() -> Weight {
+		if Pallet::<T>::on_chain_storage_version() != 0 {
+			log::info!("ocw: storage not at version 0, skipping the v1 migration");
+			return T::DbWeight::get().reads(1);
+		}
+
+		// A `VecDeque` encodes as a `Vec`, from the oldest number to the newest
+		let translated = Numbers::<T>::translate::<Vec<u64>, _>(|old| {
+			old.map(|old| {
+				let mut numbers = RingBuffer::default();
+				for number in old {
+					numbers.push(number);
+				}
+				numbers
+			})
+		});
+		if translated.is_err() {
+			log::warn!("ocw: undecodable numbers dropped in the v1 migration");
+			Numbers::<T>::kill();
+		}
+
+		// A history entry records the block and the submitter of its price, the old prices have
+		// neither
+		Prices::<T>::kill();
+
+		StorageVersion::new(1).put::<Pallet<T>>();
+		log::info!("ocw: migrated the numbers and dropped the prices for storage version 1");
+
+		T::DbWeight::get().reads_writes(2, 3)
+	}
+
+	/// Check the numbers and prices decode in the new layout.
+	#[cfg(feature = "try-runtime")]
+	pub fn post_upgrade<T: Config>() -> Result<(), &'static str> {
+		frame_support::ensure!(
+			Pallet::<T>::on_chain_storage_version() >= 1,
+			"ocw: storage version not upgraded to 1"
+		);
+		frame_support::ensure!(
+			!Numbers::<T>::exists() || Numbers::<T>::try_get().is_ok(),
+			"ocw: numbers undecodable after the v1 migration"
+		);
+		frame_support::ensure!(
+			!Prices::<T>::exists() || Prices::<T>::try_get().is_ok(),
+			"ocw: prices undecodable after the v1 migration"
+		);
+		Ok(())
+	}
+}
@@ -0,0 +1,219 @@
+use crate as pallet_ocw;
+use frame_support::{parameter_types, traits::GenesisBuild};
+use frame_system::EnsureRoot;
+use sp_core::{
+	offchain::{testing, OffchainDbExt, OffchainWorkerExt, TransactionPoolExt},
+	sr25519::Signature,
+	H256,
+};
+use sp_keystore::{testing::KeyStore, KeystoreExt, SyncCryptoStore};
+use sp_runtime::{
+	testing::{Header, TestXt},
+	traits::{BlakeTwo256, Extrinsic as ExtrinsicT, IdentifyAccount, IdentityLookup, Verify},
+	RuntimeAppPublic,
+};
+use std::sync::Arc;
+
+type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
+type Block = frame_system::mocking::MockBlock<Test>;
+
+// Configure a mock runtime to test the pallet.
+frame_support::construct_runtime!(
+	pub enum Test where
+		Block = Block,
+		NodeBlock = Block,
+		UncheckedExtrinsic = UncheckedExtrinsic,
+	{
+		System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
+		Timestamp: pallet_timestamp::{Pallet, Call, Storage, Inherent},
+		OcwDemo: pallet_ocw::{Pallet, Call, Storage, Config<T>, Event<T>, ValidateUnsigned},
+	}
+);
+
+parameter_types! {
+	pub const BlockHashCount: u64 = 250;
+	pub const SS58Prefix: u8 = 42;
+}
+
+impl frame_system::Config for Test {
+	type BaseCallFilter = frame_support::traits::Everything;
+	type BlockWeights = ();
+	type BlockLength = ();
+	type DbWeight = ();
+	type Origin = Origin;
+	type Call = Call;
+	type Index = u64;
+	type BlockNumber = u64;
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type AccountId = sp_core::sr25519::Public;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type Event = Event;
+	type BlockHashCount = BlockHashCount;
+	type Version = ();
+	type PalletInfo = PalletInfo;
+	type AccountData = ();
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+	type SystemWeightInfo = ();
+	type SS58Prefix = SS58Prefix;
+	type OnSetCode = ();
+}
+
+parameter_types! {
+	pub const MinimumPeriod: u64 = 1;
+}
+
+impl pallet_timestamp::Config for Test {
+	type Moment = u64;
+	type OnTimestampSet = ();
+	type MinimumPeriod = MinimumPeriod;
+	type WeightInfo = ();
+}
+
+pub type Extrinsic = TestXt<Call, ()>;
+pub(crate) type AccountId = <<Signature as Verify>::Signer as IdentifyAccount>::AccountId;
+
+impl frame_system::offchain::SigningTypes for Test {
+	type Public = <Signature as Verify>::Signer;
+	type Signature = Signature;
+}
+
+impl<LocalCall> frame_system::offchain::SendTransactionTypes<LocalCall> for Test
+where
+	Call: From<LocalCall>,
+{
+	type OverarchingCall = Call;
+	type Extrinsic = Extrinsic;
+}
+
+impl<LocalCall> frame_system::offchain::CreateSignedTransaction<LocalCall> for Test
+where
+	Call: From<LocalCall>,
+{
+	fn create_transaction<C: frame_system::offchain::AppCrypto<Self::Public, Self::Signature>>(
+		call: Call,
+		_public: <Signature as Verify>::Signer,
+		_account: AccountId,
+		nonce: u64,
+	) -> Option<(Call, <Extrinsic as ExtrinsicT>::SignaturePayload)> {
+		Some((call, (nonce, ())))
+	}
+}
+
+parameter_types! {
+	pub const MaxPriceSources: u32 = 3;
+	pub const MaxSourceLen: u32 = 64;
+	// A round is aggregated once 2 keys reported
+	pub const MinReports: u32 = 2;
+	pub const MaxReports: u32 = 4;
+	pub const PriceDecimals: u32 = 6;
+	pub const MaxHistory: u32 = 3;
+	// A round expires 5 blocks after its first report
+	pub const RoundTimeout: u64 = 5;
+	// Each job runs on a different interval, the github info job is disabled
+	pub const SignedTxInterval: u64 = 2;
+	pub const UnsignedTxInterval: u64 = 3;
+	pub const UnsignedTxSignedPayloadInterval: u64 = 4;
+	pub const GithubInfoInterval: u64 = 0;
+	pub const PriceInterval: u64 = 6;
+}
+
+impl pallet_ocw::Config for Test {
+	type Event = Event;
+	type Call = Call;
+	type AuthorityId = pallet_ocw::crypto::TestAuthId;
+	type AdminOrigin = EnsureRoot<AccountId>;
+	type UnixTime = Timestamp;
+	type MaxPriceSources = MaxPriceSources;
+	type MaxSourceLen = MaxSourceLen;
+	type MinReports = MinReports;
+	type MaxReports = MaxReports;
+	type PriceDecimals = PriceDecimals;
+	type MaxHistory = MaxHistory;
+	type RoundTimeout = RoundTimeout;
+	type SignedTxInterval = SignedTxInterval;
+	type UnsignedTxInterval = UnsignedTxInterval;
+	type UnsignedTxSignedPayloadInterval = UnsignedTxSignedPayloadInterval;
+	type GithubInfoInterval = GithubInfoInterval;
+	type PriceInterval = PriceInterval;
+	type WeightInfo = ();
+}
+
+pub(crate) const SOURCE_A: &str = "https://a.example/price";
+pub(crate) const SOURCE_B: &str = "https://b.example/price";
+pub(crate) const SOURCE_C: &str = "https://c.example/price";
+
+// The oracle authorities of the genesis, from 1 to 3.
+pub(crate) fn authority(n: u8) -> AccountId {
+	sp_core::sr25519::Public::from_raw([n; 32])
+}
+
+// Build genesis storage with three price sources, each with a different JSON layout.
+pub(crate) fn new_test_ext() -> sp_io::TestExternalities {
+	let mut t = frame_system::GenesisConfig::default().build_storage::<Test>().unwrap();
+	let config = pallet_ocw::GenesisConfig::<Test> {
+		price_sources: vec![
+			(SOURCE_A.as_bytes().to_vec(), b"data.priceUsd".to_vec()),
+			(SOURCE_B.as_bytes().to_vec(), b"USD".to_vec()),
+			(SOURCE_C.as_bytes().to_vec(), b"result.0.last".to_vec()),
+		],
+		authorities: vec![authority(1), authority(2), authority(3)],
+	};
+	GenesisBuild::<Test>::assimilate_storage(&config, &mut t).unwrap();
+
+	let mut ext = sp_io::TestExternalities::new(t);
+	ext.execute_with(|| System::set_block_number(1));
+	ext
+}
+
+// Build test externalities with an offchain worker environment: an HTTP mock, a transaction
+// pool and a keystore holding one oracle key.
+pub(crate) fn new_offchain_test_ext() -> (
+	sp_io::TestExternalities,
+	Arc<parking_lot::RwLock<testing::OffchainState>>,
+	Arc<parking_lot::RwLock<testing::PoolState>>,
+	sp_core::sr25519::Public,
+) {
+	const PHRASE: &str =
+		"news slush supreme milk chapter athlete soap sausage put clutch what kitten";
+
+	let (offchain, offchain_state) = testing::TestOffchainExt::new();
+	let (pool, pool_state) = testing::TestTransactionPoolExt::new();
+	let keystore = KeyStore::new();
+	SyncCryptoStore::sr25519_generate_new(
+		&keystore,
+		crate::crypto::Public::ID,
+		Some(&format!("{}/oracle1", PHRASE)),
+	)
+	.unwrap();
+	let public_key = SyncCryptoStore::sr25519_public_keys(&keystore, crate::crypto::Public::ID)
+		.get(0)
+		.unwrap()
+		.clone();
+
+	let mut ext = new_test_ext();
+	ext.register_extension(OffchainDbExt::new(offchain.clone()));
+	ext.register_extension(OffchainWorkerExt::new(offchain));
+	ext.register_extension(TransactionPoolExt::new(pool));
+	ext.register_extension(KeystoreExt(Arc::new(keystore)));
+
+	(ext, offchain_state, pool_state, public_key)
+}
+
+// Expect one GET request to `uri`, answered with `body`.
+pub(crate) fn expect_price_request(
+	state: &mut testing::OffchainState,
+	uri: &str,
+	body: &[u8],
+) {
+	state.expect_request(testing::PendingRequest {
+		method: "GET".into(),
+		uri: uri.into(),
+		headers: vec![("User-Agent".into(), "tofu2022".into())],
+		response: Some(body.to_vec()),
+		sent: true,
+		..Default::default()
+	});
+}
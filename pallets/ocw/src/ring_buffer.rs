@@ -0,0 +1,95 @@
+//! A bounded ring buffer to keep the latest values in storage.
+
+use frame_support::{
+	pallet_prelude::*,
+	CloneNoBound, EqNoBound, PartialEqNoBound, RuntimeDebugNoBound,
+};
+use sp_std::prelude::*;
+
+/// A ring buffer of at most `S` items.
+///
+/// Pushing onto a full buffer overwrites the oldest item, so that a push is a single write of a
+/// bounded value instead of shifting the whole history.
+#[derive(
+	Encode, Decode, CloneNoBound, PartialEqNoBound, EqNoBound, RuntimeDebugNoBound, TypeInfo,
+)]
+#[scale_info(skip_type_params(S))]
+pub struct RingBuffer<Item, S>
+where
+	Item: Clone + PartialEq + Eq + sp_std::fmt::Debug,
+	S: Get<u32>,
+{
+	items: BoundedVec<Item, S>,
+	// The index of the oldest item once the buffer is full.
+	head: u32,
+}
+
+impl<Item, S> Default for RingBuffer<Item, S>
+where
+	Item: Clone + PartialEq + Eq + sp_std::fmt::Debug,
+	S: Get<u32>,
+{
+	fn default() -> Self {
+		RingBuffer { items: Default::default(), head: 0 }
+	}
+}
+
+impl<Item, S> MaxEncodedLen for RingBuffer<Item, S>
+where
+	Item: Clone + PartialEq + Eq + sp_std::fmt::Debug + MaxEncodedLen,
+	S: Get<u32>,
+{
+	fn max_encoded_len() -> usize {
+		BoundedVec::<Item, S>::max_encoded_len().saturating_add(u32::max_encoded_len())
+	}
+}
+
+impl<Item, S> RingBuffer<Item, S>
+where
+	Item: Clone + PartialEq + Eq + sp_std::fmt::Debug,
+	S: Get<u32>,
+{
+	/// Append `item` as the newest one, overwriting the oldest one if the buffer is full.
+	pub fn push(&mut self, item: Item) {
+		if (self.items.len() as u32) < S::get() {
+			let _ = self.items.try_push(item);
+			return;
+		}
+		// Full, or not able to keep any item at all
+		if let Some(oldest) = self.items.get_mut(self.head as usize) {
+			*oldest = item;
+			self.head = (self.head + 1) % self.items.len() as u32;
+		}
+	}
+
+	/// The number of items in the buffer.
+	pub fn len(&self) -> usize {
+		self.items.len()
+	}
+
+	/// Whether the buffer has no item.
+	pub fn is_empty(&self) -> bool {
+		self.items.is_empty()
+	}
+
+	/// The newest item.
+	pub fn back(&self) -> Option<&Item> {
+		let newest = match self.head {
+			0 => self.items.len().checked_sub(1)?,
+			head => head as usize - 1,
+		};
+		self.items.get(newest)
+	}
+
+	/// Iterate over the items from the oldest to the newest.
+	pub fn iter(&self) -> impl Iterator<Item = &Item> {
+		let head = (self.head as usize).min(self.items.len());
+		let (newer, older) = self.items.split_at(head);
+		older.iter().chain(newer.iter())
+	}
+
+	/// The items from the oldest to the newest.
+	pub fn to_vec(&self) -> Vec<Item> {
+		self.iter().cloned().collect()
+	}
+}
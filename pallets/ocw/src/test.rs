@@ -1,23 +1,600 @@
-use sp_arithmetic::per_things::Permill;
-use scale_info::prelude::string::String;
+mod oracle {
+	use crate::{
+		crypto, format_price, median, mock::*, AggregatedPrice, CurrentRound, Error, OracleAuthorities,
+		PayloadPrice, Price, PriceEntry, Prices, RoundOpenedAt, RoundReports, INVALID_ORACLE_AUTHORITY,
+	};
+	use parity_scale_codec::{Decode, Encode};
+	use rand::{rngs::SmallRng, Rng, SeedableRng};
+	use frame_support::{assert_noop, assert_ok, traits::Hooks, unsigned::ValidateUnsigned};
+	use frame_system::offchain::{SignedPayload, SigningTypes};
+	use sp_arithmetic::FixedPointNumber;
+	use sp_core::{sr25519::{Pair, Public, Signature}, Pair as _};
+	use sp_runtime::transaction_validity::{InvalidTransaction, TransactionSource};
 
-#[test]
-fn Permill_test() {
-	let integer: u64 = 22;
-	let decimal: u32 = 604569; // 604569863
+	// A price with 6 decimals, as kept by the mock
+	fn price(integer: u64, micros: u32) -> Price {
+		Price::from_inner(integer as u128 * Price::DIV + micros as u128 * 1_000_000_000_000)
+	}
 
-	format_price((integer, Permill::from_perthousand(decimal)));
+	fn report(reporter: u8, round: u32, value: Price) -> frame_support::dispatch::DispatchResult {
+		OcwDemo::submit_price_unsigned_with_signed_payload(
+			Origin::none(),
+			PayloadPrice { round, price: value, public: Public::from_raw([reporter; 32]) },
+			Signature::from_raw([0u8; 64]),
+		)
+	}
 
-	format_price((integer, Permill::from_parts(decimal)));
+	#[test]
+	fn extract_price_by_json_path() {
+		let coincap = r#"{"data":{"id":"polkadot","priceUsd":"22.604569"},"timestamp":1}"#;
+		assert_eq!(OcwDemo::extract_price(coincap, "data.priceUsd").ok(), Some(price(22, 604569)));
+
+		let cryptocompare = r#"{"USD":23.123456}"#;
+		assert_eq!(OcwDemo::extract_price(cryptocompare, "USD").ok(), Some(price(23, 123456)));
+
+		let list = r#"{"result":[{"last":"22.900000"}]}"#;
+		assert_eq!(OcwDemo::extract_price(list, "result.0.last").ok(), Some(price(22, 900000)));
+
+		// small and large numbers are printed with an exponent
+		let small = r#"{"USD":0.000012}"#;
+		assert_eq!(OcwDemo::extract_price(small, "USD").ok(), Some(price(0, 12)));
+		let exponent = r#"{"USD":1.2e-5}"#;
+		assert_eq!(OcwDemo::extract_price(exponent, "USD").ok(), Some(price(0, 12)));
+		let exponent = r#"{"USD":2.5E2}"#;
+		assert_eq!(OcwDemo::extract_price(exponent, "USD").ok(), Some(price(250, 0)));
+		let tiny = r#"{"USD":1e-30}"#;
+		assert_eq!(OcwDemo::extract_price(tiny, "USD").ok(), Some(price(0, 0)));
+		let huge = r#"{"USD":1e300}"#;
+		assert!(OcwDemo::extract_price(huge, "USD").is_err());
+		// exponents are only read from JSON numbers
+		let string = r#"{"USD":"1.2e-5"}"#;
+		assert!(OcwDemo::extract_price(string, "USD").is_err());
+
+		// missing fields, non-price values and malformed documents
+		assert!(OcwDemo::extract_price(coincap, "data.price").is_err());
+		assert!(OcwDemo::extract_price(coincap, "data.id").is_err());
+		assert!(OcwDemo::extract_price(list, "result.1.last").is_err());
+		assert!(OcwDemo::extract_price("{", "USD").is_err());
+	}
+
+	#[test]
+	fn parse_and_format_price() {
+		assert_eq!(OcwDemo::parse_price("22.604569").ok(), Some(price(22, 604569)));
+		assert_eq!(OcwDemo::parse_price("22").ok(), Some(price(22, 0)));
+		assert_eq!(OcwDemo::parse_price("0.000001").ok(), Some(price(0, 1)));
+		// decimals beyond `PriceDecimals` are truncated
+		assert_eq!(OcwDemo::parse_price("22.6045698").ok(), Some(price(22, 604569)));
+
+		assert_eq!(format_price(price(22, 604569)), "22.604569");
+		assert_eq!(format_price(price(22, 500000)), "22.5");
+		assert_eq!(format_price(price(0, 1)), "0.000001");
+		assert_eq!(format_price(price(22, 0)), "22");
+
+		// only plain decimals are accepted
+		for invalid in ["", ".", "22.", ".5", "-1", "+1", "1e5", " 1", "1.2.3", "0x10", "2.5a"] {
+			assert!(OcwDemo::parse_price(invalid).is_err(), "{:?} should be rejected", invalid);
+		}
+		// the integer part overflows the inner value
+		assert!(OcwDemo::parse_price("340282366920938463464").is_err());
+	}
+
+	// A random decimal string with `fraction_len` decimals and no leading or trailing zeros.
+	fn random_decimal(rng: &mut SmallRng, fraction_len: usize) -> String {
+		let mut s = rng.gen_range(0u64..1_000_000_000_000).to_string();
+		if fraction_len > 0 {
+			s.push('.');
+			for _ in 1..fraction_len {
+				s.push(char::from(b'0' + rng.gen_range(0..10)));
+			}
+			s.push(char::from(b'1' + rng.gen_range(0..9)));
+		}
+		s
+	}
+
+	#[test]
+	fn format_price_round_trips() {
+		let mut rng = SmallRng::seed_from_u64(42);
+		for _ in 0..10_000 {
+			// decimal strings within `PriceDecimals` are kept exactly
+			let fraction_len = rng.gen_range(0..=6);
+			let s = random_decimal(&mut rng, fraction_len);
+			let parsed = OcwDemo::parse_price(&s).expect("a valid decimal");
+			assert_eq!(format_price(parsed), s);
+			assert_eq!(OcwDemo::parse_price(&format_price(parsed)).ok(), Some(parsed));
+
+			// longer decimals are truncated, and the truncated price still round-trips
+			let fraction_len = rng.gen_range(7..=24);
+			let s = random_decimal(&mut rng, fraction_len);
+			let parsed = OcwDemo::parse_price(&s).expect("a valid decimal");
+			assert!(s.starts_with(&format_price(parsed)));
+			assert_eq!(OcwDemo::parse_price(&format_price(parsed)).ok(), Some(parsed));
+		}
+	}
+
+	#[test]
+	fn parse_price_never_panics() {
+		const ALPHABET: &[u8] = b"0123456789.-+e ";
+		let mut rng = SmallRng::seed_from_u64(42);
+		for _ in 0..10_000 {
+			let len = rng.gen_range(0..48);
+			let s = (0..len)
+				.map(|_| char::from(ALPHABET[rng.gen_range(0..ALPHABET.len())]))
+				.collect::<String>();
+
+			if let Ok(parsed) = OcwDemo::parse_price(&s) {
+				// whatever is accepted is a plain decimal of the same value
+				assert!(s.bytes().all(|b| b.is_ascii_digit() || b == b'.'));
+				assert_eq!(OcwDemo::parse_price(&format_price(parsed)).ok(), Some(parsed));
+			}
+		}
+	}
+
+	#[test]
+	fn median_of_prices() {
+		assert_eq!(median(vec![]), None);
+		assert_eq!(median(vec![price(3, 0), price(1, 0), price(2, 0)]), Some(price(2, 0)));
+		// the lower middle for an even number of prices
+		assert_eq!(median(vec![price(4, 0), price(1, 0), price(3, 0), price(2, 0)]), Some(price(2, 0)));
+	}
+
+	#[test]
+	fn worker_reports_median_of_all_sources() {
+		let (mut t, offchain_state, pool_state, public_key) = new_offchain_test_ext();
+		{
+			let mut state = offchain_state.write();
+			expect_price_request(&mut state, SOURCE_A, br#"{"data":{"priceUsd":"22.500000"}}"#);
+			expect_price_request(&mut state, SOURCE_B, br#"{"USD":23.123456}"#);
+			expect_price_request(&mut state, SOURCE_C, br#"{"result":[{"last":"22.900000"}]}"#);
+		}
+
+		t.execute_with(|| {
+			assert_ok!(OcwDemo::fetch_price_n_submit_tx());
+
+			let tx = pool_state.write().transactions.pop().unwrap();
+			assert!(pool_state.read().transactions.is_empty());
+			let tx = Extrinsic::decode(&mut &*tx).unwrap();
+			assert_eq!(tx.signature, None);
+
+			if let Call::OcwDemo(crate::Call::submit_price_unsigned_with_signed_payload {
+				payload,
+				signature,
+			}) = tx.call
+			{
+				assert_eq!(
+					payload,
+					PayloadPrice { round: 0, price: price(22, 900000), public: public_key }
+				);
+
+				let signature_valid = <PayloadPrice<<Test as SigningTypes>::Public> as SignedPayload<
+					Test,
+				>>::verify::<crypto::TestAuthId>(&payload, signature);
+				assert!(signature_valid);
+			} else {
+				panic!("unexpected call: {:?}", tx.call);
+			}
+		});
+	}
+
+	#[test]
+	fn worker_skips_failing_source() {
+		let (mut t, offchain_state, pool_state, _) = new_offchain_test_ext();
+		{
+			let mut state = offchain_state.write();
+			expect_price_request(&mut state, SOURCE_A, br#"{"data":{"priceUsd":"22.500000"}}"#);
+			expect_price_request(&mut state, SOURCE_B, br#"{"EUR":20.1}"#);
+			expect_price_request(&mut state, SOURCE_C, br#"{"result":[{"last":"22.900000"}]}"#);
+		}
+
+		t.execute_with(|| {
+			assert_ok!(OcwDemo::fetch_price_n_submit_tx());
+
+			let tx = pool_state.write().transactions.pop().unwrap();
+			let tx = Extrinsic::decode(&mut &*tx).unwrap();
+			if let Call::OcwDemo(crate::Call::submit_price_unsigned_with_signed_payload {
+				payload, ..
+			}) = tx.call
+			{
+				assert_eq!(payload.price, price(22, 500000));
+			} else {
+				panic!("unexpected call: {:?}", tx.call);
+			}
+		});
+	}
+
+	#[test]
+	fn reports_are_aggregated_per_round() {
+		new_test_ext().execute_with(|| {
+			Timestamp::set_timestamp(42_000);
+
+			// the first report is kept until enough keys reported
+			assert_ok!(report(1, 0, price(22, 100000)));
+			assert_eq!(RoundReports::<Test>::get().len(), 1);
+			assert_eq!(AggregatedPrice::<Test>::get(), None);
+
+			// a key can only report once per round
+			assert_noop!(report(1, 0, price(22, 300000)), Error::<Test>::DuplicateReport);
+
+			// the second key completes the round
+			assert_ok!(report(2, 0, price(21, 0)));
+			assert_eq!(AggregatedPrice::<Test>::get(), Some((price(21, 0), 42_000)));
+			// the history keeps the block and the key of the median report
+			assert_eq!(
+				Prices::<Test>::get().back(),
+				Some(&PriceEntry { price: price(21, 0), block_number: 1, submitter: authority(2) })
+			);
+			assert_eq!(CurrentRound::<Test>::get(), 1);
+			assert!(RoundReports::<Test>::get().is_empty());
+
+			// reports for an old round are rejected
+			assert_noop!(report(3, 0, price(22, 0)), Error::<Test>::StaleRound);
+		});
+	}
+
+	#[test]
+	fn min_reports_follow_the_authority_set() {
+		new_test_ext().execute_with(|| {
+			assert_eq!(OcwDemo::authority_count(), 3);
+			assert_eq!(OcwDemo::min_reports(), 2);
+
+			assert_ok!(report(1, 0, price(22, 0)));
+
+			// two authorities still need two reports
+			assert_ok!(OcwDemo::remove_authority(Origin::root(), authority(2)));
+			assert_eq!(OcwDemo::min_reports(), 2);
+			assert_eq!(AggregatedPrice::<Test>::get(), None);
+
+			// a single authority aggregates the round on its own
+			assert_ok!(OcwDemo::remove_authority(Origin::root(), authority(3)));
+			assert_eq!(OcwDemo::min_reports(), 1);
+			assert_eq!(AggregatedPrice::<Test>::get().map(|(price, _)| price), Some(price(22, 0)));
+			assert_eq!(CurrentRound::<Test>::get(), 1);
+
+			assert_ok!(report(1, 1, price(23, 0)));
+			assert_eq!(AggregatedPrice::<Test>::get().map(|(price, _)| price), Some(price(23, 0)));
+			assert_eq!(CurrentRound::<Test>::get(), 2);
+		});
+	}
+
+	#[test]
+	fn removed_authority_report_is_dropped() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(report(2, 0, price(99, 0)));
+			assert_ok!(OcwDemo::remove_authority(Origin::root(), authority(2)));
+			assert!(RoundReports::<Test>::get().is_empty());
+			assert_eq!(RoundOpenedAt::<Test>::get(), None);
+
+			// the round is aggregated over the reports of the remaining authorities only
+			assert_ok!(report(1, 0, price(22, 0)));
+			assert_ok!(report(3, 0, price(21, 0)));
+			assert_eq!(AggregatedPrice::<Test>::get().map(|(price, _)| price), Some(price(21, 0)));
+		});
+	}
+
+	#[test]
+	fn round_expires_without_enough_reports() {
+		new_test_ext().execute_with(|| {
+			// rounds without any report never expire
+			OcwDemo::on_initialize(10);
+			assert_eq!(CurrentRound::<Test>::get(), 0);
+
+			System::set_block_number(10);
+			assert_ok!(report(1, 0, price(22, 0)));
+			assert_eq!(RoundOpenedAt::<Test>::get(), Some(10));
+
+			OcwDemo::on_initialize(14);
+			assert_eq!(CurrentRound::<Test>::get(), 0);
+			assert_eq!(RoundReports::<Test>::get().len(), 1);
+
+			// the round expires `RoundTimeout` blocks after its first report
+			OcwDemo::on_initialize(15);
+			assert_eq!(CurrentRound::<Test>::get(), 1);
+			assert!(RoundReports::<Test>::get().is_empty());
+			assert_eq!(RoundOpenedAt::<Test>::get(), None);
+			assert_eq!(AggregatedPrice::<Test>::get(), None);
+			System::assert_last_event(Event::OcwDemo(crate::Event::RoundExpired(0, 1)));
+
+			// late reports for the expired round are stale
+			assert_noop!(report(2, 0, price(22, 0)), Error::<Test>::StaleRound);
+			assert_ok!(report(2, 1, price(22, 0)));
+		});
+	}
+
+	#[test]
+	fn stale_report_is_invalid_transaction() {
+		new_test_ext().execute_with(|| {
+			CurrentRound::<Test>::put(1);
+
+			let pair = Pair::from_seed(&[1; 32]);
+			OracleAuthorities::<Test>::insert(pair.public(), ());
+			let payload = PayloadPrice { round: 0, price: price(22, 0), public: pair.public() };
+			let signature = payload.using_encoded(|bytes| pair.sign(bytes));
+			let call = crate::Call::submit_price_unsigned_with_signed_payload { payload, signature };
+
+			assert_eq!(
+				OcwDemo::validate_unsigned(TransactionSource::External, &call),
+				InvalidTransaction::Stale.into(),
+			);
+		});
+	}
+
+	#[test]
+	fn set_price_sources_test() {
+		new_test_ext().execute_with(|| {
+			let sources = vec![(b"https://d.example".to_vec(), b"price".to_vec())];
+
+			// only the admin origin can manage the sources
+			assert_noop!(
+				OcwDemo::set_price_sources(Origin::signed(Public::from_raw([1; 32])), sources.clone()),
+				sp_runtime::DispatchError::BadOrigin
+			);
+
+			assert_ok!(OcwDemo::set_price_sources(Origin::root(), sources));
+			assert_eq!(OcwDemo::price_sources().len(), 1);
+
+			// bounded number of sources and source length
+			assert_noop!(
+				OcwDemo::set_price_sources(Origin::root(), vec![(b"u".to_vec(), b"p".to_vec()); 4]),
+				Error::<Test>::TooManyPriceSources
+			);
+			assert_noop!(
+				OcwDemo::set_price_sources(Origin::root(), vec![(vec![b'u'; 65], b"p".to_vec())]),
+				Error::<Test>::PriceSourceTooLong
+			);
+		});
+	}
+
+	#[test]
+	fn report_from_unknown_key_is_rejected() {
+		new_test_ext().execute_with(|| {
+			// key 4 is not an oracle authority
+			assert_noop!(report(4, 0, price(22, 0)), Error::<Test>::NotOracleAuthority);
+
+			let pair = Pair::from_seed(&[4; 32]);
+			let payload = PayloadPrice { round: 0, price: price(22, 0), public: pair.public() };
+			let signature = payload.using_encoded(|bytes| pair.sign(bytes));
+			let call = crate::Call::submit_price_unsigned_with_signed_payload { payload, signature };
+
+			assert_eq!(
+				OcwDemo::validate_unsigned(TransactionSource::External, &call),
+				InvalidTransaction::Custom(INVALID_ORACLE_AUTHORITY).into(),
+			);
+		});
+	}
+
+	#[test]
+	fn manage_oracle_authorities() {
+		new_test_ext().execute_with(|| {
+			// genesis authorities
+			assert!(OcwDemo::is_oracle_authority(&authority(1)));
+			assert!(!OcwDemo::is_oracle_authority(&authority(4)));
+
+			// only the admin origin can manage the authorities
+			assert_noop!(
+				OcwDemo::add_authority(Origin::signed(authority(1)), authority(4)),
+				sp_runtime::DispatchError::BadOrigin
+			);
+			assert_noop!(
+				OcwDemo::remove_authority(Origin::signed(authority(1)), authority(2)),
+				sp_runtime::DispatchError::BadOrigin
+			);
+
+			assert_ok!(OcwDemo::add_authority(Origin::root(), authority(4)));
+			assert_ok!(report(4, 0, price(22, 0)));
+			assert_noop!(
+				OcwDemo::add_authority(Origin::root(), authority(4)),
+				Error::<Test>::AlreadyOracleAuthority
+			);
+
+			assert_ok!(OcwDemo::remove_authority(Origin::root(), authority(2)));
+			assert_noop!(report(2, 0, price(22, 0)), Error::<Test>::NotOracleAuthority);
+			assert_noop!(
+				OcwDemo::remove_authority(Origin::root(), authority(2)),
+				Error::<Test>::NotOracleAuthority
+			);
+		});
+	}
+}
+
+mod scheduler {
+	use crate::{mock::*, OffchainJob};
+	use parity_scale_codec::Decode;
+	use frame_support::traits::Hooks;
+	use parking_lot::RwLock;
+	use sp_core::offchain::testing::PoolState;
+	use sp_runtime::offchain::{
+		storage_lock::{BlockAndTime, StorageLock},
+		Duration,
+	};
+
+	// Take the calls submitted to the transaction pool, in order.
+	fn take_calls(pool_state: &RwLock<PoolState>) -> Vec<crate::Call<Test>> {
+		pool_state.write().transactions.drain(..)
+			.map(|tx| match Extrinsic::decode(&mut &*tx).unwrap().call {
+				Call::OcwDemo(call) => call,
+				call => panic!("unexpected call: {:?}", call),
+			})
+			.collect()
+	}
+
+	#[test]
+	fn jobs_run_on_their_own_interval() {
+		let (mut t, offchain_state, pool_state, _) = new_offchain_test_ext();
+		t.execute_with(|| {
+			// never run jobs are due, unless disabled
+			assert!(OcwDemo::is_job_due(OffchainJob::Price, 1));
+			assert!(!OcwDemo::is_job_due(OffchainJob::GithubInfo, 1));
+
+			for job in OffchainJob::ALL.iter() {
+				OcwDemo::set_job_last_run(*job, 1);
+			}
+
+			// only the signed tx job is due at block 3
+			OcwDemo::offchain_worker(3);
+			assert!(matches!(
+				take_calls(&pool_state)[..],
+				[crate::Call::submit_number_signed { number: 3 }]
+			));
+
+			// then the unsigned tx job at block 4
+			OcwDemo::offchain_worker(4);
+			assert!(matches!(
+				take_calls(&pool_state)[..],
+				[crate::Call::submit_number_unsigned { number: 4 }]
+			));
+
+			// all the enabled jobs are due at block 7
+			{
+				let mut state = offchain_state.write();
+				expect_price_request(&mut state, SOURCE_A, br#"{"data":{"priceUsd":"22.500000"}}"#);
+				expect_price_request(&mut state, SOURCE_B, br#"{"USD":23.123456}"#);
+				expect_price_request(&mut state, SOURCE_C, br#"{"result":[{"last":"22.900000"}]}"#);
+			}
+			OcwDemo::offchain_worker(7);
+			assert!(matches!(
+				take_calls(&pool_state)[..],
+				[
+					crate::Call::submit_number_signed { number: 7 },
+					crate::Call::submit_number_unsigned { number: 7 },
+					crate::Call::submit_number_unsigned_with_signed_payload { .. },
+					crate::Call::submit_price_unsigned_with_signed_payload { .. },
+				]
+			));
+		});
+	}
+
+	#[test]
+	fn locked_job_is_skipped() {
+		let (mut t, _, pool_state, _) = new_offchain_test_ext();
+		t.execute_with(|| {
+			for job in OffchainJob::ALL.iter() {
+				OcwDemo::set_job_last_run(*job, 1);
+			}
+
+			// another worker is still running the signed tx job
+			let mut lock = StorageLock::<BlockAndTime<OcwDemo>>::with_block_and_time_deadline(
+				b"offchain-demo::job::signed-tx::lock", 3, Duration::from_millis(4000)
+			);
+			let _guard = lock.try_lock().ok().expect("the lock is free");
+
+			// the other due jobs still run
+			OcwDemo::offchain_worker(4);
+			assert!(matches!(
+				take_calls(&pool_state)[..],
+				[crate::Call::submit_number_unsigned { number: 4 }]
+			));
+
+			// and the skipped job is still due
+			assert!(OcwDemo::is_job_due(OffchainJob::SignedTx, 4));
+		});
+	}
 }
 
-type Price = (u64, Permill);
+mod history {
+	use crate::{mock::*, ring_buffer::RingBuffer, Numbers, PriceEntry, Prices};
+	use frame_support::{
+		assert_ok,
+		pallet_prelude::MaxEncodedLen,
+		storage::unhashed,
+		traits::{GetStorageVersion, OnRuntimeUpgrade, StorageVersion},
+	};
+	use sp_arithmetic::{FixedPointNumber, FixedU128};
+
+	fn push_price(block_number: u64, price: u64) {
+		Prices::<Test>::mutate(|prices| prices.push(PriceEntry {
+			price: FixedU128::saturating_from_integer(price),
+			block_number,
+			submitter: authority(1),
+		}));
+	}
+
+	#[test]
+	fn ring_buffer_keeps_latest_items() {
+		let mut buffer = RingBuffer::<u64, MaxHistory>::default();
+		assert!(buffer.is_empty());
+		assert_eq!(buffer.back(), None);
+
+		for number in 1..=2 {
+			buffer.push(number);
+		}
+		assert_eq!(buffer.to_vec(), vec![1, 2]);
+		assert_eq!(buffer.back(), Some(&2));
+
+		// the oldest items are overwritten once full
+		for number in 3..=5 {
+			buffer.push(number);
+		}
+		assert_eq!(buffer.len(), 3);
+		assert_eq!(buffer.to_vec(), vec![3, 4, 5]);
+		assert_eq!(buffer.back(), Some(&5));
+
+		// the length prefix, 3 numbers and the head
+		assert_eq!(RingBuffer::<u64, MaxHistory>::max_encoded_len(), 1 + 3 * 8 + 4);
+	}
+
+	#[test]
+	fn numbers_are_bounded_by_max_history() {
+		new_test_ext().execute_with(|| {
+			for number in 1..=4 {
+				assert_ok!(OcwDemo::submit_number_signed(Origin::signed(authority(1)), number));
+			}
+			assert_eq!(Numbers::<Test>::get().to_vec(), vec![2, 3, 4]);
+		});
+	}
+
+	#[test]
+	fn time_weighted_average_price() {
+		new_test_ext().execute_with(|| {
+			push_price(10, 10);
+			push_price(20, 20);
+			push_price(30, 40);
+			let twap = |from, to| OcwDemo::time_weighted_average_price(from, to);
+
+			// each price holds until the next one
+			assert_eq!(twap(10, 30), Some(FixedU128::saturating_from_integer(15)));
+			assert_eq!(twap(15, 40), Some(FixedU128::saturating_from_integer(26)));
+			assert_eq!(twap(5, 15), Some(FixedU128::saturating_from_integer(10)));
+			assert_eq!(twap(35, 40), Some(FixedU128::saturating_from_integer(40)));
+
+			// empty ranges and ranges before the first price
+			assert_eq!(twap(30, 30), None);
+			assert_eq!(twap(30, 20), None);
+			assert_eq!(twap(0, 10), None);
+
+			// the oldest price is dropped from the history
+			push_price(40, 80);
+			let history = OcwDemo::price_history();
+			assert_eq!(history.iter().map(|entry| entry.block_number).collect::<Vec<_>>(), vec![20, 30, 40]);
+			assert_eq!(twap(10, 30), Some(FixedU128::saturating_from_integer(20)));
+		});
+	}
+
+	#[test]
+	fn migrate_to_v1() {
+		new_test_ext().execute_with(|| {
+			// the unbounded version 0 history, encoded like the old `VecDeque`s
+			unhashed::put(&Numbers::<Test>::hashed_key(), &vec![1u64, 2, 3, 4, 5]);
+			unhashed::put(&Prices::<Test>::hashed_key(), &vec![FixedU128::saturating_from_integer(22u64)]);
+			StorageVersion::new(0).put::<OcwDemo>();
+
+			<OcwDemo as OnRuntimeUpgrade>::on_runtime_upgrade();
+			#[cfg(feature = "try-runtime")]
+			assert_ok!(<OcwDemo as OnRuntimeUpgrade>::post_upgrade());
 
+			// the latest numbers are kept, the prices without a block and submitter are dropped
+			assert_eq!(OcwDemo::on_chain_storage_version(), 1);
+			assert_eq!(Numbers::<Test>::get().to_vec(), vec![3, 4, 5]);
+			assert!(!Prices::<Test>::exists());
 
-fn format_price(data: Price) -> String {
-	let integer = data.0;
-	let decimal = data.1.deconstruct();
-	println!("Price parsing result: ({}, {})", integer, decimal);
+			// the history keeps working
+			assert_ok!(OcwDemo::submit_number_signed(Origin::signed(authority(1)), 6));
+			assert_eq!(Numbers::<Test>::get().to_vec(), vec![4, 5, 6]);
+			push_price(10, 10);
+			assert_eq!(OcwDemo::price_history().len(), 1);
 
-	return integer.to_string() + "." + &decimal.to_string();
+			// and the migration runs once
+			unhashed::put(&Numbers::<Test>::hashed_key(), &vec![7u64]);
+			<OcwDemo as OnRuntimeUpgrade>::on_runtime_upgrade();
+			assert!(Numbers::<Test>::try_get().is_err());
+		});
+	}
 }
@@ -0,0 +1,195 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Placeholder weights for pallet_ocw
+//!
+//! THESE WEIGHTS ARE NOT MEASURED. They are estimates written by hand from the storage accesses
+//! of each call, in the layout of the benchmark CLI output, and must be replaced by running:
+//!
+//! ./target/release/node-template benchmark --chain dev --execution wasm --wasm-execution compiled
+//!     --pallet pallet_ocw --extrinsic '*' --steps 20 --repeat 20
+//!     --output=./pallets/ocw/src/weights.rs
+
+#![cfg_attr(rustfmt, rustfmt_skip)]
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use frame_support::{traits::Get, weights::{Weight, constants::RocksDbWeight}};
+use sp_std::marker::PhantomData;
+
+/// Weight functions needed for pallet_ocw.
+pub trait WeightInfo {
+	fn submit_number_signed(h: u32, ) -> Weight;
+	fn submit_number_unsigned(h: u32, ) -> Weight;
+	fn submit_number_unsigned_with_signed_payload(h: u32, ) -> Weight;
+	fn submit_price_unsigned_with_signed_payload(r: u32, h: u32, ) -> Weight;
+	fn set_price_sources(s: u32, ) -> Weight;
+	fn add_authority() -> Weight;
+	fn remove_authority(r: u32, h: u32, ) -> Weight;
+	fn expire_round(r: u32, ) -> Weight;
+}
+
+/// Weights for pallet_ocw using the Substrate node and recommended hardware.
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+	// Storage: OcwDemo Numbers (r:1 w:1)
+	fn submit_number_signed(h: u32, ) -> Weight {
+		(17_436_000 as Weight)
+			.saturating_add((24_000 as Weight).saturating_mul(h as Weight))
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	// Storage: OcwDemo Numbers (r:1 w:1)
+	fn submit_number_unsigned(h: u32, ) -> Weight {
+		(16_902_000 as Weight)
+			.saturating_add((23_000 as Weight).saturating_mul(h as Weight))
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	// Storage: OcwDemo Numbers (r:1 w:1)
+	fn submit_number_unsigned_with_signed_payload(h: u32, ) -> Weight {
+		(17_215_000 as Weight)
+			.saturating_add((24_000 as Weight).saturating_mul(h as Weight))
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	// Storage: OcwDemo OracleAuthorities (r:1 w:0)
+	// Storage: OcwDemo CurrentRound (r:1 w:1)
+	// Storage: OcwDemo RoundReports (r:1 w:1)
+	// Storage: Timestamp Now (r:1 w:0)
+	// Storage: OcwDemo Prices (r:1 w:1)
+	// Storage: OcwDemo RoundOpenedAt (r:1 w:1)
+	// Storage: OcwDemo AggregatedPrice (r:0 w:1)
+	fn submit_price_unsigned_with_signed_payload(r: u32, h: u32, ) -> Weight {
+		(50_946_000 as Weight)
+			.saturating_add((512_000 as Weight).saturating_mul(r as Weight))
+			.saturating_add((31_000 as Weight).saturating_mul(h as Weight))
+			.saturating_add(T::DbWeight::get().reads(6 as Weight))
+			.saturating_add(T::DbWeight::get().writes(5 as Weight))
+	}
+	// Storage: OcwDemo PriceSources (r:0 w:1)
+	fn set_price_sources(s: u32, ) -> Weight {
+		(4_126_000 as Weight)
+			.saturating_add((1_382_000 as Weight).saturating_mul(s as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	// Storage: OcwDemo OracleAuthorities (r:1 w:1)
+	fn add_authority() -> Weight {
+		(21_384_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	// Storage: OcwDemo OracleAuthorities (r:1 w:1)
+	// Storage: OcwDemo AuthorityCount (r:1 w:1)
+	// Storage: OcwDemo RoundReports (r:1 w:1)
+	// Storage: OcwDemo CurrentRound (r:1 w:1)
+	// Storage: Timestamp Now (r:1 w:0)
+	// Storage: OcwDemo Prices (r:1 w:1)
+	// Storage: OcwDemo RoundOpenedAt (r:0 w:1)
+	// Storage: OcwDemo AggregatedPrice (r:0 w:1)
+	fn remove_authority(r: u32, h: u32, ) -> Weight {
+		(58_304_000 as Weight)
+			.saturating_add((530_000 as Weight).saturating_mul(r as Weight))
+			.saturating_add((31_000 as Weight).saturating_mul(h as Weight))
+			.saturating_add(T::DbWeight::get().reads(6 as Weight))
+			.saturating_add(T::DbWeight::get().writes(7 as Weight))
+	}
+	// Storage: OcwDemo RoundOpenedAt (r:1 w:1)
+	// Storage: OcwDemo CurrentRound (r:1 w:1)
+	// Storage: OcwDemo RoundReports (r:0 w:1)
+	fn expire_round(r: u32, ) -> Weight {
+		(9_112_000 as Weight)
+			.saturating_add((65_000 as Weight).saturating_mul(r as Weight))
+			.saturating_add(T::DbWeight::get().reads(2 as Weight))
+			.saturating_add(T::DbWeight::get().writes(3 as Weight))
+	}
+}
+
+// For backwards compatibility and tests
+impl WeightInfo for () {
+	// Storage: OcwDemo Numbers (r:1 w:1)
+	fn submit_number_signed(h: u32, ) -> Weight {
+		(17_436_000 as Weight)
+			.saturating_add((24_000 as Weight).saturating_mul(h as Weight))
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+	// Storage: OcwDemo Numbers (r:1 w:1)
+	fn submit_number_unsigned(h: u32, ) -> Weight {
+		(16_902_000 as Weight)
+			.saturating_add((23_000 as Weight).saturating_mul(h as Weight))
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+	// Storage: OcwDemo Numbers (r:1 w:1)
+	fn submit_number_unsigned_with_signed_payload(h: u32, ) -> Weight {
+		(17_215_000 as Weight)
+			.saturating_add((24_000 as Weight).saturating_mul(h as Weight))
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+	// Storage: OcwDemo OracleAuthorities (r:1 w:0)
+	// Storage: OcwDemo CurrentRound (r:1 w:1)
+	// Storage: OcwDemo RoundReports (r:1 w:1)
+	// Storage: Timestamp Now (r:1 w:0)
+	// Storage: OcwDemo Prices (r:1 w:1)
+	// Storage: OcwDemo RoundOpenedAt (r:1 w:1)
+	// Storage: OcwDemo AggregatedPrice (r:0 w:1)
+	fn submit_price_unsigned_with_signed_payload(r: u32, h: u32, ) -> Weight {
+		(50_946_000 as Weight)
+			.saturating_add((512_000 as Weight).saturating_mul(r as Weight))
+			.saturating_add((31_000 as Weight).saturating_mul(h as Weight))
+			.saturating_add(RocksDbWeight::get().reads(6 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(5 as Weight))
+	}
+	// Storage: OcwDemo PriceSources (r:0 w:1)
+	fn set_price_sources(s: u32, ) -> Weight {
+		(4_126_000 as Weight)
+			.saturating_add((1_382_000 as Weight).saturating_mul(s as Weight))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+	// Storage: OcwDemo OracleAuthorities (r:1 w:1)
+	fn add_authority() -> Weight {
+		(21_384_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+	// Storage: OcwDemo OracleAuthorities (r:1 w:1)
+	// Storage: OcwDemo AuthorityCount (r:1 w:1)
+	// Storage: OcwDemo RoundReports (r:1 w:1)
+	// Storage: OcwDemo CurrentRound (r:1 w:1)
+	// Storage: Timestamp Now (r:1 w:0)
+	// Storage: OcwDemo Prices (r:1 w:1)
+	// Storage: OcwDemo RoundOpenedAt (r:0 w:1)
+	// Storage: OcwDemo AggregatedPrice (r:0 w:1)
+	fn remove_authority(r: u32, h: u32, ) -> Weight {
+		(58_304_000 as Weight)
+			.saturating_add((530_000 as Weight).saturating_mul(r as Weight))
+			.saturating_add((31_000 as Weight).saturating_mul(h as Weight))
+			.saturating_add(RocksDbWeight::get().reads(6 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(7 as Weight))
+	}
+	// Storage: OcwDemo RoundOpenedAt (r:1 w:1)
+	// Storage: OcwDemo CurrentRound (r:1 w:1)
+	// Storage: OcwDemo RoundReports (r:0 w:1)
+	fn expire_round(r: u32, ) -> Weight {
+		(9_112_000 as Weight)
+			.saturating_add((65_000 as Weight).saturating_mul(r as Weight))
+			.saturating_add(RocksDbWeight::get().reads(2 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(3 as Weight))
+	}
+}
@@ -0,0 +1,66 @@
+//! Benchmarking setup for pallet-poe
+
+use super::*;
+
+use crate::Pallet as PoeModule;
+use frame_benchmarking::{account, benchmarks, whitelisted_caller};
+use frame_support::{sp_runtime::traits::Saturating, traits::Currency};
+use frame_system::RawOrigin;
+use sp_core::H256;
+use sp_std::{prelude::*, vec};
+
+// Fund `who` with enough to reserve the deposit of a claim with the longest metadata.
+fn fund<T: Config>(who: &T::AccountId) {
+	let deposit = T::DepositPerByte::get()
+		.saturating_mul(T::MaxMetadataLen::get().into())
+		.saturating_add(T::ClaimDeposit::get());
+	T::Currency::make_free_balance_be(who, T::Currency::minimum_balance().saturating_add(deposit));
+}
+
+// Metadata of `len` bytes, at most `MaxMetadataLen`.
+fn metadata<T: Config>(len: u32) -> Option<MetadataOf<T>> {
+	Some(vec![0u8; len as usize].try_into().expect("the length is bounded"))
+}
+
+// Have `owner` claim `proof` with `len` bytes of metadata.
+fn claim<T: Config>(owner: &T::AccountId, proof: H256, len: u32) -> Result<(), &'static str> {
+	fund::<T>(owner);
+	PoeModule::<T>::create_claim(RawOrigin::Signed(owner.clone()).into(), proof, metadata::<T>(len))
+		.map_err(Into::into)
+}
+
+benchmarks! {
+	create_claim {
+		let m in 0 .. T::MaxMetadataLen::get();
+		let caller: T::AccountId = whitelisted_caller();
+		fund::<T>(&caller);
+		let proof = H256::repeat_byte(1);
+	}: _(RawOrigin::Signed(caller.clone()), proof, metadata::<T>(m))
+	verify {
+		assert_eq!(Proofs::<T>::get(&proof).map(|claim| claim.owner), Some(caller));
+	}
+
+	revoke_claim {
+		let m in 0 .. T::MaxMetadataLen::get();
+		let caller: T::AccountId = whitelisted_caller();
+		let proof = H256::repeat_byte(1);
+		claim::<T>(&caller, proof, m)?;
+	}: _(RawOrigin::Signed(caller), proof)
+	verify {
+		assert!(!Proofs::<T>::contains_key(&proof));
+	}
+
+	transfer_claim {
+		let m in 0 .. T::MaxMetadataLen::get();
+		let caller: T::AccountId = whitelisted_caller();
+		// The destination has no account yet, which the deposit opens
+		let dest: T::AccountId = account("dest", 0, 0);
+		let proof = H256::repeat_byte(1);
+		claim::<T>(&caller, proof, m)?;
+	}: _(RawOrigin::Signed(caller), proof, dest.clone())
+	verify {
+		assert_eq!(Proofs::<T>::get(&proof).map(|claim| claim.owner), Some(dest));
+	}
+
+	impl_benchmark_test_suite!(PoeModule, crate::mock::new_test_ext(), crate::mock::Test);
+}
@@ -9,17 +9,66 @@ mod mock;
 #[cfg(test)]
 mod tests;
 
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+pub mod migrations;
+pub mod weights;
+
 #[frame_support::pallet]
 pub mod pallet {
-    use frame_support::pallet_prelude::*;
+    pub use crate::weights::WeightInfo;
+    use frame_support::{
+        pallet_prelude::*,
+        traits::{Currency, ExistenceRequirement, ReservableCurrency, StorageVersion},
+        transactional,
+    };
     use frame_system::pallet_prelude::*;
-    use sp_std::vec::Vec;
+    use sp_core::H256;
+    use sp_runtime::traits::{Saturating, Zero};
+
+    type BalanceOf<T> =
+        <<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+
+    /// The metadata attached to a claim, e.g. the filename, MIME type or URI of the document.
+    pub type MetadataOf<T> = BoundedVec<u8, <T as Config>::MaxMetadataLen>;
+
+    /// A claim on a document digest.
+    #[derive(Clone, Encode, Decode, PartialEq, RuntimeDebug, TypeInfo)]
+    #[scale_info(skip_type_params(T))]
+    pub struct Claim<T: Config> {
+        /// The account owning the claim.
+        pub owner: T::AccountId,
+        /// The block at which the claim was created or last transferred.
+        pub block_number: T::BlockNumber,
+        /// The amount reserved from the owner for storing the claim.
+        pub deposit: BalanceOf<T>,
+        /// Optional metadata describing the document.
+        pub metadata: Option<MetadataOf<T>>,
+    }
 
     /// Configure the pallet by specifying the parameters and types on which it depends.
     #[pallet::config]
     pub trait Config: frame_system::Config {
         /// Because this pallet emits events, it depends on the runtime's definition of an event.
         type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
+
+        /// The currency in which the claim deposits are reserved.
+        type Currency: ReservableCurrency<Self::AccountId>;
+
+        /// The base deposit reserved for every claim.
+        #[pallet::constant]
+        type ClaimDeposit: Get<BalanceOf<Self>>;
+
+        /// The additional deposit reserved for every byte of metadata.
+        #[pallet::constant]
+        type DepositPerByte: Get<BalanceOf<Self>>;
+
+        /// The maximum length of the metadata attached to a claim.
+        #[pallet::constant]
+        type MaxMetadataLen: Get<u32>;
+
+        /// Weight information for extrinsics in this pallet.
+        type WeightInfo: WeightInfo;
     }
     
     // Pallets use events to inform users when important changes are made.
@@ -28,12 +77,12 @@ pub mod pallet {
     #[pallet::event]
     #[pallet::generate_deposit(pub(super) fn deposit_event)]
     pub enum Event<T: Config> {
-        /// Event emitted when a proof has been claimed. [who, claim]
-        ClaimCreated(T::AccountId, Vec<u8>),
-        /// Event emitted when a claim is revoked by the owner. [who, claim]
-        ClaimRevoked(T::AccountId, Vec<u8>),
+        /// Event emitted when a proof has been claimed. [who, claim, deposit]
+        ClaimCreated(T::AccountId, H256, BalanceOf<T>),
+        /// Event emitted when a claim is revoked by the owner. [who, claim, refund]
+        ClaimRevoked(T::AccountId, H256, BalanceOf<T>),
         /// Event emitted when a claim is transfered by the owner. [who, towhom, claim]
-        ClaimTransfered(T::AccountId, T::AccountId, Vec<u8>),
+        ClaimTransfered(T::AccountId, T::AccountId, H256),
     }
 
     #[pallet::error]
@@ -44,58 +93,86 @@ pub mod pallet {
         NoSuchProof,
         /// The proof is claimed by another account, so caller can't revoke it.
         NotProofOwner,
-        /// The length of the proof exceed the predefined limit.
-        ExceedProofLengthLimit,
+        /// The deposit of the claim is no longer fully reserved from the owner.
+        DepositNotReserved,
     }
 
+    /// The current storage version.
+    const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
+
     #[pallet::pallet]
     #[pallet::generate_store(pub(super) trait Store)]
+    #[pallet::storage_version(STORAGE_VERSION)]
     /// #[pallet::generate_storage_info]
     pub struct Pallet<T>(_);
 
     #[pallet::storage]
-    pub(super) type Proofs<T: Config> = StorageMap<_, Blake2_128Concat, Vec<u8>, (T::AccountId, T::BlockNumber), ValueQuery>;
+    #[pallet::getter(fn proofs)]
+    pub(super) type Proofs<T: Config> = StorageMap<_, Blake2_128Concat, H256, Claim<T>>;
 
     #[pallet::hooks]
-    impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {}
+    impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+        fn on_runtime_upgrade() -> Weight {
+            crate::migrations::v1::migrate::<T>()
+        }
+
+        #[cfg(feature = "try-runtime")]
+        fn pre_upgrade() -> Result<(), &'static str> {
+            crate::migrations::v1::pre_upgrade::<T>()
+        }
+
+        #[cfg(feature = "try-runtime")]
+        fn post_upgrade() -> Result<(), &'static str> {
+            crate::migrations::v1::post_upgrade::<T>()
+        }
+    }
 
     // Dispatchable functions allow users to interact with the pallet and invoke state changes.
     // These functions materialize as "extrinsics", which are often compared to transactions.
     // Dispatchable functions must be annotated with a weight and must return a DispatchResult.
     #[pallet::call]
     impl<T: Config> Pallet<T> {
-        #[pallet::weight(1_000)]
+        #[pallet::weight(T::WeightInfo::create_claim(
+            metadata.as_ref().map_or(0, |m| m.len() as u32)
+        ))]
         pub fn create_claim(
             origin: OriginFor<T>,
-            proof: Vec<u8>,
+            proof: H256,
+            metadata: Option<MetadataOf<T>>,
         ) -> DispatchResult {
             // Check that the extrinsic was signed and get the signer.
             // This function will return an error if the extrinsic is not signed.
             // https://docs.substrate.io/v3/runtime/origins
             let sender = ensure_signed(origin)?;
 
-            // Verify that the length of the proof is less than 32 bits
-            ensure!(proof.len() <= 5, Error::<T>::ExceedProofLengthLimit);
-
             // Verify that the specified proof has not already been claimed.
             ensure!(!Proofs::<T>::contains_key(&proof), Error::<T>::ProofAlreadyClaimed);
 
+            // Reserve the storage deposit from the sender.
+            let deposit = Self::deposit_for(&metadata);
+            T::Currency::reserve(&sender, deposit)?;
+
             // Get the block number from the FRAME System pallet.
             let current_block = <frame_system::Pallet<T>>::block_number();
 
             // Store the proof with the sender and block number.
-            Proofs::<T>::insert(&proof, (&sender, current_block));
+            Proofs::<T>::insert(&proof, Claim {
+                owner: sender.clone(),
+                block_number: current_block,
+                deposit,
+                metadata,
+            });
 
             // Emit an event that the claim was created.
-            Self::deposit_event(Event::ClaimCreated(sender, proof));
+            Self::deposit_event(Event::ClaimCreated(sender, proof, deposit));
 
             Ok(())
         }
 
-        #[pallet::weight(10_000)]
+        #[pallet::weight(T::WeightInfo::revoke_claim(T::MaxMetadataLen::get()))]
         pub fn revoke_claim(
             origin: OriginFor<T>,
-            proof: Vec<u8>,
+            proof: H256,
         ) -> DispatchResult {
             // Check that the extrinsic was signed and get the signer.
             // This function will return an error if the extrinsic is not signed.
@@ -103,46 +180,64 @@ pub mod pallet {
             let sender = ensure_signed(origin)?;
 
             // Verify that the specified proof has been claimed.
-            ensure!(Proofs::<T>::contains_key(&proof), Error::<T>::NoSuchProof);
-
-            // Get owner of the claim.
-            let (owner, _) = Proofs::<T>::get(&proof);
+            let claim = Proofs::<T>::get(&proof).ok_or(Error::<T>::NoSuchProof)?;
 
             // Verify that sender of the current call is the claim owner.
-            ensure!(sender == owner, Error::<T>::NotProofOwner);
+            ensure!(sender == claim.owner, Error::<T>::NotProofOwner);
 
             // Remove claim from storage.
             Proofs::<T>::remove(&proof);
 
+            // Refund the storage deposit to the owner.
+            T::Currency::unreserve(&sender, claim.deposit);
+
             // Emit an event that the claim was erased.
-            Self::deposit_event(Event::ClaimRevoked(sender, proof));
+            Self::deposit_event(Event::ClaimRevoked(sender, proof, claim.deposit));
             Ok(())
         }
 
-        #[pallet::weight(10_000)]
+        #[pallet::weight(T::WeightInfo::transfer_claim(T::MaxMetadataLen::get()))]
+        #[transactional]
         pub fn transfer_claim(
             origin: OriginFor<T>,
-            proof: Vec<u8>,
+            proof: H256,
             dest: T::AccountId
         ) -> DispatchResult {
             // Check that the extrinsic was signed and get the signer.
             let sender = ensure_signed(origin)?;
 
             // Verify that the specified proof has been claimed.
-            ensure!(Proofs::<T>::contains_key(&proof), Error::<T>::NoSuchProof);
-
-            // Get owner of the claim.
-            let (owner, _) = Proofs::<T>::get(&proof);
+            let mut claim = Proofs::<T>::get(&proof).ok_or(Error::<T>::NoSuchProof)?;
 
             // Verify that sender of the current call is the claim owner.
-            ensure!(sender == owner, Error::<T>::NotProofOwner);
+            ensure!(sender == claim.owner, Error::<T>::NotProofOwner);
+
+            // The storage deposit moves along with the claim, so that the new owner
+            // is refunded when revoking it. It goes through the free balance, which opens
+            // the account of the destination if it does not exist yet.
+            let unreserved = T::Currency::unreserve(&sender, claim.deposit);
+            ensure!(unreserved.is_zero(), Error::<T>::DepositNotReserved);
+            T::Currency::transfer(&sender, &dest, claim.deposit, ExistenceRequirement::AllowDeath)?;
+            T::Currency::reserve(&dest, claim.deposit)?;
 
             // Transfer the claim from current owner to destination account.
-            Proofs::<T>::insert(&proof, (&dest, <frame_system::Pallet<T>>::block_number()));
-            
+            claim.owner = dest.clone();
+            claim.block_number = <frame_system::Pallet<T>>::block_number();
+            Proofs::<T>::insert(&proof, claim);
+
             // Emit an event that the claim was transfered.
             Self::deposit_event(Event::ClaimTransfered(sender, dest, proof));
             Ok(())
         }        
     }
+
+    impl<T: Config> Pallet<T> {
+        /// The storage deposit of a claim: a base deposit plus a deposit per byte of metadata.
+        pub fn deposit_for(metadata: &Option<MetadataOf<T>>) -> BalanceOf<T> {
+            let len = metadata.as_ref().map_or(0, |m| m.len() as u32);
+            T::DepositPerByte::get()
+                .saturating_mul(len.into())
+                .saturating_add(T::ClaimDeposit::get())
+        }
+    }
 }
\ No newline at end of file
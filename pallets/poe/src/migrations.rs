@@ -0,0 +1,92 @@
+//! Storage migrations for pallet-poe.
+
+/// Migration to storage version 1: `Proofs` moves from raw proofs of up to 5 bytes, claimed
+/// without a deposit, to `H256` digests with a deposit and optional metadata.
+pub mod v1 {
+	use crate::pallet::{Claim, Config, Pallet, Proofs};
+	use frame_support::{
+		storage::migration,
+		traits::{GetStorageVersion, PalletInfoAccess, StorageVersion},
+		weights::Weight,
+		Blake2_128Concat,
+	};
+	use sp_core::H256;
+	use sp_runtime::traits::Zero;
+	use sp_std::prelude::*;
+
+	#[cfg(feature = "try-runtime")]
+	use frame_support::traits::OnRuntimeUpgradeHelpersExt;
+
+	/// The name of the `Proofs` storage, in both layouts.
+	const PROOFS: &[u8] = b"Proofs";
+
+	/// The digest a version 0 proof is claimed under from version 1 on.
+	pub fn proof_digest(proof: &[u8]) -> H256 {
+		H256(sp_io::hashing::blake2_256(proof))
+	}
+
+	// Take every claim out of the version 0 `Proofs`.
+	fn drain_old_proofs<T: Config>() -> Vec<(Vec<u8>, (T::AccountId, T::BlockNumber))> {
+		migration::storage_key_iter::<Vec<u8>, (T::AccountId, T::BlockNumber), Blake2_128Concat>(
+			Pallet::<T>::name().as_bytes(),
+			PROOFS,
+		)
+		.drain()
+		.collect()
+	}
+
+	/// Move every claim under the digest of its proof. No deposit was reserved for these claims,
+	/// so they keep a zero deposit and revoking them refunds nothing.
+	pub fn migrate<T: Config>() -> Weight {
+		if Pallet::<T>::on_chain_storage_version() != 0 {
+			return T::DbWeight::get().reads(1);
+		}
+
+		// Drain everything before writing, as both layouts live under the same prefix
+		let old_proofs = drain_old_proofs::<T>();
+
+		let claims = old_proofs.len() as Weight;
+		for (proof, (owner, block_number)) in old_proofs {
+			Proofs::<T>::insert(proof_digest(&proof), Claim::<T> {
+				owner,
+				block_number,
+				deposit: Zero::zero(),
+				metadata: None,
+			});
+		}
+
+		StorageVersion::new(1).put::<Pallet<T>>();
+
+		T::DbWeight::get().reads_writes(claims + 1, 2 * claims + 1)
+	}
+
+	/// Record the number of claims to move.
+	#[cfg(feature = "try-runtime")]
+	pub fn pre_upgrade<T: Config>() -> Result<(), &'static str> {
+		if Pallet::<T>::on_chain_storage_version() < 1 {
+			let count = migration::storage_key_iter::<Vec<u8>, (T::AccountId, T::BlockNumber), Blake2_128Concat>(
+				Pallet::<T>::name().as_bytes(),
+				PROOFS,
+			)
+			.count() as u32;
+			Pallet::<T>::set_temp_storage(count, "v1::proofs");
+		}
+		Ok(())
+	}
+
+	/// Check every claim was moved.
+	#[cfg(feature = "try-runtime")]
+	pub fn post_upgrade<T: Config>() -> Result<(), &'static str> {
+		frame_support::ensure!(
+			Pallet::<T>::on_chain_storage_version() >= 1,
+			"poe: storage version not upgraded to 1"
+		);
+		if let Some(count) = Pallet::<T>::get_temp_storage::<u32>("v1::proofs") {
+			frame_support::ensure!(
+				Proofs::<T>::iter_keys().count() as u32 == count,
+				"poe: claims lost in the v1 migration"
+			);
+		}
+		Ok(())
+	}
+}
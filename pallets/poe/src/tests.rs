@@ -1,32 +1,73 @@
-use crate::{mock::*, Error};
-use frame_support::{assert_noop, assert_ok};
+use crate::{migrations, mock::*, Error};
+use codec::{Decode, Encode};
+use frame_support::{
+    assert_noop, assert_ok,
+    storage::migration,
+    traits::{GetStorageVersion, OnRuntimeUpgrade, StorageVersion},
+    Blake2_128Concat, StorageHasher,
+};
+use sp_core::H256;
 use super::*;
 
+// Metadata from a byte string, within `MaxMetadataLen`.
+fn metadata(bytes: &[u8]) -> Option<MetadataOf<Test>> {
+    Some(bytes.to_vec().try_into().expect("the metadata fits"))
+}
+
 #[test]
 fn create_claim_test() {
     new_test_ext().execute_with(|| {
 		// Dispatch a claim extrinsic from account 1.
-        let proof = vec![0, 1];
-		assert_ok!(PoeModule::create_claim(Origin::signed(1), proof.clone()));
+        let proof = H256::repeat_byte(1);
+		assert_ok!(PoeModule::create_claim(Origin::signed(1), proof, None));
 
         // Read pallet storage and assert an expected result.
-		assert_eq!(
-            Proofs::<Test>::get(&proof),
-            (1, frame_system::Pallet::<Test>::block_number())
-        );
+        let claim = Proofs::<Test>::get(&proof).expect("claim is stored");
+		assert_eq!(claim.owner, 1);
+		assert_eq!(claim.block_number, frame_system::Pallet::<Test>::block_number());
+		assert_eq!(claim.metadata, None);
+
+        // The base deposit is reserved.
+		assert_eq!(claim.deposit, 10);
+		assert_eq!(Balances::reserved_balance(1), 10);
 	});
 }
 
 #[test]
-fn create_claim_exceed_length_limit() {
+fn create_claim_with_metadata_test() {
     new_test_ext().execute_with(|| {
-		// Dispatch a claim extrinsic from account 1 that exceeds 32 bits.
-        let proof_exceed = vec![0, 1, 1, 0, 1, 0];
+		// Dispatch a claim extrinsic with metadata from account 1.
+        let proof = H256::repeat_byte(1);
+		assert_ok!(PoeModule::create_claim(Origin::signed(1), proof, metadata(b"report.pdf")));
+
+        // The deposit covers the metadata bytes.
+        let claim = Proofs::<Test>::get(&proof).expect("claim is stored");
+		assert_eq!(claim.metadata, metadata(b"report.pdf"));
+		assert_eq!(claim.deposit, 20);
+		assert_eq!(Balances::reserved_balance(1), 20);
+	});
+}
 
-        // Execute create claim and assert the ExceedProofLengthLimit error
+#[test]
+fn create_claim_exceed_metadata_limit() {
+    new_test_ext().execute_with(|| {
+		// Metadata longer than 16 bytes does not even decode as a call parameter.
+        let encoded = Some(vec![0u8; 17]).encode();
+        assert!(Option::<MetadataOf<Test>>::decode(&mut &encoded[..]).is_err());
+
+        let encoded = Some(vec![0u8; 16]).encode();
+        assert!(Option::<MetadataOf<Test>>::decode(&mut &encoded[..]).is_ok());
+	});
+}
+
+#[test]
+fn create_claim_not_enough_deposit() {
+    new_test_ext().execute_with(|| {
+		// Account 3 cannot afford the deposit.
+        let proof = H256::repeat_byte(1);
         assert_noop!(
-            PoeModule::create_claim(Origin::signed(1), proof_exceed.clone()),
-            Error::<Test, Test>::ExceedProofLengthLimit
+            PoeModule::create_claim(Origin::signed(3), proof, None),
+            pallet_balances::Error::<Test>::InsufficientBalance
         );
 	});
 }
@@ -35,12 +76,12 @@ fn create_claim_exceed_length_limit() {
 fn create_claim_fail_when_claim_exist() {
     new_test_ext().execute_with(|| {
 		// Dispatch a claim extrinsic from account 1.
-        let proof = vec![0, 1];
-		let _ = PoeModule::create_claim(Origin::signed(1), proof.clone());
+        let proof = H256::repeat_byte(1);
+		let _ = PoeModule::create_claim(Origin::signed(1), proof, None);
 
         // Execute create claim and assert the ProofAlreadyClaimed error
 		assert_noop!(
-            PoeModule::create_claim(Origin::signed(1), proof.clone()),
+            PoeModule::create_claim(Origin::signed(1), proof, None),
             Error::<Test>::ProofAlreadyClaimed
         );
 	});
@@ -50,14 +91,19 @@ fn create_claim_fail_when_claim_exist() {
 fn revoke_claim_test() {
     new_test_ext().execute_with(|| {
 		// Dispatch a claim extrinsic from account 1.
-        let proof = vec![0, 1];
-		let _ = PoeModule::create_claim(Origin::signed(1), proof.clone());
+        let proof = H256::repeat_byte(1);
+		let _ = PoeModule::create_claim(Origin::signed(1), proof, metadata(b"a.txt"));
+		assert_eq!(Balances::reserved_balance(1), 15);
 
         // Excute revoke claim and assert no errors
-        assert_ok!(PoeModule::revoke_claim(Origin::signed(1), proof.clone()));
+        assert_ok!(PoeModule::revoke_claim(Origin::signed(1), proof));
 
         // Read pallet storage and assert an expected result
-		assert_eq!(Proofs::<Test>::get(&proof), (0, 0));
+		assert_eq!(Proofs::<Test>::get(&proof), None);
+
+        // The deposit is refunded
+		assert_eq!(Balances::reserved_balance(1), 0);
+		assert_eq!(Balances::free_balance(1), 100);
 	});
 }
 
@@ -65,11 +111,11 @@ fn revoke_claim_test() {
 fn revoke_claim_not_exist() {
     new_test_ext().execute_with(|| {
 		// Dispatch a claim extrinsic from account 1.
-        let proof = vec![0, 1];
+        let proof = H256::repeat_byte(1);
 
         // Execute revoke claim and assert the NoSuchProof error
         assert_noop!(
-            PoeModule::revoke_claim(Origin::signed(1), proof.clone()),
+            PoeModule::revoke_claim(Origin::signed(1), proof),
             Error::<Test>::NoSuchProof
         );
 	});
@@ -79,12 +125,12 @@ fn revoke_claim_not_exist() {
 fn revoke_claim_not_owner() {
     new_test_ext().execute_with(|| {
 		// Dispatch a claim extrinsic from account 1.
-        let proof = vec![0, 1];
-        let _ = PoeModule::create_claim(Origin::signed(1), proof.clone());
+        let proof = H256::repeat_byte(1);
+        let _ = PoeModule::create_claim(Origin::signed(1), proof, None);
 
         // Execute revoke claim and assert the NotProofOwner error
         assert_noop!(
-            PoeModule::revoke_claim(Origin::signed(2), proof.clone()),
+            PoeModule::revoke_claim(Origin::signed(2), proof),
             Error::<Test>::NotProofOwner
         );
 	});
@@ -94,31 +140,59 @@ fn revoke_claim_not_owner() {
 fn transfer_claim_test() {
     new_test_ext().execute_with(|| {
 		// Dispatch a claim extrinsic from account 1.
-        let proof = vec![0, 1];
-		let _ = PoeModule::create_claim(Origin::signed(1), proof.clone());
+        let proof = H256::repeat_byte(1);
+		let _ = PoeModule::create_claim(Origin::signed(1), proof, None);
 
         // Execute transfer claim and assert no errors
         let receiver = 2;
-        assert_ok!(PoeModule::transfer_claim(Origin::signed(1), proof.clone(), receiver));
+        assert_ok!(PoeModule::transfer_claim(Origin::signed(1), proof, receiver));
 
         // Read pallet storage and assert the owner is changed to the receiver
-        let (owner, _) = Proofs::<Test>::get(&proof);
-		assert_eq!(owner, receiver);
+        let claim = Proofs::<Test>::get(&proof).expect("claim is stored");
+		assert_eq!(claim.owner, receiver);
+
+        // The deposit moves along with the claim
+		assert_eq!(Balances::reserved_balance(1), 0);
+		assert_eq!(Balances::reserved_balance(receiver), 10);
+
+        // The receiver is refunded when revoking the claim
+        assert_ok!(PoeModule::revoke_claim(Origin::signed(receiver), proof));
+		assert_eq!(Balances::free_balance(receiver), 110);
 	});
 }
 
+#[test]
+fn transfer_claim_to_new_account() {
+    new_test_ext().execute_with(|| {
+        let proof = H256::repeat_byte(1);
+        assert_ok!(PoeModule::create_claim(Origin::signed(1), proof, None));
+
+        // Account 4 has no balance, the deposit opens its account
+        assert_eq!(Balances::free_balance(4), 0);
+        assert_ok!(PoeModule::transfer_claim(Origin::signed(1), proof, 4));
+        assert_eq!(Proofs::<Test>::get(&proof).map(|claim| claim.owner), Some(4));
+        assert_eq!(Balances::reserved_balance(1), 0);
+        assert_eq!(Balances::free_balance(1), 90);
+        assert_eq!(Balances::reserved_balance(4), 10);
+        assert_eq!(Balances::free_balance(4), 0);
+
+        assert_ok!(PoeModule::revoke_claim(Origin::signed(4), proof));
+        assert_eq!(Balances::free_balance(4), 10);
+    });
+}
+
 #[test]
 fn transfer_claim_not_exist() {
     new_test_ext().execute_with(|| {
 		// Dispatch a claim extrinsic from account 1.
-        let proof = vec![0, 1];
-        let proof2 = vec![0, 2];
-		let _ = PoeModule::create_claim(Origin::signed(1), proof.clone());
+        let proof = H256::repeat_byte(1);
+        let proof2 = H256::repeat_byte(2);
+		let _ = PoeModule::create_claim(Origin::signed(1), proof, None);
 
         let receiver = 2;
         // Transfer the claim ownership and assert the NoSuchProof error.
 		assert_noop!(
-            PoeModule::transfer_claim(Origin::signed(1), proof2.clone(), receiver),
+            PoeModule::transfer_claim(Origin::signed(1), proof2, receiver),
             Error::<Test>::NoSuchProof
         );
 	});
@@ -128,14 +202,52 @@ fn transfer_claim_not_exist() {
 fn transfer_claim_not_owner() {
     new_test_ext().execute_with(|| {
 		// Dispatch a claim extrinsic from account 1.
-        let proof = vec![0, 1];
-		let _ = PoeModule::create_claim(Origin::signed(1), proof.clone());
+        let proof = H256::repeat_byte(1);
+		let _ = PoeModule::create_claim(Origin::signed(1), proof, None);
 
         let receiver = 2;
         // Transger the claim ownership and assert the NotProofOwner error.
 		assert_noop!(
-            PoeModule::transfer_claim(Origin::signed(3), proof.clone(), receiver),
+            PoeModule::transfer_claim(Origin::signed(3), proof, receiver),
             Error::<Test>::NotProofOwner
         );
 	});
 }
+
+#[test]
+fn migrate_to_v1() {
+    new_test_ext().execute_with(|| {
+        // Account 1 claimed a raw proof before claims were digests with a deposit
+        let old_key = Blake2_128Concat::hash(&b"hello".to_vec().encode());
+        migration::put_storage_value(b"PoeModule", b"Proofs", &old_key, (1u64, 3u64));
+        StorageVersion::new(0).put::<PoeModule>();
+
+        #[cfg(feature = "try-runtime")]
+        assert_ok!(<PoeModule as OnRuntimeUpgrade>::pre_upgrade());
+        <PoeModule as OnRuntimeUpgrade>::on_runtime_upgrade();
+        #[cfg(feature = "try-runtime")]
+        assert_ok!(<PoeModule as OnRuntimeUpgrade>::post_upgrade());
+
+        // The claim moves under the digest of the proof, without a deposit
+        assert_eq!(PoeModule::on_chain_storage_version(), 1);
+        assert_eq!(
+            migration::get_storage_value::<(u64, u64)>(b"PoeModule", b"Proofs", &old_key),
+            None
+        );
+        let proof = migrations::v1::proof_digest(b"hello");
+        let claim = Proofs::<Test>::get(&proof).expect("claim is migrated");
+        assert_eq!(claim.owner, 1);
+        assert_eq!(claim.block_number, 3);
+        assert_eq!(claim.deposit, 0);
+        assert_eq!(claim.metadata, None);
+
+        // The claim keeps working, and revoking it refunds nothing
+        assert_ok!(PoeModule::revoke_claim(Origin::signed(1), proof));
+        assert_eq!(Balances::free_balance(1), 100);
+
+        // And the migration runs once
+        migration::put_storage_value(b"PoeModule", b"Proofs", &old_key, (1u64, 3u64));
+        <PoeModule as OnRuntimeUpgrade>::on_runtime_upgrade();
+        assert_eq!(Proofs::<Test>::get(&proof), None);
+    });
+}
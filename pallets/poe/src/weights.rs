@@ -0,0 +1,94 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Placeholder weights for pallet_poe
+//!
+//! THESE WEIGHTS ARE NOT MEASURED. They are estimates written by hand from the storage accesses
+//! of each call, in the layout of the benchmark CLI output, and must be replaced by running:
+//!
+//! ./target/release/node-template benchmark --chain dev --execution wasm --wasm-execution compiled
+//!     --pallet pallet_poe --extrinsic '*' --steps 20 --repeat 20
+//!     --output=./pallets/poe/src/weights.rs
+
+#![cfg_attr(rustfmt, rustfmt_skip)]
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use frame_support::{traits::Get, weights::{Weight, constants::RocksDbWeight}};
+use sp_std::marker::PhantomData;
+
+/// Weight functions needed for pallet_poe.
+pub trait WeightInfo {
+	fn create_claim(m: u32, ) -> Weight;
+	fn revoke_claim(m: u32, ) -> Weight;
+	fn transfer_claim(m: u32, ) -> Weight;
+}
+
+/// Weights for pallet_poe using the Substrate node and recommended hardware.
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+	// Storage: PoeModule Proofs (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	fn create_claim(m: u32, ) -> Weight {
+		(41_870_000 as Weight)
+			.saturating_add((9_000 as Weight).saturating_mul(m as Weight))
+			.saturating_add(T::DbWeight::get().reads(2 as Weight))
+			.saturating_add(T::DbWeight::get().writes(2 as Weight))
+	}
+	// Storage: PoeModule Proofs (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	fn revoke_claim(_m: u32, ) -> Weight {
+		(39_562_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(2 as Weight))
+			.saturating_add(T::DbWeight::get().writes(2 as Weight))
+	}
+	// Storage: PoeModule Proofs (r:1 w:1)
+	// Storage: System Account (r:2 w:2)
+	fn transfer_claim(m: u32, ) -> Weight {
+		(58_094_000 as Weight)
+			.saturating_add((5_000 as Weight).saturating_mul(m as Weight))
+			.saturating_add(T::DbWeight::get().reads(3 as Weight))
+			.saturating_add(T::DbWeight::get().writes(3 as Weight))
+	}
+}
+
+// For backwards compatibility and tests
+impl WeightInfo for () {
+	// Storage: PoeModule Proofs (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	fn create_claim(m: u32, ) -> Weight {
+		(41_870_000 as Weight)
+			.saturating_add((9_000 as Weight).saturating_mul(m as Weight))
+			.saturating_add(RocksDbWeight::get().reads(2 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(2 as Weight))
+	}
+	// Storage: PoeModule Proofs (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	fn revoke_claim(_m: u32, ) -> Weight {
+		(39_562_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(2 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(2 as Weight))
+	}
+	// Storage: PoeModule Proofs (r:1 w:1)
+	// Storage: System Account (r:2 w:2)
+	fn transfer_claim(m: u32, ) -> Weight {
+		(58_094_000 as Weight)
+			.saturating_add((5_000 as Weight).saturating_mul(m as Weight))
+			.saturating_add(RocksDbWeight::get().reads(3 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(3 as Weight))
+	}
+}
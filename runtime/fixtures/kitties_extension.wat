@@ -0,0 +1,38 @@
+;; Pass the input of the call through to the kitties chain extension and return its output.
+;;
+;; The input is the id of the extension function as a little endian `u32`, followed by the
+;; SCALE encoded arguments of the function.
+(module
+	(import "seal0" "seal_call_chain_extension"
+		(func $seal_call_chain_extension (param i32 i32 i32 i32 i32) (result i32))
+	)
+	(import "seal0" "seal_input" (func $seal_input (param i32 i32)))
+	(import "seal0" "seal_return" (func $seal_return (param i32 i32 i32)))
+	(import "env" "memory" (memory 1 1))
+
+	;; [0, 4) length of the input buffer
+	(data (i32.const 0) "\00\01")
+
+	;; [4, 260) input buffer: the function id, then its arguments
+
+	;; [260, 264) length of the output buffer
+	(data (i32.const 260) "\00\01")
+
+	;; [264, 520) output buffer
+
+	(func (export "deploy"))
+
+	(func (export "call")
+		(call $seal_input (i32.const 4) (i32.const 0))
+
+		(drop (call $seal_call_chain_extension
+			(i32.load (i32.const 4))	;; func_id
+			(i32.const 8)	;; input_ptr
+			(i32.sub (i32.load (i32.const 0)) (i32.const 4))	;; input_len
+			(i32.const 264)	;; output_ptr
+			(i32.const 260)	;; output_len_ptr
+		))
+
+		(call $seal_return (i32.const 0) (i32.const 264) (i32.load (i32.const 260)))
+	)
+)
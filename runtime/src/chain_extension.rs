@@ -0,0 +1,83 @@
+//! A chain extension giving contracts access to the kitties and the proofs of existence.
+//!
+//! A contract calls a function of the extension by its id in `func_id`, with the SCALE encoded
+//! arguments as input. The functions reading storage write back their SCALE encoded result.
+
+use crate::{AccountId, BlockNumber, MaxOffersPerKitty, Origin, PoeModule, Runtime, SubstrateKitties};
+use codec::Encode;
+use frame_support::{traits::Get, weights::Weight};
+use pallet_contracts::chain_extension::{
+	ChainExtension, Environment, Ext, InitState, RetVal, SysConfig, UncheckedFrom,
+};
+use pallet_kitties::weights::WeightInfo;
+use sp_core::H256;
+use sp_runtime::DispatchError;
+
+/// The ids of the functions of `KittiesExtension`.
+pub mod func_id {
+	/// `(kitty_id: H256) -> Option<AccountId>`: the owner of a kitty.
+	pub const KITTY_OWNER: u32 = 1;
+	/// `(kitty_id: H256) -> Option<[u8; 16]>`: the dna of a kitty.
+	pub const KITTY_DNA: u32 = 2;
+	/// `(kitty_id: H256, to: AccountId)`: transfer a kitty owned by the calling contract.
+	pub const TRANSFER_KITTY: u32 = 3;
+	/// `(proof: H256) -> Option<(AccountId, BlockNumber)>`: the owner of a proof of existence
+	/// and the block it was claimed or last transferred at.
+	pub const POE_CLAIM: u32 = 4;
+}
+
+/// The chain extension of the runtime, see `func_id` for its functions.
+pub struct KittiesExtension;
+
+// The weight of reading a single storage item.
+fn read_weight() -> Weight {
+	<Runtime as frame_system::Config>::DbWeight::get().reads(1)
+}
+
+impl ChainExtension<Runtime> for KittiesExtension {
+	fn call<E>(func_id: u32, env: Environment<E, InitState>) -> Result<RetVal, DispatchError>
+	where
+		E: Ext<T = Runtime>,
+		<E::T as SysConfig>::AccountId: UncheckedFrom<<E::T as SysConfig>::Hash> + AsRef<[u8]>,
+	{
+		let mut env = env.buf_in_buf_out();
+		match func_id {
+			func_id::KITTY_OWNER => {
+				env.charge_weight(read_weight())?;
+				let kitty_id: H256 = env.read_as()?;
+				let owner: Option<AccountId> = SubstrateKitties::kitties(kitty_id).map(|kitty| kitty.owner);
+				env.write(&owner.encode(), false, None)?;
+			},
+			func_id::KITTY_DNA => {
+				env.charge_weight(read_weight())?;
+				let kitty_id: H256 = env.read_as()?;
+				let dna = SubstrateKitties::kitties(kitty_id).map(|kitty| kitty.dna);
+				env.write(&dna.encode(), false, None)?;
+			},
+			func_id::TRANSFER_KITTY => {
+				// The kitty changing hands releases its offers, as charged by the call
+				env.charge_weight(
+					<Runtime as pallet_kitties::Config>::WeightInfo::transfer().saturating_add(
+						<Runtime as pallet_kitties::Config>::WeightInfo::release_offers(MaxOffersPerKitty::get()),
+					),
+				)?;
+				let (kitty_id, to): (H256, AccountId) = env.read_as()?;
+				// The contract can only give away its own kitties, as checked by the call
+				let contract = env.ext().address().clone();
+				SubstrateKitties::transfer(Origin::signed(contract), kitty_id, to)?;
+			},
+			func_id::POE_CLAIM => {
+				env.charge_weight(read_weight())?;
+				let proof: H256 = env.read_as()?;
+				let claim: Option<(AccountId, BlockNumber)> =
+					PoeModule::proofs(proof).map(|claim| (claim.owner, claim.block_number));
+				env.write(&claim.encode(), false, None)?;
+			},
+			_ => {
+				log::error!("called an unregistered chain extension function: {}", func_id);
+				return Err(DispatchError::Other("unregistered chain extension function"))
+			},
+		}
+		Ok(RetVal::Converging(0))
+	}
+}
@@ -8,7 +8,7 @@ include!(concat!(env!("OUT_DIR"), "/wasm_binary.rs"));
 
 // required by lesson 5 pallet-contracts
 use frame_support::weights::DispatchClass;
-// use pallet_contracts::weights::WeightInfo;
+use pallet_contracts::weights::WeightInfo;
 
 use pallet_grandpa::{
 	fg_primitives, AuthorityId as GrandpaId, AuthorityList as GrandpaAuthorityList,
@@ -18,7 +18,7 @@ use sp_consensus_aura::sr25519::AuthorityId as AuraId;
 use sp_core::{crypto::KeyTypeId, OpaqueMetadata};
 use sp_runtime::{
 	create_runtime_str, generic, impl_opaque_keys,
-	traits::{AccountIdLookup, BlakeTwo256, Block as BlockT, IdentifyAccount, NumberFor, Verify},
+	traits::{AccountIdConversion, AccountIdLookup, BlakeTwo256, Block as BlockT, IdentifyAccount, NumberFor, Verify},
 	transaction_validity::{TransactionSource, TransactionValidity},
 	ApplyExtrinsicResult, MultiSignature,
 };
@@ -39,7 +39,7 @@ pub use frame_support::{
 		constants::{BlockExecutionWeight, ExtrinsicBaseWeight, RocksDbWeight, WEIGHT_PER_SECOND},
 		IdentityFee, Weight,
 	},
-	StorageValue,
+	PalletId, StorageValue,
 };
 // use frame_support::pallet_prelude::ConstU32;
 pub use pallet_balances::Call as BalancesCall;
@@ -53,6 +53,9 @@ pub use sp_runtime::{Perbill, Permill};
 pub use pallet_template;
 pub use pallet_ocw;
 
+/// The chain extension of `pallet_contracts`.
+pub mod chain_extension;
+
 /// An index to a block.
 pub type BlockNumber = u32;
 
@@ -144,7 +147,7 @@ parameter_types! {
 	pub const BlockHashCount: BlockNumber = 2400;
 	/// We allow for 2 seconds of compute with a 6 second average block time.
 	pub BlockWeights: frame_system::limits::BlockWeights = frame_system::limits::BlockWeights
-		::with_sensible_defaults(2 * WEIGHT_PER_SECOND, NORMAL_DISPATCH_RATIO);
+		::with_sensible_defaults(MAXIMUM_BLOCK_WEIGHT, NORMAL_DISPATCH_RATIO);
 	pub BlockLength: frame_system::limits::BlockLength = frame_system::limits::BlockLength
 		::max_with_normal_ratio(5 * 1024 * 1024, NORMAL_DISPATCH_RATIO);
 	pub const SS58Prefix: u8 = 42;
@@ -318,34 +321,106 @@ impl pallet_template::Config for Runtime {
 	type WeightInfo = pallet_template::weights::SubstrateWeight<Runtime>;
 }
 
+parameter_types! {
+	// A claim reserves 1 milli-unit plus 10 micro-units per byte of metadata
+	pub const ClaimDeposit: Balance = 1_000_000_000;
+	pub const ClaimDepositPerByte: Balance = 10_000_000;
+	pub const MaxClaimMetadataLen: u32 = 256;
+}
+
 /// Configure the pallet-poe in pallets/poe.
 impl pallet_poe::Config for Runtime {
 	type Event = Event;
+	type Currency = Balances;
+	type ClaimDeposit = ClaimDeposit;
+	type DepositPerByte = ClaimDepositPerByte;
+	type MaxMetadataLen = MaxClaimMetadataLen;
+	type WeightInfo = pallet_poe::weights::SubstrateWeight<Runtime>;
 }
 
 parameter_types! {
 	// One can owned at most 9,999 Kitties
 	pub const MaxKittyOwned: u32 = 9999;
 	pub const MinKittyMintingPrice: u32 = 3;
+	pub const MaxAuctionDuration: BlockNumber = 7 * DAYS;
+	pub const MaxAuctionsPerBlock: u32 = 64;
+	pub const BreedingCooldown: BlockNumber = 10 * MINUTES;
+	pub const MaxKittyBatchSize: u32 = 64;
+	pub const MaxOffersPerKitty: u32 = 16;
+	pub const KittiesPalletId: PalletId = PalletId(*b"py/kitty");
+	// The protocol fees of the kitties marketplace are kept by the pallet account
+	pub KittiesTreasury: AccountId = KittiesPalletId::get().into_account();
+	pub const KittyProtocolFee: Permill = Permill::from_perthousand(25);
+	pub const MaxKittyRoyalty: Permill = Permill::from_percent(10);
+	// The dna of a new kitty is revealed a minute later
+	pub const KittyRevealDelay: BlockNumber = MINUTES;
+	pub const MaxKittyMintsPerBlock: u32 = 64;
 }
 
 /// Configure the pallet-kitties in pallets/kitties.
 impl pallet_kitties::Config for Runtime {
 	type Event = Event;
 	type Currency = Balances;
+	// Aura has no VRF output to draw from, so this runtime stays on the collective flip: the
+	// author of a reveal block can still steer the kitties revealed in it, the reveal delay only
+	// keeps creators and breeders from picking the block they draw from. An unbiasable source
+	// needs BABE, whose VRF output `pallet_babe::RandomnessFromOneEpochAgo` plugs in here.
 	type KittyRandomness = RandomnessCollectiveFlip;
 	type MaxKittyOwned = MaxKittyOwned;
 	type MinKittyMintingPrice = MinKittyMintingPrice;
+	type MaxAuctionDuration = MaxAuctionDuration;
+	type MaxAuctionsPerBlock = MaxAuctionsPerBlock;
+	type BreedingCooldown = BreedingCooldown;
+	type MaxBatchSize = MaxKittyBatchSize;
+	type MaxOffersPerKitty = MaxOffersPerKitty;
+	type Treasury = KittiesTreasury;
+	type ProtocolFee = KittyProtocolFee;
+	type MaxRoyalty = MaxKittyRoyalty;
+	type MintRevealDelay = KittyRevealDelay;
+	type MaxMintsPerBlock = MaxKittyMintsPerBlock;
 	type KittyIndex = sp_core::H256;
 	type KittyHashing = BlakeTwo256;
 	type WeightInfo = pallet_kitties::weights::SubstrateWeight<Runtime>;
 }
 
+parameter_types! {
+	pub const MaxPriceSources: u32 = 8;
+	pub const MaxPriceSourceLen: u32 = 256;
+	// A round is aggregated once 3 oracle keys reported, or all of them if there are fewer
+	pub const MinPriceReports: u32 = 3;
+	pub const MaxPriceReports: u32 = 16;
+	pub const PriceDecimals: u32 = 6;
+	pub const MaxPriceHistory: u32 = 10;
+	// A round expires 4 price jobs after its first report
+	pub const PriceRoundTimeout: BlockNumber = 20;
+	// The offchain worker jobs, each once every 5 blocks
+	pub const OcwSignedTxInterval: BlockNumber = 5;
+	pub const OcwUnsignedTxInterval: BlockNumber = 5;
+	pub const OcwUnsignedTxSignedPayloadInterval: BlockNumber = 5;
+	pub const OcwGithubInfoInterval: BlockNumber = 5;
+	pub const OcwPriceInterval: BlockNumber = 5;
+}
+
 /// Configure the pallet-ocw in pallets/ocw
 impl pallet_ocw::Config for Runtime {
 	type AuthorityId = pallet_ocw::crypto::TestAuthId;
 	type Call = Call;
 	type Event = Event;
+	type AdminOrigin = frame_system::EnsureRoot<AccountId>;
+	type UnixTime = Timestamp;
+	type MaxPriceSources = MaxPriceSources;
+	type MaxSourceLen = MaxPriceSourceLen;
+	type MinReports = MinPriceReports;
+	type MaxReports = MaxPriceReports;
+	type PriceDecimals = PriceDecimals;
+	type MaxHistory = MaxPriceHistory;
+	type RoundTimeout = PriceRoundTimeout;
+	type SignedTxInterval = OcwSignedTxInterval;
+	type UnsignedTxInterval = OcwUnsignedTxInterval;
+	type UnsignedTxSignedPayloadInterval = OcwUnsignedTxSignedPayloadInterval;
+	type GithubInfoInterval = OcwGithubInfoInterval;
+	type PriceInterval = OcwPriceInterval;
+	type WeightInfo = pallet_ocw::weights::SubstrateWeight<Runtime>;
 }
 
 impl<LocalCall> frame_system::offchain::CreateSignedTransaction<LocalCall> for Runtime
@@ -398,7 +473,6 @@ impl<C> frame_system::offchain::SendTransactionTypes<C> for Runtime
 	type Extrinsic = UncheckedExtrinsic;
 }
 
-/*
 /// We assume that ~10% of the block weight is consumed by `on_initialize` handlers.
 /// This is used to limit the maximal weight of a single extrinsic.
 const AVERAGE_ON_INITIALIZE_RATIO: Perbill = Perbill::from_percent(10);
@@ -420,12 +494,12 @@ const fn deposit(items: u32, bytes: u32) -> Balance {
 }
 
 parameter_types! {
-	pub const DepositPerItem: Balance = deposit(1, 0);
-	pub const DepositPerByte: Balance = deposit(0, 1);
+	// Reserved for every contract instantiated, as for a storage item.
+	pub const ContractDeposit: Balance = deposit(1, 0);
 
 	// The lazy deletion runs inside on_initialize.
 	pub DeletionWeightLimit: Weight = AVERAGE_ON_INITIALIZE_RATIO *
-		RuntimeBlockWeights::get().max_block;
+		BlockWeights::get().max_block;
 
 	// The weight needed for decoding the queue should be less or equal than a fifth
 	// of the overall weight dedicated to the lazy deletion.
@@ -462,19 +536,15 @@ impl pallet_contracts::Config for Runtime {
 	/// change because that would break already deployed contracts. The `Call` structure itself
 	/// is not allowed to change the indices of existing pallets, too.
 	type CallFilter = frame_support::traits::Nothing;
-	// type DepositPerItem = DepositPerItem;
-	// type DepositPerByte = DepositPerByte;
+	type ContractDeposit = ContractDeposit;
 	type WeightPrice = pallet_transaction_payment::Pallet<Self>;
 	type WeightInfo = pallet_contracts::weights::SubstrateWeight<Self>;
-	type ChainExtension = ();
+	type ChainExtension = chain_extension::KittiesExtension;
 	type Schedule = Schedule;
-    type ContractDeposit = ();
 	type CallStack = [pallet_contracts::Frame<Self>; 31];
 	type DeletionQueueDepth = DeletionQueueDepth;
 	type DeletionWeightLimit = DeletionWeightLimit;
-	// type AddressGenerator = pallet_contracts::DefaultAddressGenerator;
 }
- */
 
 // Create the runtime by composing the FRAME pallets that were previously configured.
 construct_runtime!(
@@ -498,9 +568,9 @@ construct_runtime!(
 		// Substrate lesson 2&3: Added from Substrate Kitties Chain Tutorial
 		SubstrateKitties: pallet_kitties,
 		// Substrate lesson 4: Added from Github SubstrateCourse owc-example
-		OcwDemo: pallet_ocw::{Pallet, Call, Storage, Event<T>, ValidateUnsigned},
+		OcwDemo: pallet_ocw::{Pallet, Call, Storage, Config<T>, Event<T>, ValidateUnsigned},
 		// Substrate lesson 5: Added from Github Paritytech substrate-contracts-node
-		// Contracts: pallet_contracts,
+		Contracts: pallet_contracts,
 	}
 );
 
@@ -665,7 +735,40 @@ impl_runtime_apis! {
 		}
 	}
 
-	/*
+	impl pallet_kitties_rpc_runtime_api::KittiesApi<Block, Hash, AccountId, Balance> for Runtime {
+		fn kitty(kitty_id: Hash) -> Option<pallet_kitties::KittyInfo<Hash, AccountId, Balance>> {
+			SubstrateKitties::kitty_info(kitty_id)
+		}
+
+		fn kitties_owned(owner: AccountId) -> Vec<pallet_kitties::KittyInfo<Hash, AccountId, Balance>> {
+			SubstrateKitties::owned_kitty_infos(&owner)
+		}
+
+		fn kitties_for_sale(
+			offset: u32,
+			limit: u32,
+		) -> Vec<pallet_kitties::KittyInfo<Hash, AccountId, Balance>> {
+			SubstrateKitties::kitties_for_sale(offset, limit)
+		}
+
+		fn ancestry(kitty_id: Hash, depth: u32) -> Vec<(u32, Hash)> {
+			SubstrateKitties::ancestry(kitty_id, depth)
+		}
+	}
+
+	impl pallet_ocw_runtime_api::PriceOracleApi<Block, BlockNumber, AccountId> for Runtime {
+		fn price_history() -> Vec<pallet_ocw::PriceEntry<BlockNumber, AccountId>> {
+			OcwDemo::price_history()
+		}
+
+		fn time_weighted_average_price(
+			from: BlockNumber,
+			to: BlockNumber,
+		) -> Option<pallet_ocw::Price> {
+			OcwDemo::time_weighted_average_price(from, to)
+		}
+	}
+
 	// required by lesson 5 substrate contract node
 	impl pallet_contracts_rpc_runtime_api::ContractsApi<Block, AccountId, Balance, BlockNumber, Hash>
 		for Runtime
@@ -710,7 +813,6 @@ impl_runtime_apis! {
 			Contracts::get_storage(address, key)
 		}
 	}
-	 */
 
 	#[cfg(feature = "runtime-benchmarks")]
 	impl frame_benchmarking::Benchmark<Block> for Runtime {
@@ -731,6 +833,8 @@ impl_runtime_apis! {
 			list_benchmark!(list, extra, pallet_timestamp, Timestamp);
 			list_benchmark!(list, extra, pallet_template, TemplateModule);
 			list_benchmark!(list, extra, pallet_kitties, SubstrateKitties);
+			list_benchmark!(list, extra, pallet_poe, PoeModule);
+			list_benchmark!(list, extra, pallet_ocw, OcwDemo);
 
 			let storage_info = AllPalletsWithSystem::storage_info();
 
@@ -770,6 +874,8 @@ impl_runtime_apis! {
 			add_benchmark!(params, batches, pallet_timestamp, Timestamp);
 			add_benchmark!(params, batches, pallet_template, TemplateModule);
 			add_benchmark!(params, batches, pallet_kitties, SubstrateKitties);
+			add_benchmark!(params, batches, pallet_poe, PoeModule);
+			add_benchmark!(params, batches, pallet_ocw, OcwDemo);
 
 			Ok(batches)
 		}
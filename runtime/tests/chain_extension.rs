@@ -0,0 +1,123 @@
+//! Tests of the kitties chain extension, through the contract in `fixtures/kitties_extension.wat`
+//! which passes its input through to the extension and returns its output.
+
+use codec::{Decode, Encode};
+use frame_support::{assert_ok, traits::OnInitialize};
+use node_template_runtime::{
+	chain_extension::func_id, AccountId, BlockNumber, Contracts, KittyRevealDelay, Origin, Permill,
+	PoeModule, Runtime, SubstrateKitties, System, UNIT,
+};
+use pallet_contracts_primitives::Code;
+use sp_core::{Bytes, H256};
+use sp_runtime::{AccountId32, DispatchError};
+
+const ALICE: AccountId32 = AccountId32::new([1u8; 32]);
+const BOB: AccountId32 = AccountId32::new([2u8; 32]);
+
+const GAS_LIMIT: u64 = 100_000_000_000;
+
+fn new_test_ext() -> sp_io::TestExternalities {
+	let mut t = frame_system::GenesisConfig::default().build_storage::<Runtime>().unwrap();
+	pallet_balances::GenesisConfig::<Runtime> {
+		balances: vec![(ALICE, 1_000_000 * UNIT), (BOB, 1_000_000 * UNIT)],
+	}
+	.assimilate_storage(&mut t)
+	.unwrap();
+
+	let mut ext = sp_io::TestExternalities::new(t);
+	ext.execute_with(|| System::set_block_number(1));
+	ext
+}
+
+// Instantiate the fixture contract from Alice.
+fn instantiate() -> AccountId {
+	let wasm = wat::parse_file(concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures/kitties_extension.wat"))
+		.expect("the fixture is valid WAT");
+	Contracts::bare_instantiate(ALICE, 0, GAS_LIMIT, None, Code::Upload(Bytes(wasm)), vec![], vec![], false)
+		.result
+		.expect("the fixture instantiates")
+		.account_id
+}
+
+// Call the extension function `func_id` with `input` through `contract`, returning its output.
+fn call_extension(contract: &AccountId, func_id: u32, input: impl Encode) -> Result<Vec<u8>, DispatchError> {
+	let mut data = func_id.to_le_bytes().to_vec();
+	input.encode_to(&mut data);
+	Contracts::bare_call(ALICE, contract.clone(), 0, GAS_LIMIT, None, data, false)
+		.result
+		.map(|ret| ret.data.0)
+}
+
+fn decode<T: Decode>(output: Vec<u8>) -> T {
+	T::decode(&mut &output[..]).expect("the extension output decodes")
+}
+
+// Mint a kitty for `owner`, revealed right away.
+fn create_kitty(owner: &AccountId) -> H256 {
+	assert_ok!(SubstrateKitties::create_kitty(Origin::signed(owner.clone()), Permill::zero()));
+	SubstrateKitties::on_initialize(System::block_number() + KittyRevealDelay::get());
+	*SubstrateKitties::kitties_owned(owner).last().unwrap()
+}
+
+#[test]
+fn reads_kitty_owner_and_dna() {
+	new_test_ext().execute_with(|| {
+		let contract = instantiate();
+		let kitty_id = create_kitty(&ALICE);
+		let dna = SubstrateKitties::kitties(kitty_id).unwrap().dna;
+
+		let owner = call_extension(&contract, func_id::KITTY_OWNER, kitty_id).unwrap();
+		assert_eq!(decode::<Option<AccountId>>(owner), Some(ALICE));
+		let output = call_extension(&contract, func_id::KITTY_DNA, kitty_id).unwrap();
+		assert_eq!(decode::<Option<[u8; 16]>>(output), Some(dna));
+
+		// Unknown kitties have neither owner nor dna
+		let unknown = H256::repeat_byte(7);
+		let owner = call_extension(&contract, func_id::KITTY_OWNER, unknown).unwrap();
+		assert_eq!(decode::<Option<AccountId>>(owner), None);
+		let output = call_extension(&contract, func_id::KITTY_DNA, unknown).unwrap();
+		assert_eq!(decode::<Option<[u8; 16]>>(output), None);
+	});
+}
+
+#[test]
+fn transfers_kitties_owned_by_the_contract() {
+	new_test_ext().execute_with(|| {
+		let contract = instantiate();
+		let kitty_id = create_kitty(&ALICE);
+
+		// The contract cannot give away a kitty of Alice
+		assert!(call_extension(&contract, func_id::TRANSFER_KITTY, (kitty_id, BOB)).is_err());
+		assert_eq!(SubstrateKitties::kitties(kitty_id).unwrap().owner, ALICE);
+
+		// Once the kitty is the contract's, it can
+		assert_ok!(SubstrateKitties::transfer(Origin::signed(ALICE), kitty_id, contract.clone()));
+		assert_ok!(call_extension(&contract, func_id::TRANSFER_KITTY, (kitty_id, BOB)));
+		assert_eq!(SubstrateKitties::kitties(kitty_id).unwrap().owner, BOB);
+		assert_eq!(SubstrateKitties::kitties_owned(&contract), vec![]);
+		assert_eq!(SubstrateKitties::kitties_owned(&BOB), vec![kitty_id]);
+	});
+}
+
+#[test]
+fn checks_poe_claims() {
+	new_test_ext().execute_with(|| {
+		let contract = instantiate();
+		let proof = H256::repeat_byte(1);
+
+		let claim = call_extension(&contract, func_id::POE_CLAIM, proof).unwrap();
+		assert_eq!(decode::<Option<(AccountId, BlockNumber)>>(claim), None);
+
+		assert_ok!(PoeModule::create_claim(Origin::signed(ALICE), proof, None));
+		let claim = call_extension(&contract, func_id::POE_CLAIM, proof).unwrap();
+		assert_eq!(decode::<Option<(AccountId, BlockNumber)>>(claim), Some((ALICE, 1)));
+	});
+}
+
+#[test]
+fn rejects_unregistered_functions() {
+	new_test_ext().execute_with(|| {
+		let contract = instantiate();
+		assert!(call_extension(&contract, 0, ()).is_err());
+	});
+}